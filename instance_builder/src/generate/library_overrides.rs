@@ -0,0 +1,159 @@
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use serde::Deserialize;
+use shared::{
+    files::hash_file,
+    paths::get_libraries_dir,
+    version::version_metadata::{Download, Library, LibraryDownloads, VersionMetadata},
+};
+use tokio::fs;
+
+#[derive(Deserialize)]
+pub struct LibraryOverride {
+    /// Maven coordinates, `group:artifact`, `group:artifact:version` or `group:artifact:version:classifier`.
+    #[serde(rename = "match")]
+    pub match_: String,
+
+    #[serde(default)]
+    pub exclude: bool,
+
+    pub url: Option<String>,
+    pub sha1: Option<String>,
+    pub size: Option<u64>,
+
+    /// A local jar to use instead, only possible with `replace_download_urls`.
+    pub path: Option<PathBuf>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum LibraryOverrideError {
+    #[error("Library override {0} must set exactly one of exclude, url or path")]
+    InvalidOverride(String),
+    #[error("Library override {0} sets a url without its sha1")]
+    MissingSha1(String),
+    #[error("Library override {0} sets a local path, which requires replace_download_urls")]
+    PathRequiresReplace(String),
+}
+
+impl LibraryOverride {
+    fn validate(&self, replace_download_urls: bool) -> Result<(), LibraryOverrideError> {
+        let actions = [self.exclude, self.url.is_some(), self.path.is_some()];
+        if actions.iter().filter(|x| **x).count() != 1 {
+            return Err(LibraryOverrideError::InvalidOverride(self.match_.clone()));
+        }
+        if self.url.is_some() && self.sha1.is_none() {
+            return Err(LibraryOverrideError::MissingSha1(self.match_.clone()));
+        }
+        if self.path.is_some() && !replace_download_urls {
+            return Err(LibraryOverrideError::PathRequiresReplace(
+                self.match_.clone(),
+            ));
+        }
+        Ok(())
+    }
+
+    // every part given in the pattern has to be equal, missing trailing parts match anything
+    fn matches(&self, library: &Library) -> bool {
+        let full_name = library.get_full_name();
+        let name_parts: Vec<&str> = full_name.split(':').collect();
+        let pattern_parts: Vec<&str> = self.match_.split(':').collect();
+        pattern_parts.len() >= 2
+            && pattern_parts.len() <= name_parts.len()
+            && pattern_parts
+                .iter()
+                .zip(&name_parts)
+                .all(|(pattern, part)| pattern == part)
+    }
+
+    async fn apply(&self, library: &mut Library, libraries_dir: &Path) -> anyhow::Result<()> {
+        let download = if let Some(url) = &self.url {
+            Download {
+                url: url.clone(),
+                sha1: self.sha1.clone().unwrap(),
+                size: self.size,
+            }
+        } else if let Some(path) = &self.path {
+            // placed where the sync expects the library, replace_download_urls then points to it
+            let library_path = libraries_dir.join(library.get_path_from_name());
+            if let Some(parent) = library_path.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            fs::copy(path, &library_path).await?;
+            Download {
+                url: library.get_url(),
+                sha1: hash_file(&library_path).await?,
+                size: Some(fs::metadata(&library_path).await?.len()),
+            }
+        } else {
+            return Ok(());
+        };
+
+        let classifiers = library
+            .downloads
+            .take()
+            .and_then(|downloads| downloads.classifiers);
+        library.url = None;
+        library.sha1 = None;
+        library.downloads = Some(LibraryDownloads {
+            artifact: Some(download),
+            classifiers,
+        });
+        Ok(())
+    }
+}
+
+/// Excludes or substitutes libraries of the generated metadata before it is synced.
+pub async fn apply_library_overrides(
+    version_metadata: &mut VersionMetadata,
+    overrides: &[LibraryOverride],
+    replace_download_urls: bool,
+    data_dir: &Path,
+) -> anyhow::Result<()> {
+    for library_override in overrides {
+        library_override.validate(replace_download_urls)?;
+    }
+
+    let libraries_dir = get_libraries_dir(data_dir);
+    let mut libraries = Vec::with_capacity(version_metadata.libraries.len());
+    for mut library in std::mem::take(&mut version_metadata.libraries) {
+        let Some(library_override) = overrides.iter().find(|x| x.matches(&library)) else {
+            libraries.push(library);
+            continue;
+        };
+        if library_override.exclude {
+            info!(
+                "Excluding library {} from {}",
+                library.get_full_name(),
+                version_metadata.id
+            );
+            continue;
+        }
+        info!(
+            "Overriding library {} in {}",
+            library.get_full_name(),
+            version_metadata.id
+        );
+        library_override.apply(&mut library, &libraries_dir).await?;
+        libraries.push(library);
+    }
+    version_metadata.libraries = libraries;
+    Ok(())
+}
+
+pub fn warn_unused_overrides(overrides: &[LibraryOverride], metadata: &[VersionMetadata]) {
+    for library_override in overrides {
+        let used = metadata.iter().any(|metadata| {
+            metadata
+                .libraries
+                .iter()
+                .any(|library| library_override.matches(library))
+        });
+        if !used {
+            warn!(
+                "Library override {} did not match any library",
+                library_override.match_
+            );
+        }
+    }
+}
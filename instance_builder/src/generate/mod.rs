@@ -1,3 +1,4 @@
+pub mod library_overrides;
 pub mod mapping;
 pub mod patch;
 pub mod sync;
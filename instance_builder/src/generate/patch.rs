@@ -46,6 +46,7 @@ pub async fn replace_download_urls(
                     artifact: Some(Download {
                         url: url_from_path(&library_path, data_dir, download_server_base)?,
                         sha1,
+                        size: None,
                     }),
                     classifiers: None,
                 });
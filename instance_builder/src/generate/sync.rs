@@ -3,11 +3,11 @@ use std::{
     sync::Arc,
 };
 
-use log::{debug, info};
-use rand::seq::SliceRandom as _;
+use log::{debug, info, warn};
+use rand::{rngs::StdRng, seq::SliceRandom as _, SeedableRng as _};
 use shared::{
-    adaptive_download::download_files,
-    files::{get_download_entries, CheckEntry},
+    adaptive_download::{download_files, DownloadOptions},
+    files::{get_download_entries, verify_check_entries, CheckEntry, DownloadEntry},
     paths::{get_client_jar_path, get_libraries_dir},
     progress::ProgressBar,
     version::{asset_metadata::AssetsMetadata, version_metadata::VersionMetadata},
@@ -38,6 +38,35 @@ fn get_client_download_entry(
 
 const RESOURCES_URL_BASE: &str = "https://resources.download.minecraft.net";
 
+// unset: random order to spread the load between servers,
+// "off": sorted by path, a number: shuffled with that seed
+const SHUFFLE_SEED_ENV: &str = "DOWNLOAD_SHUFFLE_SEED";
+
+fn order_download_entries(download_entries: &mut [DownloadEntry]) {
+    // sort first, get_download_entries doesn't keep the order stable
+    download_entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+    match std::env::var(SHUFFLE_SEED_ENV) {
+        Ok(value) if value == "off" => {
+            info!("Download shuffle disabled");
+        }
+        Ok(value) => match value.parse::<u64>() {
+            Ok(seed) => {
+                info!("Shuffling downloads with seed {}", seed);
+                download_entries.shuffle(&mut StdRng::seed_from_u64(seed));
+            }
+            Err(_) => {
+                warn!(
+                    "Invalid {}: {}, shuffling randomly",
+                    SHUFFLE_SEED_ENV, value
+                );
+                download_entries.shuffle(&mut rand::rngs::OsRng);
+            }
+        },
+        Err(_) => download_entries.shuffle(&mut rand::rngs::OsRng),
+    }
+}
+
 pub struct SyncResult {
     pub paths_to_copy: Vec<PathBuf>,
 }
@@ -52,7 +81,9 @@ pub async fn sync_version(
 
     if let Some(asset_index) = &version_metadata.asset_index {
         let assets_dir = get_assets_dir(output_dir);
-        let assets_metadata = AssetsMetadata::read_or_download(asset_index, &assets_dir).await?;
+        let assets_metadata =
+            AssetsMetadata::read_or_download(asset_index, &assets_dir, &DownloadOptions::default())
+                .await?;
         let asset_check_entries =
             assets_metadata.get_check_entries(&assets_dir, RESOURCES_URL_BASE)?;
 
@@ -71,9 +102,10 @@ pub async fn sync_version(
         check_entries.extend(asset_check_entries);
     }
 
-    if let Some(client_entry) = get_client_download_entry(version_metadata, output_dir) {
+    let client_entry = get_client_download_entry(version_metadata, output_dir);
+    if let Some(client_entry) = &client_entry {
         info!("Got client.jar to check");
-        check_entries.push(client_entry);
+        check_entries.push(client_entry.clone());
     }
 
     let progress_bar = Arc::new(TerminalProgressBar::new());
@@ -86,14 +118,96 @@ pub async fn sync_version(
     progress_bar.set_message("Checking files...");
     let mut download_entries = get_download_entries(check_entries, progress_bar.clone()).await?;
 
-    let rng = &mut rand::rngs::OsRng;
-    download_entries.shuffle(rng);
+    order_download_entries(&mut download_entries);
 
     progress_bar.reset();
     progress_bar.set_message("Downloading files...");
     download_files(download_entries, progress_bar).await?;
 
+    // the jar is copied into every instance, make sure a bad download doesn't end up there
+    if let Some(client_entry) = client_entry {
+        verify_check_entries(&[client_entry]).await?;
+        info!("client.jar matches the expected hash");
+    }
+
     Ok(SyncResult {
         paths_to_copy: all_paths,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use shared::files::hash_file;
+    use tokio::{
+        fs,
+        io::{AsyncReadExt as _, AsyncWriteExt as _},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    const CLIENT_JAR: &[u8] = b"client jar contents";
+
+    // serves CLIENT_JAR for any request
+    async fn serve_client_jar() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0; 4096];
+                let _ = stream.read(&mut buf).await;
+                let header = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    CLIENT_JAR.len()
+                );
+                let _ = stream.write_all(header.as_bytes()).await;
+                let _ = stream.write_all(CLIENT_JAR).await;
+            }
+        });
+        format!("http://{}/client.jar", addr)
+    }
+
+    #[tokio::test]
+    async fn test_corrupted_client_jar_redownloaded() {
+        let temp_dir = env::temp_dir().join("instance_builder_client_jar_test");
+        let _ = fs::remove_dir_all(&temp_dir).await;
+        fs::create_dir_all(&temp_dir).await.unwrap();
+
+        let expected_path = temp_dir.join("expected.jar");
+        fs::write(&expected_path, CLIENT_JAR).await.unwrap();
+        let sha1 = hash_file(&expected_path).await.unwrap();
+
+        let output_dir = temp_dir.join("output");
+        let version_metadata: VersionMetadata = serde_json::from_value(serde_json::json!({
+            "id": "test",
+            "libraries": [],
+            "mainClass": "net.minecraft.client.main.Main",
+            "downloads": {
+                "client": {
+                    "sha1": sha1,
+                    "url": serve_client_jar().await,
+                },
+            },
+        }))
+        .unwrap();
+
+        let client_jar_path = get_client_jar_path(&output_dir, "test");
+        fs::create_dir_all(client_jar_path.parent().unwrap())
+            .await
+            .unwrap();
+        fs::write(&client_jar_path, b"corrupted").await.unwrap();
+
+        let check_entry = get_client_download_entry(&version_metadata, &output_dir).unwrap();
+        assert_eq!(check_entry.remote_sha1.as_ref(), Some(&sha1));
+        assert!(verify_check_entries(&[check_entry]).await.is_err());
+        fs::write(&client_jar_path, b"corrupted").await.unwrap();
+
+        let result = sync_version(&version_metadata, &output_dir).await.unwrap();
+        assert!(result.paths_to_copy.contains(&client_jar_path));
+        assert_eq!(fs::read(&client_jar_path).await.unwrap(), CLIENT_JAR);
+
+        fs::remove_dir_all(&temp_dir).await.unwrap();
+    }
+}
@@ -8,6 +8,7 @@ use std::{
 use tokio::fs;
 
 use shared::{
+    command::exec_command,
     files::sync_mapping,
     generate::{
         extra::{ExtraMetadataGenerator, IncludeConfig},
@@ -31,9 +32,14 @@ use shared::{
 };
 
 use crate::{
-    generate::{mapping::get_mapping, patch::replace_download_urls, sync::sync_version},
+    generate::{
+        library_overrides::{apply_library_overrides, warn_unused_overrides, LibraryOverride},
+        mapping::get_mapping,
+        patch::replace_download_urls,
+        sync::sync_version,
+    },
     progress::TerminalProgressBar,
-    utils::{exec_string_command, get_assets_dir, get_replaced_metadata_dir},
+    utils::{get_assets_dir, get_replaced_metadata_dir},
 };
 
 fn vanilla() -> String {
@@ -60,8 +66,13 @@ pub struct Version {
 
     pub auth_backend: Option<AuthBackend>,
 
+    pub recommended_xmx: Option<String>,
+
     pub exec_before: Option<String>,
     pub exec_after: Option<String>,
+
+    #[serde(default)]
+    pub library_overrides: Vec<LibraryOverride>,
 }
 
 #[derive(Deserialize)]
@@ -90,7 +101,7 @@ impl VersionsSpec {
 
     pub async fn generate(self, output_dir: &Path, work_dir: &Path) -> anyhow::Result<()> {
         if let Some(command) = &self.exec_before_all {
-            exec_string_command(command).await?;
+            exec_command(command, None, &HashMap::new()).await?;
         }
 
         info!("Fetching version manifest");
@@ -102,11 +113,11 @@ impl VersionsSpec {
 
         for version in self.versions {
             if let Some(command) = &version.exec_before {
-                exec_string_command(command).await?;
+                exec_command(command, None, &HashMap::new()).await?;
             }
 
             let vanilla_version_info =
-                get_vanilla_version_info(&vanilla_manifest, &version.minecraft_version)?;
+                get_vanilla_version_info(&vanilla_manifest, &version.minecraft_version, true)?;
 
             let progress_bar = Arc::new(TerminalProgressBar::new());
 
@@ -153,6 +164,22 @@ impl VersionsSpec {
             let mut workdir_paths_to_copy = vec![];
 
             let mut result = generator.generate(work_dir).await?;
+            if !version.library_overrides.is_empty() {
+                warn_unused_overrides(&version.library_overrides, &result.metadata);
+                for metadata in result.metadata.iter_mut() {
+                    apply_library_overrides(
+                        metadata,
+                        &version.library_overrides,
+                        self.replace_download_urls,
+                        work_dir,
+                    )
+                    .await?;
+                    if !self.replace_download_urls {
+                        // the generated metadata file is copied as is in this mode
+                        metadata.save(&get_versions_dir(work_dir)).await?;
+                    }
+                }
+            }
             if self.replace_download_urls {
                 let versions_dir = get_versions_dir(output_dir);
                 let replaced_metadata_dir = get_replaced_metadata_dir(work_dir);
@@ -217,6 +244,7 @@ impl VersionsSpec {
                 include_config,
                 result.extra_libs_paths,
                 version.auth_backend,
+                version.recommended_xmx,
             );
             let extra_generator_result = extra_generator.generate(work_dir).await?;
             mapping.extend(extra_generator_result.include_mapping.into_iter().map(
@@ -242,7 +270,7 @@ impl VersionsSpec {
             mapping.extend(get_mapping(output_dir, work_dir, &workdir_paths_to_copy)?);
 
             if let Some(command) = &version.exec_after {
-                exec_string_command(command).await?;
+                exec_command(command, None, &HashMap::new()).await?;
             }
 
             info!("Finished generating version {}", &version.name);
@@ -256,7 +284,7 @@ impl VersionsSpec {
         version_manifest.save_to_file(&manifest_path).await?;
 
         if let Some(command) = &self.exec_after_all {
-            exec_string_command(command).await?;
+            exec_command(command, None, &HashMap::new()).await?;
         }
         Ok(())
     }
@@ -4,7 +4,15 @@ use std::fs;
 fn main() {
     let build_envs = ["LAUNCHER_NAME", "VERSION_MANIFEST_URL"];
 
-    let optional_envs = ["AUTO_UPDATE_BASE", "VERSION"];
+    let optional_envs = [
+        "AUTO_UPDATE_BASE",
+        "VERSION",
+        "DISCORD_CLIENT_ID",
+        "DOWNLOAD_MIRRORS",
+        "SERVER_HEADERS",
+        "JAVA_METADATA_URL",
+        "VANILLA_MANIFEST_URL",
+    ];
 
     let out_dir = env::var("OUT_DIR").unwrap();
     let dest_path = format!("{}/generated.rs", out_dir);
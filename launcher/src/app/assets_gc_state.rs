@@ -0,0 +1,196 @@
+use std::sync::Arc;
+
+use tokio::runtime::Runtime;
+
+use crate::config::runtime_config::Config;
+use crate::lang::LangMessage;
+use crate::version::assets_gc::{self, UnreferencedAssets};
+use crate::version::instance_storage::InstanceStorage;
+
+use super::background_task::{BackgroundTask, BackgroundTaskResult};
+use super::colors;
+use super::error_label;
+
+enum AssetsGcStatus {
+    Found(Arc<UnreferencedAssets>),
+    Deleted { count: usize, size: u64 },
+    Error(String),
+}
+
+enum AssetsGcTask {
+    Find(BackgroundTask<anyhow::Result<UnreferencedAssets>>),
+    Delete(BackgroundTask<anyhow::Result<UnreferencedAssets>>),
+}
+
+pub struct AssetsGcState {
+    task: Option<AssetsGcTask>,
+    status: Option<AssetsGcStatus>,
+}
+
+impl AssetsGcState {
+    pub fn new() -> Self {
+        AssetsGcState {
+            task: None,
+            status: None,
+        }
+    }
+
+    fn start_find(&mut self, ctx: &egui::Context, runtime: &Runtime, config: &Config) {
+        let version_infos = runtime
+            .block_on(InstanceStorage::load(config))
+            .get_local_version_infos();
+        let launcher_dir = config.get_launcher_dir();
+        let assets_dir = config.get_assets_dir();
+        let custom_assets_dir = config.assets_dir.is_some();
+        let fut = async move {
+            assets_gc::find_unreferenced_assets(
+                &version_infos,
+                &launcher_dir,
+                &assets_dir,
+                custom_assets_dir,
+            )
+            .await
+        };
+        let ctx = ctx.clone();
+        self.status = None;
+        self.task = Some(AssetsGcTask::Find(BackgroundTask::with_callback(
+            fut,
+            runtime,
+            Box::new(move || ctx.request_repaint()),
+        )));
+    }
+
+    fn start_delete(
+        &mut self,
+        ctx: &egui::Context,
+        runtime: &Runtime,
+        config: &Config,
+        assets: Arc<UnreferencedAssets>,
+    ) {
+        let version_infos = runtime
+            .block_on(InstanceStorage::load(config))
+            .get_local_version_infos();
+        let launcher_dir = config.get_launcher_dir();
+        let assets_dir = config.get_assets_dir();
+        let fut = async move {
+            assets_gc::delete_assets(&assets, &version_infos, &launcher_dir, &assets_dir).await
+        };
+        let ctx = ctx.clone();
+        self.status = None;
+        self.task = Some(AssetsGcTask::Delete(BackgroundTask::with_callback(
+            fut,
+            runtime,
+            Box::new(move || ctx.request_repaint()),
+        )));
+    }
+
+    fn update(&mut self) {
+        self.status = match self.task.take() {
+            Some(AssetsGcTask::Find(task)) if task.has_result() => match task.take_result() {
+                BackgroundTaskResult::Finished(Ok(assets)) => {
+                    Some(AssetsGcStatus::Found(Arc::new(assets)))
+                }
+                BackgroundTaskResult::Finished(Err(e)) => {
+                    Some(AssetsGcStatus::Error(format!("{:?}", e)))
+                }
+                BackgroundTaskResult::Cancelled => None,
+            },
+            Some(AssetsGcTask::Delete(task)) if task.has_result() => match task.take_result() {
+                BackgroundTaskResult::Finished(Ok(assets)) => Some(AssetsGcStatus::Deleted {
+                    count: assets.paths.len(),
+                    size: assets.size,
+                }),
+                BackgroundTaskResult::Finished(Err(e)) => {
+                    Some(AssetsGcStatus::Error(format!("{:?}", e)))
+                }
+                BackgroundTaskResult::Cancelled => None,
+            },
+            task => {
+                self.task = task;
+                return;
+            }
+        };
+    }
+
+    pub fn render_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        runtime: &Runtime,
+        config: &Config,
+        sync_running: bool,
+    ) {
+        self.update();
+
+        let lang = config.lang;
+        let dark_mode = ui.style().visuals.dark_mode;
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    self.task.is_none() && !sync_running,
+                    egui::Button::new(LangMessage::FindUnusedAssets.to_string(lang)),
+                )
+                .clicked()
+            {
+                self.start_find(ui.ctx(), runtime, config);
+            }
+            if self.task.is_some() {
+                ui.spinner();
+            }
+        });
+
+        let mut to_delete = None;
+        match &self.status {
+            Some(AssetsGcStatus::Found(assets)) if assets.paths.is_empty() => {
+                ui.label(
+                    egui::RichText::new(LangMessage::NoUnusedAssets.to_string(lang))
+                        .color(colors::ok(dark_mode)),
+                );
+            }
+            Some(AssetsGcStatus::Found(assets)) => {
+                ui.label(
+                    LangMessage::UnusedAssetsFound {
+                        count: assets.paths.len(),
+                        size: assets.size,
+                    }
+                    .to_string(lang),
+                );
+                if ui
+                    .add_enabled(
+                        !sync_running,
+                        egui::Button::new(LangMessage::DeleteUnusedAssets.to_string(lang)),
+                    )
+                    .clicked()
+                {
+                    to_delete = Some(assets.clone());
+                }
+            }
+            Some(AssetsGcStatus::Deleted { count, size }) => {
+                ui.label(
+                    egui::RichText::new(
+                        LangMessage::UnusedAssetsDeleted {
+                            count: *count,
+                            size: *size,
+                        }
+                        .to_string(lang),
+                    )
+                    .color(colors::ok(dark_mode)),
+                );
+            }
+            Some(AssetsGcStatus::Error(e)) => {
+                error_label::render(
+                    ui,
+                    egui::RichText::new(LangMessage::AssetsCleanupError.to_string(lang))
+                        .color(colors::error(dark_mode)),
+                    Some(e),
+                    lang,
+                );
+            }
+            None => {}
+        }
+
+        if let Some(assets) = to_delete {
+            self.start_delete(ui.ctx(), runtime, config, assets);
+        }
+    }
+}
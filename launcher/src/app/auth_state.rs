@@ -16,10 +16,12 @@ use tokio::runtime::Runtime;
 
 use crate::auth::auth_flow::perform_auth;
 use crate::auth::auth_flow::AuthMessageProvider;
+use crate::auth::auth_storage::AuthDataMap;
 use crate::auth::auth_storage::AuthDataSource;
 use crate::auth::auth_storage::AuthStorage;
 use crate::auth::auth_storage::StorageEntry;
 use crate::auth::base::get_auth_provider;
+use crate::auth::microsoft;
 use crate::auth::user_info::AuthData;
 use crate::config::runtime_config::AuthProfile;
 use crate::config::runtime_config::Config;
@@ -28,12 +30,14 @@ use crate::utils;
 
 use super::background_task::{BackgroundTask, BackgroundTaskResult};
 use super::colors;
+use super::error_label;
+use super::status_bar::{Severity, StatusMessage};
 
 #[derive(Clone, PartialEq)]
 enum AuthStatus {
     NotAuthorized,
     Authorized,
-    AuthorizeError,
+    AuthorizeError(String),
     AuthorizeErrorOffline,
     AuthorizeErrorTimeout,
 }
@@ -83,7 +87,7 @@ fn authenticate(
                         AuthStatus::AuthorizeErrorTimeout
                     } else {
                         error!("Auth error:\n{:?}", e);
-                        AuthStatus::AuthorizeError
+                        AuthStatus::AuthorizeError(format!("{:?}", e))
                     },
                     auth_data: None,
                 }
@@ -100,6 +104,66 @@ fn authenticate(
     )
 }
 
+enum SkinUploadStatus {
+    Uploaded,
+    Error(String),
+}
+
+fn upload_skin(
+    runtime: &Runtime,
+    token: String,
+    skin: Vec<u8>,
+    slim: bool,
+    ctx: &egui::Context,
+) -> BackgroundTask<SkinUploadStatus> {
+    let ctx = ctx.clone();
+    let fut = async move {
+        match microsoft::upload_skin(&token, skin, slim).await {
+            Ok(()) => SkinUploadStatus::Uploaded,
+            Err(e) => {
+                error!("Skin upload error:\n{:?}", e);
+                SkinUploadStatus::Error(format!("{:#}", e))
+            }
+        }
+    };
+
+    BackgroundTask::with_callback(
+        fut,
+        runtime,
+        Box::new(move || {
+            ctx.request_repaint();
+        }),
+    )
+}
+
+fn get_qr_png(url: &str) -> anyhow::Result<Vec<u8>> {
+    let code = QrCode::new(url)?;
+    let image = code.render::<Luma<u8>>().build();
+
+    let mut png_bytes: Vec<u8> = Vec::new();
+    let mut cursor = Cursor::new(&mut png_bytes);
+    image::DynamicImage::ImageLuma8(image).write_to(&mut cursor, image::ImageFormat::Png)?;
+    Ok(png_bytes)
+}
+
+fn render_qr_code(ui: &mut egui::Ui, url: &str) {
+    // every link gets its own uri, otherwise egui keeps showing the cached image of the previous one
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    let uri = format!("bytes://auth_qr_{:X}.png", hasher.finish());
+
+    if ui.ctx().try_load_bytes(&uri).is_err() {
+        match get_qr_png(url) {
+            Ok(png_bytes) => ui.ctx().include_bytes(uri.clone(), png_bytes),
+            Err(e) => {
+                error!("Error generating auth QR code:\n{:?}", e);
+                return;
+            }
+        }
+    }
+    ui.add(egui::Image::new(uri));
+}
+
 #[derive(Clone, Copy, PartialEq)]
 enum NewAccountType {
     Microsoft,
@@ -126,6 +190,12 @@ pub struct AuthState {
     offline_nickname: String,
 
     last_auth_profile: Option<AuthProfile>,
+    opened_auth_url: Option<String>,
+
+    show_skin_window: bool,
+    skin_slim: bool,
+    skin_task: Option<BackgroundTask<SkinUploadStatus>>,
+    skin_status: Option<SkinUploadStatus>,
 }
 
 impl AuthState {
@@ -148,6 +218,12 @@ impl AuthState {
             offline_nickname: String::new(),
 
             last_auth_profile: None,
+            opened_auth_url: None,
+
+            show_skin_window: false,
+            skin_slim: false,
+            skin_task: None,
+            skin_status: None,
         }
     }
 
@@ -192,28 +268,45 @@ impl AuthState {
             let lang = config.lang;
             let ctx = ui.ctx();
 
+            let (url, code) = match &message {
+                LangMessage::AuthMessage { url } => (url.clone(), None),
+                LangMessage::DeviceAuthMessage { url, code } => (url.clone(), Some(code.clone())),
+                _ => unreachable!(),
+            };
+            if config.auto_open_auth_urls && self.opened_auth_url.as_ref() != Some(&url) {
+                if let Err(e) = open::that(&url) {
+                    error!("Error opening auth url:\n{:?}", e);
+                }
+                self.opened_auth_url = Some(url.clone());
+            }
+
             egui::Window::new(LangMessage::Authorization.to_string(lang)).show(ctx, |ui| {
-                ui.label(message.to_string(lang));
-                let url = match message {
-                    LangMessage::AuthMessage { url } => Some(url),
-                    LangMessage::DeviceAuthMessage { url, .. } => Some(url),
-                    _ => None,
+                if config.auto_open_auth_urls {
+                    ui.label(message.to_string(lang));
+                } else {
+                    ui.label(LangMessage::OpenAuthLink.to_string(lang));
+                }
+                if let Some(code) = &code {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(code).monospace().size(18.0));
+                        if ui.button(LangMessage::CopyCode.to_string(lang)).clicked() {
+                            ctx.copy_text(code.clone());
+                        }
+                    });
                 }
-                .unwrap();
 
                 ui.hyperlink(&url);
-                let code = QrCode::new(url).unwrap();
-                let image = code.render::<Luma<u8>>().build();
-
-                let mut png_bytes: Vec<u8> = Vec::new();
-                let mut cursor = Cursor::new(&mut png_bytes);
-                image::DynamicImage::ImageLuma8(image)
-                    .write_to(&mut cursor, image::ImageFormat::Png)
-                    .unwrap();
-
-                let uri = "bytes://auth_qr.png";
-                ctx.include_bytes(uri, png_bytes.clone());
-                ui.add(egui::Image::from_bytes(uri.to_string(), png_bytes));
+                ui.horizontal(|ui| {
+                    if ui.button(LangMessage::OpenLink.to_string(lang)).clicked() {
+                        if let Err(e) = open::that(&url) {
+                            error!("Error opening auth url:\n{:?}", e);
+                        }
+                    }
+                    if ui.button(LangMessage::CopyLink.to_string(lang)).clicked() {
+                        ctx.copy_text(url.clone());
+                    }
+                });
+                render_qr_code(ui, &url);
 
                 if ui.button(LangMessage::Cancel.to_string(lang)).clicked() {
                     self.auth_status = AuthStatus::NotAuthorized;
@@ -261,6 +354,103 @@ impl AuthState {
         }
     }
 
+    fn get_skin_token(&self, config: &Config) -> Option<String> {
+        let profile = config.get_selected_auth_profile()?;
+        if AuthBackend::from_id(&profile.auth_backend_id) != AuthBackend::Microsoft
+            || self.auth_status != AuthStatus::Authorized
+        {
+            return None;
+        }
+        self.get_auth_data(config).map(|data| data.access_token)
+    }
+
+    fn render_skin_window(&mut self, ctx: &egui::Context, config: &Config, runtime: &Runtime) {
+        if let Some(task) = self.skin_task.as_ref() {
+            if task.has_result() {
+                let task = self.skin_task.take().unwrap();
+                if let BackgroundTaskResult::Finished(status) = task.take_result() {
+                    self.skin_status = Some(status);
+                }
+            }
+        }
+
+        let lang = config.lang;
+        let token = self.get_skin_token(config);
+        let dark_mode = ctx.style().visuals.dark_mode;
+        let mut show_skin_window = self.show_skin_window && token.is_some();
+        Window::new(LangMessage::ChangeSkin.to_string(lang))
+            .open(&mut show_skin_window)
+            .show(ctx, |ui| {
+                if ui
+                    .button(LangMessage::OpenSkinPage.to_string(lang))
+                    .clicked()
+                {
+                    if let Err(e) = open::that(microsoft::SKIN_PAGE_URL) {
+                        error!("Error opening skin page:\n{:?}", e);
+                    }
+                }
+
+                ui.separator();
+                ui.checkbox(
+                    &mut self.skin_slim,
+                    LangMessage::SlimSkinModel.to_string(lang),
+                );
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            self.skin_task.is_none(),
+                            egui::Button::new(LangMessage::UploadSkin.to_string(lang)),
+                        )
+                        .clicked()
+                    {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("PNG", &["png"])
+                            .pick_file()
+                        {
+                            match std::fs::read(&path) {
+                                Ok(skin) => {
+                                    self.skin_status = None;
+                                    self.skin_task = Some(upload_skin(
+                                        runtime,
+                                        token.clone().unwrap(),
+                                        skin,
+                                        self.skin_slim,
+                                        ui.ctx(),
+                                    ));
+                                }
+                                Err(e) => {
+                                    self.skin_status = Some(SkinUploadStatus::Error(e.to_string()));
+                                }
+                            }
+                        }
+                    }
+                    if self.skin_task.is_some() {
+                        ui.spinner();
+                    }
+                });
+
+                match &self.skin_status {
+                    Some(SkinUploadStatus::Uploaded) => {
+                        ui.label(
+                            RichText::new(LangMessage::SkinUploaded.to_string(lang))
+                                .color(colors::ok(dark_mode)),
+                        );
+                    }
+                    Some(SkinUploadStatus::Error(e)) => {
+                        error_label::render(
+                            ui,
+                            RichText::new(LangMessage::SkinUploadError.to_string(lang))
+                                .color(colors::error(dark_mode)),
+                            Some(e),
+                            lang,
+                        );
+                    }
+                    None => {}
+                }
+            });
+        self.show_skin_window = show_skin_window;
+    }
+
     fn get_type_display_name(lang: Lang, new_account_type: NewAccountType) -> String {
         match new_account_type {
             NewAccountType::Microsoft => "Microsoft".to_string(),
@@ -376,7 +566,11 @@ impl AuthState {
 
         let storage_entry = self.get_selected_storage_entry(config);
         if let Some(storage_entry) = &storage_entry {
-            if storage_entry.source == AuthDataSource::Persistent && self.auth_task.is_none() {
+            if storage_entry.source == AuthDataSource::Persistent && config.work_offline {
+                // use the cached token as is, it gets refreshed once back online
+                self.auth_status = AuthStatus::AuthorizeErrorOffline;
+            } else if storage_entry.source == AuthDataSource::Persistent && self.auth_task.is_none()
+            {
                 self.auth_message_provider = Arc::new(AuthMessageProvider::new(ctx));
                 self.auth_task = Some(authenticate(
                     runtime,
@@ -415,6 +609,15 @@ impl AuthState {
             }
         }
 
+        if self.get_skin_token(config).is_some()
+            && ui
+                .button(LangMessage::ChangeSkin.to_string(config.lang))
+                .clicked()
+        {
+            self.show_skin_window = true;
+            self.skin_status = None;
+        }
+
         if ui.button("+").clicked() {
             if let Some(instance_auth_backend) = instance_auth_backend {
                 let ctx = ui.ctx();
@@ -456,6 +659,21 @@ impl AuthState {
         format!("{} ({} #{})", username, provider_name, &hex[0..4])
     }
 
+    pub fn get_status_message(&self) -> Option<StatusMessage> {
+        Some(match &self.auth_status {
+            AuthStatus::AuthorizeError(e) => {
+                StatusMessage::new(Severity::Error, LangMessage::UnknownAuthError, Some(e))
+            }
+            AuthStatus::AuthorizeErrorOffline => {
+                StatusMessage::new(Severity::Offline, LangMessage::Offline, None)
+            }
+            AuthStatus::AuthorizeErrorTimeout => {
+                StatusMessage::new(Severity::Timeout, LangMessage::AuthTimeout, None)
+            }
+            _ => return None,
+        })
+    }
+
     fn get_combobox_text(
         nickname: &str,
         status: &AuthStatus,
@@ -470,7 +688,7 @@ impl AuthState {
                 LangMessage::Authorizing.to_string(lang)
             ))
             .color(colors::in_progress(dark_mode)),
-            AuthStatus::AuthorizeError => RichText::new(format!(
+            AuthStatus::AuthorizeError(_) => RichText::new(format!(
                 "{} ({})",
                 nickname,
                 LangMessage::UnknownAuthError.to_string(lang)
@@ -534,6 +752,9 @@ impl AuthState {
                 self.render_buttons(ui, config, runtime, Some(instance_auth_backend));
 
                 let mut selected_username = auth_profile.as_ref().map(|x| x.username.to_string());
+                if let AuthStatus::AuthorizeError(e) = &self.auth_status {
+                    error_label::render_copy_button(ui, e, lang);
+                }
                 ComboBox::from_id_salt("select_account")
                     .selected_text(match &selected_username {
                         Some(username) => {
@@ -644,6 +865,15 @@ impl AuthState {
 
         self.render_new_account_window(ui, ctx, runtime, lang);
         self.render_auth_window(config, runtime, ui);
+        self.render_skin_window(ctx, config, runtime);
+    }
+
+    pub fn export_auth_data(&self) -> AuthDataMap {
+        self.auth_storage.get_persistent_data()
+    }
+
+    pub fn import_auth_data(&mut self, config: &Config, data: AuthDataMap) {
+        self.auth_storage.import(config, data);
     }
 
     pub fn get_auth_data(&self, config: &Config) -> Option<AuthData> {
@@ -672,7 +902,7 @@ impl AuthState {
             self.auth_status,
             AuthStatus::AuthorizeErrorOffline
                 | AuthStatus::AuthorizeErrorTimeout
-                | AuthStatus::AuthorizeError
+                | AuthStatus::AuthorizeError(_)
         )
     }
 
@@ -0,0 +1,91 @@
+use tokio::runtime::Runtime;
+
+use crate::config::runtime_config::Config;
+use crate::lang::LangMessage;
+use crate::version::caches;
+
+use super::background_task::{BackgroundTask, BackgroundTaskResult};
+use super::colors;
+use super::error_label;
+
+pub struct ClearCachesState {
+    task: Option<BackgroundTask<anyhow::Result<u64>>>,
+    result: Option<Result<u64, String>>,
+}
+
+impl ClearCachesState {
+    pub fn new() -> Self {
+        ClearCachesState {
+            task: None,
+            result: None,
+        }
+    }
+
+    fn start(&mut self, ctx: &egui::Context, runtime: &Runtime, config: &Config) {
+        let launcher_dir = config.get_launcher_dir();
+        let fut = async move {
+            Ok(tokio::task::spawn_blocking(move || caches::clear_caches(&launcher_dir)).await?)
+        };
+        let ctx = ctx.clone();
+        self.result = None;
+        self.task = Some(BackgroundTask::with_callback(
+            fut,
+            runtime,
+            Box::new(move || ctx.request_repaint()),
+        ));
+    }
+
+    fn update(&mut self) {
+        if !self.task.as_ref().is_some_and(|task| task.has_result()) {
+            return;
+        }
+        self.result = match self.task.take().unwrap().take_result() {
+            BackgroundTaskResult::Finished(Ok(freed)) => Some(Ok(freed)),
+            BackgroundTaskResult::Finished(Err(e)) => Some(Err(format!("{:?}", e))),
+            BackgroundTaskResult::Cancelled => None,
+        };
+    }
+
+    pub fn render_ui(&mut self, ui: &mut egui::Ui, runtime: &Runtime, config: &Config) {
+        self.update();
+
+        let lang = config.lang;
+        let dark_mode = ui.style().visuals.dark_mode;
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    self.task.is_none(),
+                    egui::Button::new(LangMessage::ClearCaches.to_string(lang)),
+                )
+                .clicked()
+            {
+                self.start(ui.ctx(), runtime, config);
+            }
+            if self.task.is_some() {
+                ui.spinner();
+            }
+        });
+
+        match &self.result {
+            Some(Ok(freed)) => {
+                ui.label(
+                    egui::RichText::new(
+                        LangMessage::CachesCleared { size: *freed }.to_string(lang),
+                    )
+                    .color(colors::ok(dark_mode)),
+                );
+            }
+            Some(Err(e)) => {
+                error_label::render(
+                    ui,
+                    egui::RichText::new(LangMessage::ClearCachesError.to_string(lang))
+                        .color(colors::error(dark_mode)),
+                    Some(e),
+                    lang,
+                );
+            }
+            None => {}
+        }
+    }
+}
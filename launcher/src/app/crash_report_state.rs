@@ -0,0 +1,47 @@
+use crate::crash_report;
+use crate::lang::{Lang, LangMessage};
+
+pub struct CrashReportState {
+    report: Option<String>,
+}
+
+impl CrashReportState {
+    pub fn new() -> Self {
+        CrashReportState {
+            report: crash_report::take_crash_report(),
+        }
+    }
+
+    pub fn render_window(&mut self, ctx: &egui::Context, lang: Lang) {
+        let Some(report) = &self.report else {
+            return;
+        };
+
+        let mut open = true;
+        let mut dismissed = false;
+        egui::Window::new(LangMessage::LauncherCrashed.to_string(lang))
+            .open(&mut open)
+            .default_width(500.0)
+            .show(ctx, |ui| {
+                ui.label(LangMessage::LauncherCrashedDescription.to_string(lang));
+                egui::ScrollArea::vertical()
+                    .max_height(250.0)
+                    .show(ui, |ui| {
+                        ui.add(egui::Label::new(egui::RichText::new(report).monospace()).wrap());
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button(LangMessage::CopyAll.to_string(lang)).clicked() {
+                        ui.ctx().copy_text(report.clone());
+                    }
+                    if ui.button(LangMessage::Close.to_string(lang)).clicked() {
+                        dismissed = true;
+                    }
+                });
+            });
+
+        if !open || dismissed {
+            self.report = None;
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use shared::files;
+use shared::http;
+use shared::java::{self, JavaPackageType};
+use shared::paths::get_java_dir;
+use shared::version::version_manifest::VersionManifest;
+use tokio::runtime::Runtime;
+
+use crate::config::build_config;
+use crate::config::runtime_config::Config;
+use crate::lang::{Lang, LangMessage};
+use crate::launcher::update;
+use crate::utils;
+
+use super::background_task::{BackgroundTask, BackgroundTaskResult};
+use super::colors;
+
+#[derive(Clone, Copy)]
+enum CheckKind {
+    ServerBase,
+    VersionManifest,
+    AutoUpdate,
+    JavaApi,
+    Java,
+    DataDir,
+}
+
+impl CheckKind {
+    fn message(self) -> LangMessage {
+        match self {
+            CheckKind::ServerBase => LangMessage::DiagnosticsServer,
+            CheckKind::VersionManifest => LangMessage::DiagnosticsVersionManifest,
+            CheckKind::AutoUpdate => LangMessage::DiagnosticsAutoUpdate,
+            CheckKind::JavaApi => LangMessage::DiagnosticsJavaApi,
+            CheckKind::Java => LangMessage::DiagnosticsJava,
+            CheckKind::DataDir => LangMessage::DiagnosticsDataDir,
+        }
+    }
+}
+
+enum CheckOutcome {
+    Passed(String),
+    Failed(String),
+    Skipped,
+}
+
+struct CheckResult {
+    kind: CheckKind,
+    outcome: CheckOutcome,
+}
+
+fn outcome_from_result(result: anyhow::Result<String>) -> CheckOutcome {
+    match result {
+        Ok(details) => CheckOutcome::Passed(details),
+        Err(e) => CheckOutcome::Failed(format!("{:#}", e)),
+    }
+}
+
+async fn check_server_base() -> anyhow::Result<String> {
+    let manifest_url = reqwest::Url::parse(&build_config::get_version_manifest_url())?;
+    let base = manifest_url.origin().ascii_serialization();
+    let start = Instant::now();
+    // any response means the server is reachable
+    let response = http::client().get(&base).send().await?;
+    Ok(format!(
+        "{} ({}, {} ms)",
+        base,
+        response.status(),
+        start.elapsed().as_millis()
+    ))
+}
+
+async fn check_version_manifest() -> anyhow::Result<String> {
+    let manifest = VersionManifest::fetch(&build_config::get_version_manifest_url()).await?;
+    Ok(format!("{} versions", manifest.versions.len()))
+}
+
+async fn check_auto_update() -> Option<anyhow::Result<String>> {
+    build_config::get_auto_update_base()?;
+    let current_version = build_config::get_version()?;
+    Some(
+        update::need_update()
+            .await
+            .map(|new_version| match new_version {
+                Some(new_version) => format!("{} -> {}", current_version, new_version),
+                None => current_version,
+            }),
+    )
+}
+
+async fn check_java_api(
+    java_version: &str,
+    package_type: JavaPackageType,
+) -> anyhow::Result<String> {
+    let archive_type = if cfg!(windows) { "zip" } else { "tar.gz" };
+    let packages = java::fetch_java_packages(java_version, archive_type, package_type).await?;
+    Ok(format!(
+        "{} packages for Java {}",
+        packages.len(),
+        java_version
+    ))
+}
+
+#[derive(thiserror::Error, Debug)]
+enum DiagnosticsError {
+    #[error("No Java {0} installation found")]
+    JavaNotFound(String),
+    #[error("{0} is not writable")]
+    NotWritable(PathBuf),
+}
+
+pub(super) async fn check_java(
+    java_version: &str,
+    package_type: JavaPackageType,
+    data_dir: &Path,
+) -> anyhow::Result<String> {
+    let installation = java::get_java(java_version, package_type, &get_java_dir(data_dir))
+        .await
+        .ok_or_else(|| DiagnosticsError::JavaNotFound(java_version.to_string()))?;
+    Ok(format!(
+        "{} ({})",
+        installation.version,
+        installation.path.display()
+    ))
+}
+
+fn check_data_dir(launcher_dir: &Path) -> anyhow::Result<String> {
+    if !utils::is_dir_writable(launcher_dir) {
+        return Err(DiagnosticsError::NotWritable(launcher_dir.to_path_buf()).into());
+    }
+    let free_space = files::get_free_space(launcher_dir)?;
+    Ok(format!(
+        "{}, {:.1} GB free",
+        launcher_dir.display(),
+        free_space as f64 / (1024.0 * 1024.0 * 1024.0)
+    ))
+}
+
+async fn run_diagnostics(
+    java_version: Option<String>,
+    package_type: JavaPackageType,
+    launcher_dir: PathBuf,
+) -> Vec<CheckResult> {
+    let data_dir = utils::get_data_dir();
+    // the Azul API is queried for Java 17 when no instance is selected
+    let api_java_version = java_version.clone().unwrap_or("17".to_string());
+
+    let (server_base, version_manifest, auto_update, java_api, java) = tokio::join!(
+        check_server_base(),
+        check_version_manifest(),
+        check_auto_update(),
+        check_java_api(&api_java_version, package_type),
+        async {
+            match &java_version {
+                Some(java_version) => Some(check_java(java_version, package_type, &data_dir).await),
+                None => None,
+            }
+        },
+    );
+
+    vec![
+        CheckResult {
+            kind: CheckKind::ServerBase,
+            outcome: outcome_from_result(server_base),
+        },
+        CheckResult {
+            kind: CheckKind::VersionManifest,
+            outcome: outcome_from_result(version_manifest),
+        },
+        CheckResult {
+            kind: CheckKind::AutoUpdate,
+            outcome: auto_update.map_or(CheckOutcome::Skipped, outcome_from_result),
+        },
+        CheckResult {
+            kind: CheckKind::JavaApi,
+            outcome: outcome_from_result(java_api),
+        },
+        CheckResult {
+            kind: CheckKind::Java,
+            outcome: java.map_or(CheckOutcome::Skipped, outcome_from_result),
+        },
+        CheckResult {
+            kind: CheckKind::DataDir,
+            outcome: outcome_from_result(check_data_dir(&launcher_dir)),
+        },
+    ]
+}
+
+pub(super) fn get_launcher_info() -> String {
+    format!(
+        "{} {} ({} {})",
+        build_config::get_launcher_name(),
+        build_config::get_version().unwrap_or("dev".to_string()),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+    )
+}
+
+pub struct DiagnosticsState {
+    window_open: bool,
+    task: Option<BackgroundTask<Vec<CheckResult>>>,
+    results: Option<Vec<CheckResult>>,
+}
+
+impl DiagnosticsState {
+    pub fn new() -> Self {
+        DiagnosticsState {
+            window_open: false,
+            task: None,
+            results: None,
+        }
+    }
+
+    fn start(
+        &mut self,
+        ctx: &egui::Context,
+        runtime: &Runtime,
+        java_version: Option<String>,
+        config: &Config,
+    ) {
+        self.results = None;
+        let ctx = ctx.clone();
+        self.task = Some(BackgroundTask::with_callback(
+            run_diagnostics(
+                java_version,
+                config.java_package_type,
+                config.get_launcher_dir(),
+            ),
+            runtime,
+            Box::new(move || ctx.request_repaint()),
+        ));
+    }
+
+    pub fn render_button(
+        &mut self,
+        ui: &mut egui::Ui,
+        runtime: &Runtime,
+        config: &Config,
+        java_version: Option<String>,
+    ) {
+        if ui
+            .button(LangMessage::RunDiagnostics.to_string(config.lang))
+            .clicked()
+        {
+            self.window_open = true;
+            if self.task.is_none() {
+                self.start(ui.ctx(), runtime, java_version, config);
+            }
+        }
+    }
+
+    fn get_summary(results: &[CheckResult], lang: Lang) -> String {
+        let mut lines = vec![get_launcher_info()];
+        for result in results {
+            let name = result.kind.message().to_string(lang);
+            lines.push(match &result.outcome {
+                CheckOutcome::Passed(details) => format!("[OK] {}: {}", name, details),
+                CheckOutcome::Failed(error) => format!("[FAIL] {}: {}", name, error),
+                CheckOutcome::Skipped => format!("[SKIP] {}", name),
+            });
+        }
+        lines.join("\n")
+    }
+
+    pub fn render_window(&mut self, ctx: &egui::Context, lang: Lang) {
+        if let Some(task) = self.task.as_ref() {
+            if task.has_result() {
+                let task = self.task.take().unwrap();
+                if let BackgroundTaskResult::Finished(results) = task.take_result() {
+                    self.results = Some(results);
+                }
+            }
+        }
+
+        let dark_mode = ctx.style().visuals.dark_mode;
+        let results = &self.results;
+        let running = self.task.is_some();
+        egui::Window::new(LangMessage::Diagnostics.to_string(lang))
+            .open(&mut self.window_open)
+            .default_width(500.0)
+            .show(ctx, |ui| {
+                if running {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(LangMessage::DiagnosticsRunning.to_string(lang));
+                    });
+                }
+                let Some(results) = results else {
+                    return;
+                };
+
+                egui::Grid::new("diagnostics_results")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        for result in results {
+                            let (status, color, details) = match &result.outcome {
+                                CheckOutcome::Passed(details) => {
+                                    ("✔", colors::ok(dark_mode), Some(details))
+                                }
+                                CheckOutcome::Failed(error) => {
+                                    ("✖", colors::error(dark_mode), Some(error))
+                                }
+                                CheckOutcome::Skipped => {
+                                    ("–", colors::partial_error(dark_mode), None)
+                                }
+                            };
+                            ui.label(
+                                egui::RichText::new(format!(
+                                    "{} {}",
+                                    status,
+                                    result.kind.message().to_string(lang)
+                                ))
+                                .color(color),
+                            );
+                            ui.add(
+                                egui::Label::new(
+                                    details
+                                        .cloned()
+                                        .unwrap_or(LangMessage::DiagnosticsSkipped.to_string(lang)),
+                                )
+                                .wrap(),
+                            );
+                            ui.end_row();
+                        }
+                    });
+
+                ui.separator();
+                if ui.button(LangMessage::CopyAll.to_string(lang)).clicked() {
+                    ui.ctx().copy_text(Self::get_summary(results, lang));
+                }
+            });
+    }
+}
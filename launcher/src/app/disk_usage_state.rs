@@ -0,0 +1,133 @@
+use std::path::Path;
+
+use shared::files;
+use shared::paths::get_instances_dir;
+use tokio::runtime::Runtime;
+
+use crate::config::runtime_config::Config;
+use crate::lang::LangMessage;
+
+use super::background_task::{BackgroundTask, BackgroundTaskResult};
+use super::colors;
+use super::error_label;
+
+struct DiskUsage {
+    total: u64,
+    free: u64,
+    // sorted from the largest
+    instances: Vec<(String, u64)>,
+}
+
+fn get_disk_usage(launcher_dir: &Path) -> anyhow::Result<DiskUsage> {
+    let mut instances = vec![];
+    for entry in std::fs::read_dir(get_instances_dir(launcher_dir))? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            instances.push((name, files::get_dir_size(&entry.path())));
+        }
+    }
+    instances.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+
+    Ok(DiskUsage {
+        total: files::get_dir_size(launcher_dir),
+        free: files::get_free_space(launcher_dir)?,
+        instances,
+    })
+}
+
+pub struct DiskUsageState {
+    task: Option<BackgroundTask<anyhow::Result<DiskUsage>>>,
+    result: Option<Result<DiskUsage, String>>,
+}
+
+impl DiskUsageState {
+    pub fn new() -> Self {
+        DiskUsageState {
+            task: None,
+            result: None,
+        }
+    }
+
+    fn start(&mut self, ctx: &egui::Context, runtime: &Runtime, config: &Config) {
+        let launcher_dir = config.get_launcher_dir();
+        let fut = async move {
+            tokio::task::spawn_blocking(move || get_disk_usage(&launcher_dir)).await?
+        };
+        let ctx = ctx.clone();
+        self.task = Some(BackgroundTask::with_callback(
+            fut,
+            runtime,
+            Box::new(move || ctx.request_repaint()),
+        ));
+    }
+
+    fn update(&mut self) {
+        if !self.task.as_ref().is_some_and(|task| task.has_result()) {
+            return;
+        }
+        if let BackgroundTaskResult::Finished(result) = self.task.take().unwrap().take_result() {
+            self.result = Some(result.map_err(|e| format!("{:?}", e)));
+        }
+    }
+
+    pub fn render_ui(&mut self, ui: &mut egui::Ui, runtime: &Runtime, config: &Config) {
+        self.update();
+
+        let lang = config.lang;
+        let dark_mode = ui.style().visuals.dark_mode;
+
+        // the directory walk only starts once the section is expanded
+        egui::CollapsingHeader::new(LangMessage::DiskUsage.to_string(lang))
+            .id_salt("disk_usage")
+            .show(ui, |ui| {
+                if self.result.is_none() && self.task.is_none() {
+                    self.start(ui.ctx(), runtime, config);
+                }
+
+                match &self.result {
+                    Some(Ok(usage)) => {
+                        ui.label(
+                            LangMessage::DataDirUsage {
+                                used: usage.total,
+                                free: usage.free,
+                            }
+                            .to_string(lang),
+                        );
+                        for (name, size) in &usage.instances {
+                            ui.label(format!(
+                                "{}: {}",
+                                name,
+                                LangMessage::ApproximateSize { size: *size }.to_string(lang)
+                            ));
+                        }
+                    }
+                    Some(Err(e)) => {
+                        error_label::render(
+                            ui,
+                            egui::RichText::new(LangMessage::DiskUsageError.to_string(lang))
+                                .color(colors::error(dark_mode)),
+                            Some(e),
+                            lang,
+                        );
+                    }
+                    None => {}
+                }
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            self.task.is_none(),
+                            egui::Button::new(LangMessage::Refresh.to_string(lang)),
+                        )
+                        .clicked()
+                    {
+                        self.start(ui.ctx(), runtime, config);
+                    }
+                    if self.task.is_some() {
+                        ui.spinner();
+                    }
+                });
+            });
+    }
+}
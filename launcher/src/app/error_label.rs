@@ -0,0 +1,21 @@
+use crate::lang::{Lang, LangMessage};
+
+pub fn render_copy_button(ui: &mut egui::Ui, error: &str, lang: Lang) {
+    if ui.small_button(LangMessage::Copy.to_string(lang)).clicked() {
+        ui.ctx().copy_text(error.to_string());
+    }
+}
+
+pub fn render(
+    ui: &mut egui::Ui,
+    text: impl Into<egui::WidgetText>,
+    error: Option<&str>,
+    lang: Lang,
+) {
+    ui.horizontal(|ui| {
+        ui.label(text);
+        if let Some(error) = error {
+            render_copy_button(ui, error, lang);
+        }
+    });
+}
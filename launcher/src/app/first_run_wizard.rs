@@ -0,0 +1,230 @@
+use shared::version::extra_version_metadata::AuthBackend;
+use tokio::runtime::Runtime;
+
+use crate::config::runtime_config::Config;
+use crate::lang::LangMessage;
+use crate::utils;
+
+use super::auth_state::AuthState;
+use super::colors;
+use super::language_selector::LanguageSelector;
+use super::manifest_state::ManifestState;
+
+#[derive(Clone, Copy, PartialEq)]
+enum WizardStep {
+    Language,
+    Instance,
+    SignIn,
+    DataDir,
+    Finish,
+}
+
+impl WizardStep {
+    // accounts are stored per instance, so signing in comes after picking one
+    const ALL: [WizardStep; 5] = [
+        WizardStep::Language,
+        WizardStep::Instance,
+        WizardStep::SignIn,
+        WizardStep::DataDir,
+        WizardStep::Finish,
+    ];
+
+    fn index(self) -> usize {
+        WizardStep::ALL.iter().position(|&x| x == self).unwrap()
+    }
+
+    fn message(self) -> LangMessage {
+        match self {
+            WizardStep::Language => LangMessage::WizardChooseLanguage,
+            WizardStep::Instance => LangMessage::WizardChooseInstance,
+            WizardStep::SignIn => LangMessage::WizardSignIn,
+            WizardStep::DataDir => LangMessage::WizardDataDir,
+            WizardStep::Finish => LangMessage::WizardReady,
+        }
+    }
+}
+
+pub enum WizardEvent {
+    None,
+    InstanceChanged,
+    DataDirChanged,
+    Finished { launch: bool },
+}
+
+pub struct WizardParams<'a> {
+    pub runtime: &'a Runtime,
+    pub auth_state: &'a mut AuthState,
+    pub manifest_state: &'a mut ManifestState,
+    pub local_instance_names: &'a Vec<String>,
+    pub remote_instance_names: &'a Vec<String>,
+    pub auth_backend: Option<&'a AuthBackend>,
+}
+
+pub struct FirstRunWizard {
+    step: WizardStep,
+    language_selector: LanguageSelector,
+    data_dir_error: bool,
+}
+
+impl FirstRunWizard {
+    pub fn new() -> Self {
+        FirstRunWizard {
+            step: WizardStep::Language,
+            language_selector: LanguageSelector::new(),
+            data_dir_error: false,
+        }
+    }
+
+    pub fn is_active(config: &Config) -> bool {
+        config.show_first_run_wizard
+    }
+
+    // returns whether the user can move on to the next step
+    fn render_step(
+        &mut self,
+        ui: &mut egui::Ui,
+        config: &mut Config,
+        params: &mut WizardParams,
+        event: &mut WizardEvent,
+    ) -> bool {
+        let lang = config.lang;
+        let dark_mode = ui.style().visuals.dark_mode;
+
+        match self.step {
+            WizardStep::Language => {
+                self.language_selector.render_ui(ui, config);
+                true
+            }
+            WizardStep::Instance => {
+                if params.manifest_state.is_fetching() {
+                    ui.spinner();
+                }
+                if params.manifest_state.render_combo_box(
+                    ui,
+                    config,
+                    params.local_instance_names,
+                    params.remote_instance_names,
+                ) {
+                    *event = WizardEvent::InstanceChanged;
+                }
+                config.selected_instance_name.is_some()
+            }
+            WizardStep::SignIn => {
+                if params.auth_backend.is_none() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(LangMessage::GettingMetadata.to_string(lang));
+                    });
+                    return false;
+                }
+                params.auth_state.render_ui(
+                    ui,
+                    config,
+                    params.runtime,
+                    &ui.ctx().clone(),
+                    params.auth_backend,
+                );
+                params.auth_state.get_auth_data(config).is_some()
+            }
+            WizardStep::DataDir => {
+                ui.code(config.get_launcher_dir().display().to_string());
+                let button = egui::Button::new(LangMessage::ChangeDataDir.to_string(lang));
+                if ui
+                    .add_enabled(config.data_dir_override.is_none(), button)
+                    .clicked()
+                {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .set_directory(config.get_launcher_dir())
+                        .pick_folder()
+                    {
+                        self.data_dir_error = !utils::is_dir_writable(&path);
+                        if !self.data_dir_error {
+                            config.data_dir = Some(path.display().to_string());
+                            config.data_dir_unavailable = false;
+                            config.save();
+                            *event = WizardEvent::DataDirChanged;
+                        }
+                    }
+                }
+                if self.data_dir_error {
+                    ui.label(
+                        egui::RichText::new(LangMessage::DataDirNotWritable.to_string(lang))
+                            .color(colors::error(dark_mode)),
+                    );
+                }
+                true
+            }
+            WizardStep::Finish => {
+                let button = egui::Button::new(
+                    egui::RichText::new(LangMessage::DownloadAndPlay.to_string(lang)).size(20.0),
+                );
+                if ui.add_sized([ui.available_width(), 40.0], button).clicked() {
+                    *event = WizardEvent::Finished { launch: true };
+                }
+                false
+            }
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        config: &mut Config,
+        mut params: WizardParams,
+    ) -> WizardEvent {
+        let lang = config.lang;
+        let mut event = WizardEvent::None;
+
+        egui::Window::new(LangMessage::Welcome.to_string(lang))
+            .collapsible(false)
+            .resizable(false)
+            .default_width(400.0)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                let index = self.step.index();
+                ui.label(
+                    egui::RichText::new(
+                        LangMessage::WizardStep {
+                            current: index + 1,
+                            total: WizardStep::ALL.len(),
+                        }
+                        .to_string(lang),
+                    )
+                    .weak(),
+                );
+                ui.heading(self.step.message().to_string(lang));
+                ui.add_space(5.0);
+
+                let can_continue = self.render_step(ui, config, &mut params, &mut event);
+
+                ui.add_space(5.0);
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if index > 0 && ui.button(LangMessage::Back.to_string(lang)).clicked() {
+                        self.step = WizardStep::ALL[index - 1];
+                    }
+                    if index + 1 < WizardStep::ALL.len()
+                        && ui
+                            .add_enabled(
+                                can_continue,
+                                egui::Button::new(LangMessage::Next.to_string(lang)),
+                            )
+                            .clicked()
+                    {
+                        self.step = WizardStep::ALL[index + 1];
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(LangMessage::SkipSetup.to_string(lang)).clicked() {
+                            event = WizardEvent::Finished { launch: false };
+                        }
+                    });
+                });
+            });
+
+        if let WizardEvent::Finished { .. } = event {
+            config.show_first_run_wizard = false;
+            config.save();
+        }
+        event
+    }
+}
@@ -0,0 +1,159 @@
+use std::{
+    collections::VecDeque,
+    io::Write as _,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use log::error;
+use tokio::{
+    io::{AsyncBufReadExt as _, AsyncRead, BufReader},
+    process::Child,
+    runtime::Runtime,
+};
+
+use crate::lang::{Lang, LangMessage};
+
+use super::progress_bar::REPAINT_INTERVAL;
+
+const MAX_LINES: usize = 5000;
+const TAIL_LINES: usize = 30;
+
+type Lines = Arc<Mutex<VecDeque<String>>>;
+type LogFile = Arc<Mutex<std::fs::File>>;
+
+pub struct GameOutput {
+    lines: Lines,
+    stderr_lines: Lines,
+    window_open: bool,
+}
+
+fn push_line(lines: &Lines, line: String, max_lines: usize) {
+    let mut lines = lines.lock().unwrap();
+    if lines.len() == max_lines {
+        lines.pop_front();
+    }
+    lines.push_back(line);
+}
+
+impl GameOutput {
+    pub fn new() -> Self {
+        GameOutput {
+            lines: Arc::new(Mutex::new(VecDeque::new())),
+            stderr_lines: Arc::new(Mutex::new(VecDeque::new())),
+            window_open: false,
+        }
+    }
+
+    async fn read_lines<R: AsyncRead + Unpin>(
+        reader: R,
+        lines: Lines,
+        stderr_lines: Option<Lines>,
+        log_file: LogFile,
+        ctx: egui::Context,
+    ) {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf).await {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Error reading Minecraft output:\n{:?}", e);
+                    break;
+                }
+            }
+
+            let _ = log_file.lock().unwrap().write_all(&buf);
+
+            let line = String::from_utf8_lossy(&buf).trim_end().to_string();
+            if let Some(stderr_lines) = &stderr_lines {
+                push_line(stderr_lines, line.clone(), TAIL_LINES);
+            }
+            push_line(&lines, line, MAX_LINES);
+            ctx.request_repaint_after(REPAINT_INTERVAL);
+        }
+    }
+
+    pub fn capture(
+        &mut self,
+        runtime: &Runtime,
+        child: &mut Child,
+        log_path: &Path,
+        ctx: &egui::Context,
+    ) -> anyhow::Result<()> {
+        self.lines.lock().unwrap().clear();
+        self.stderr_lines.lock().unwrap().clear();
+
+        let log_file = Arc::new(Mutex::new(std::fs::File::create(log_path)?));
+        if let Some(stdout) = child.stdout.take() {
+            runtime.spawn(Self::read_lines(
+                stdout,
+                self.lines.clone(),
+                None,
+                log_file.clone(),
+                ctx.clone(),
+            ));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            runtime.spawn(Self::read_lines(
+                stderr,
+                self.lines.clone(),
+                Some(self.stderr_lines.clone()),
+                log_file,
+                ctx.clone(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The last lines of stderr, or of the whole output if nothing was written to stderr.
+    pub fn get_tail(&self) -> String {
+        let stderr_lines = self.stderr_lines.lock().unwrap();
+        if !stderr_lines.is_empty() {
+            return stderr_lines.iter().cloned().collect::<Vec<_>>().join("\n");
+        }
+        let lines = self.lines.lock().unwrap();
+        let skip = lines.len().saturating_sub(TAIL_LINES);
+        lines
+            .iter()
+            .skip(skip)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn render_button(&mut self, ui: &mut egui::Ui, lang: Lang) {
+        if ui.button(LangMessage::GameOutput.to_string(lang)).clicked() {
+            self.window_open = !self.window_open;
+        }
+    }
+
+    pub fn render_window(&mut self, ctx: &egui::Context, lang: Lang) {
+        let lines = self.lines.clone();
+        egui::Window::new(LangMessage::GameOutput.to_string(lang))
+            .open(&mut self.window_open)
+            .default_size([600.0, 300.0])
+            .show(ctx, |ui| {
+                let lines = lines.lock().unwrap();
+                if ui.button(LangMessage::CopyAll.to_string(lang)).clicked() {
+                    let text = lines.iter().cloned().collect::<Vec<_>>().join("\n");
+                    ui.ctx().copy_text(text);
+                }
+                ui.separator();
+
+                let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+                egui::ScrollArea::both()
+                    .auto_shrink([false, false])
+                    .stick_to_bottom(true)
+                    .show_rows(ui, row_height, lines.len(), |ui, row_range| {
+                        for line in lines.range(row_range) {
+                            ui.add(
+                                egui::Label::new(egui::RichText::new(line).monospace()).extend(),
+                            );
+                        }
+                    });
+            });
+    }
+}
@@ -0,0 +1,194 @@
+use std::path::PathBuf;
+
+use tokio::runtime::Runtime;
+
+use crate::config::runtime_config::Config;
+use crate::lang::LangMessage;
+use crate::version::import;
+
+use super::background_task::{BackgroundTask, BackgroundTaskResult};
+use super::colors;
+use super::error_label;
+
+enum ImportStatus {
+    Imported(Vec<String>),
+    Error(String),
+}
+
+struct PendingImport {
+    source_dir: PathBuf,
+    conflicts: Vec<String>,
+}
+
+struct RunningImport {
+    instance_name: String,
+    task: BackgroundTask<anyhow::Result<Vec<String>>>,
+}
+
+pub struct ImportState {
+    pending: Option<PendingImport>,
+    running: Option<RunningImport>,
+    status: Option<ImportStatus>,
+}
+
+impl ImportState {
+    pub fn new() -> Self {
+        ImportState {
+            pending: None,
+            running: None,
+            status: None,
+        }
+    }
+
+    fn start(
+        &mut self,
+        ctx: &egui::Context,
+        runtime: &Runtime,
+        config: &Config,
+        instance_name: &str,
+        source_dir: PathBuf,
+    ) {
+        self.pending = None;
+        self.status = None;
+        let instance_dir = config.get_game_dir(instance_name);
+        let fut = async move {
+            tokio::task::spawn_blocking(move || {
+                import::import_minecraft_dir(&source_dir, &instance_dir)
+            })
+            .await?
+        };
+        let ctx = ctx.clone();
+        self.running = Some(RunningImport {
+            instance_name: instance_name.to_string(),
+            task: BackgroundTask::with_callback(
+                fut,
+                runtime,
+                Box::new(move || ctx.request_repaint()),
+            ),
+        });
+    }
+
+    fn update(&mut self, config: &mut Config) {
+        if !self
+            .running
+            .as_ref()
+            .is_some_and(|running| running.task.has_result())
+        {
+            return;
+        }
+        let running = self.running.take().unwrap();
+        self.status = match running.task.take_result() {
+            BackgroundTaskResult::Finished(Ok(paths)) => {
+                let preserved_paths = &mut config
+                    .get_instance_settings_mut(&running.instance_name)
+                    .preserved_paths;
+                for path in &paths {
+                    if !preserved_paths.contains(path) {
+                        preserved_paths.push(path.clone());
+                    }
+                }
+                config.save();
+                Some(ImportStatus::Imported(paths))
+            }
+            BackgroundTaskResult::Finished(Err(e)) => Some(ImportStatus::Error(format!("{:?}", e))),
+            BackgroundTaskResult::Cancelled => None,
+        };
+    }
+
+    pub fn render_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        runtime: &Runtime,
+        config: &mut Config,
+        instance_name: &str,
+    ) {
+        self.update(config);
+
+        let lang = config.lang;
+        let dark_mode = ui.style().visuals.dark_mode;
+
+        if let Some(running) = &self.running {
+            if running.instance_name == instance_name {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(LangMessage::ImportingMinecraftDir.to_string(lang));
+                });
+            }
+            return;
+        }
+
+        if let Some(pending) = &self.pending {
+            ui.label(
+                egui::RichText::new(
+                    LangMessage::ImportOverwriteWarning {
+                        paths: pending.conflicts.join(", "),
+                    }
+                    .to_string(lang),
+                )
+                .color(colors::partial_error(dark_mode)),
+            );
+            let mut confirmed = false;
+            let mut cancelled = false;
+            ui.horizontal(|ui| {
+                confirmed = ui
+                    .button(LangMessage::ImportAndOverwrite.to_string(lang))
+                    .clicked();
+                cancelled = ui.button(LangMessage::Cancel.to_string(lang)).clicked();
+            });
+            if confirmed {
+                let source_dir = pending.source_dir.clone();
+                self.start(ui.ctx(), runtime, config, instance_name, source_dir);
+            } else if cancelled {
+                self.pending = None;
+            }
+            return;
+        }
+
+        if ui
+            .button(LangMessage::ImportMinecraftDir.to_string(lang))
+            .clicked()
+        {
+            let mut dialog = rfd::FileDialog::new();
+            if let Some(default_dir) = import::get_default_minecraft_dir().filter(|x| x.is_dir()) {
+                dialog = dialog.set_directory(default_dir);
+            }
+            if let Some(source_dir) = dialog.pick_folder() {
+                let instance_dir = config.get_game_dir(instance_name);
+                let conflicts = import::get_conflicting_paths(&source_dir, &instance_dir);
+                // the import itself reports overlapping directories, there is nothing to confirm
+                if conflicts.is_empty() || import::dirs_overlap(&source_dir, &instance_dir) {
+                    self.start(ui.ctx(), runtime, config, instance_name, source_dir);
+                } else {
+                    self.pending = Some(PendingImport {
+                        source_dir,
+                        conflicts,
+                    });
+                }
+            }
+        }
+
+        match &self.status {
+            Some(ImportStatus::Imported(paths)) => {
+                ui.label(
+                    egui::RichText::new(
+                        LangMessage::ImportedMinecraftDir {
+                            paths: paths.join(", "),
+                        }
+                        .to_string(lang),
+                    )
+                    .color(colors::ok(dark_mode)),
+                );
+            }
+            Some(ImportStatus::Error(e)) => {
+                error_label::render(
+                    ui,
+                    egui::RichText::new(LangMessage::ImportMinecraftDirError.to_string(lang))
+                        .color(colors::error(dark_mode)),
+                    Some(e),
+                    lang,
+                );
+            }
+            None => {}
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use egui::RichText;
 use log::error;
+use shared::adaptive_download::DownloadOptions;
 use shared::progress::ProgressBar;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::runtime::Runtime;
 
@@ -9,30 +10,39 @@ use crate::config::runtime_config::Config;
 use crate::lang::{Lang, LangMessage};
 use crate::utils;
 use crate::version::complete_version_metadata::CompleteVersionMetadata;
-use crate::version::sync;
+use crate::version::sync::{self, GameDir, VerifyReport};
 
 use super::background_task::{BackgroundTask, BackgroundTaskResult};
 use super::colors;
+use super::error_label;
 use super::progress_bar::GuiProgressBar;
+use super::status_bar::{Severity, StatusMessage};
 
 #[derive(Clone, PartialEq)]
 enum InstanceSyncStatus {
     NotSynced,
     Synced,
-    SyncError,
+    Repaired(usize),
+    SyncError(String),
+    SyncErrorServer(u16, String),
     SyncErrorOffline,
+    SyncErrorDiskSpace { required: u64, available: u64 },
 }
 
 fn sync_instance(
     runtime: &Runtime,
     instance_metadata: Arc<CompleteVersionMetadata>,
     force_overwrite: bool,
-    launcher_dir: &Path,
-    assets_dir: &Path,
+    config: &Config,
     progress_bar: Arc<dyn ProgressBar<LangMessage>>,
 ) -> BackgroundTask<anyhow::Result<()>> {
-    let launcher_dir = launcher_dir.to_path_buf();
-    let assets_dir = assets_dir.to_path_buf();
+    let launcher_dir = config.get_launcher_dir();
+    let assets_dir = config.get_assets_dir();
+    let download_options = config.get_download_options();
+    let game_dir = config.get_game_dir(instance_metadata.get_name());
+    let preserved_paths = config
+        .get_instance_settings(instance_metadata.get_name())
+        .preserved_paths;
 
     let instance_metadata = instance_metadata.clone();
     let progress_bar_clone = progress_bar.clone();
@@ -41,8 +51,128 @@ fn sync_instance(
         sync::sync_instance(
             &instance_metadata,
             force_overwrite,
+            &GameDir {
+                path: &game_dir,
+                preserved_paths: &preserved_paths,
+            },
             &launcher_dir,
             &assets_dir,
+            &download_options,
+            progress_bar_clone,
+        )
+        .await
+    };
+
+    BackgroundTask::with_callback(
+        fut,
+        runtime,
+        Box::new(move || {
+            progress_bar.finish();
+        }),
+    )
+}
+
+fn verify_instance(
+    runtime: &Runtime,
+    instance_metadata: Arc<CompleteVersionMetadata>,
+    config: &Config,
+    progress_bar: Arc<dyn ProgressBar<LangMessage>>,
+) -> BackgroundTask<anyhow::Result<VerifyReport>> {
+    let launcher_dir = config.get_launcher_dir();
+    let assets_dir = config.get_assets_dir();
+    let game_dir = config.get_game_dir(instance_metadata.get_name());
+    let preserved_paths = config
+        .get_instance_settings(instance_metadata.get_name())
+        .preserved_paths;
+
+    let progress_bar_clone = progress_bar.clone();
+    let fut = async move {
+        sync::verify_instance(
+            &instance_metadata,
+            &GameDir {
+                path: &game_dir,
+                preserved_paths: &preserved_paths,
+            },
+            &launcher_dir,
+            &assets_dir,
+            progress_bar_clone,
+        )
+        .await
+    };
+
+    BackgroundTask::with_callback(
+        fut,
+        runtime,
+        Box::new(move || {
+            progress_bar.finish();
+        }),
+    )
+}
+
+fn repair_instance(
+    runtime: &Runtime,
+    instance_metadata: Arc<CompleteVersionMetadata>,
+    report: VerifyReport,
+    launcher_dir: &Path,
+    download_options: DownloadOptions,
+    progress_bar: Arc<dyn ProgressBar<LangMessage>>,
+) -> BackgroundTask<anyhow::Result<()>> {
+    let launcher_dir = launcher_dir.to_path_buf();
+
+    let progress_bar_clone = progress_bar.clone();
+    let fut = async move {
+        sync::repair_instance(
+            &instance_metadata,
+            report,
+            &launcher_dir,
+            &download_options,
+            progress_bar_clone,
+        )
+        .await
+    };
+
+    BackgroundTask::with_callback(
+        fut,
+        runtime,
+        Box::new(move || {
+            progress_bar.finish();
+        }),
+    )
+}
+
+const MAX_DELETION_SAMPLES: usize = 10;
+
+struct PendingSync {
+    instance_metadata: Arc<CompleteVersionMetadata>,
+    force_overwrite: bool,
+    files_to_delete: Vec<PathBuf>,
+}
+
+fn verify_and_repair_instance(
+    runtime: &Runtime,
+    instance_metadata: Arc<CompleteVersionMetadata>,
+    config: &Config,
+    progress_bar: Arc<dyn ProgressBar<LangMessage>>,
+) -> BackgroundTask<anyhow::Result<usize>> {
+    let launcher_dir = config.get_launcher_dir();
+    let assets_dir = config.get_assets_dir();
+    let download_options = config.get_download_options();
+    let game_dir = config.get_game_dir(instance_metadata.get_name());
+    let preserved_paths = config
+        .get_instance_settings(instance_metadata.get_name())
+        .preserved_paths;
+
+    let progress_bar_clone = progress_bar.clone();
+    let fut = async move {
+        sync::verify_and_repair_instance(
+            &instance_metadata,
+            &GameDir {
+                path: &game_dir,
+                preserved_paths: &preserved_paths,
+            },
+            &launcher_dir,
+            &assets_dir,
+            &download_options,
             progress_bar_clone,
         )
         .await
@@ -64,6 +194,18 @@ pub struct InstanceSyncState {
 
     instance_sync_window_open: bool,
     force_overwrite_checked: bool,
+    last_force_overwrite: bool,
+
+    verify_task: Option<BackgroundTask<anyhow::Result<VerifyReport>>>,
+    verify_result: Option<Result<VerifyReport, String>>,
+
+    repair_task: Option<BackgroundTask<anyhow::Result<usize>>>,
+
+    // walking the include directories takes a while on large packs
+    deletion_check_task: Option<BackgroundTask<anyhow::Result<PendingSync>>>,
+    pending_sync: Option<PendingSync>,
+
+    launch_offline: bool,
 }
 
 impl InstanceSyncState {
@@ -77,10 +219,50 @@ impl InstanceSyncState {
 
             instance_sync_window_open: false,
             force_overwrite_checked: false,
+            last_force_overwrite: false,
+
+            verify_task: None,
+            verify_result: None,
+
+            repair_task: None,
+
+            deletion_check_task: None,
+            pending_sync: None,
+
+            launch_offline: false,
         }
     }
 
     pub fn update(&mut self) -> bool {
+        if let Some(task) = self.verify_task.as_ref() {
+            if task.has_result() {
+                let task = self.verify_task.take().unwrap();
+                if let BackgroundTaskResult::Finished(result) = task.take_result() {
+                    self.verify_result = Some(result.map_err(|e| {
+                        error!("Error verifying instance:\n{:?}", e);
+                        format!("{:?}", e)
+                    }));
+                }
+            }
+        }
+
+        if let Some(task) = self.repair_task.as_ref() {
+            if task.has_result() {
+                let task = self.repair_task.take().unwrap();
+                match task.take_result() {
+                    BackgroundTaskResult::Finished(Ok(repaired)) => {
+                        self.status = InstanceSyncStatus::Repaired(repaired);
+                        return true;
+                    }
+                    BackgroundTaskResult::Finished(Err(e)) => {
+                        error!("Error repairing instance:\n{:?}", e);
+                        self.status = InstanceSyncStatus::SyncError(format!("{:?}", e));
+                    }
+                    BackgroundTaskResult::Cancelled => {}
+                }
+            }
+        }
+
         if let Some(task) = self.instance_sync_task.as_ref() {
             if task.has_result() {
                 self.instance_sync_window_open = false;
@@ -90,11 +272,23 @@ impl InstanceSyncState {
                         self.status = match result {
                             Ok(()) => InstanceSyncStatus::Synced,
                             Err(e) => {
-                                if utils::is_connect_error(&e) {
+                                if let Some((required, available)) = utils::get_disk_space_error(&e)
+                                {
+                                    InstanceSyncStatus::SyncErrorDiskSpace {
+                                        required,
+                                        available,
+                                    }
+                                } else if utils::is_connect_error(&e) {
                                     InstanceSyncStatus::SyncErrorOffline
+                                } else if let Some(status) = utils::get_http_status(&e) {
+                                    error!("Server error syncing instance:\n{:?}", e);
+                                    InstanceSyncStatus::SyncErrorServer(
+                                        status.as_u16(),
+                                        format!("{:?}", e),
+                                    )
                                 } else {
                                     error!("Error syncing instance:\n{:?}", e);
-                                    InstanceSyncStatus::SyncError
+                                    InstanceSyncStatus::SyncError(format!("{:?}", e))
                                 }
                             }
                         };
@@ -113,8 +307,55 @@ impl InstanceSyncState {
         false
     }
 
+    pub fn get_status_message(&self) -> Option<StatusMessage> {
+        Some(match &self.status {
+            InstanceSyncStatus::SyncError(e) => {
+                StatusMessage::new(Severity::Error, LangMessage::InstanceSyncError, Some(e))
+            }
+            InstanceSyncStatus::SyncErrorServer(status, e) => StatusMessage::new(
+                Severity::Error,
+                LangMessage::SyncServerError { status: *status },
+                Some(e),
+            ),
+            InstanceSyncStatus::SyncErrorOffline => StatusMessage::new(
+                Severity::Offline,
+                LangMessage::NoConnectionToSyncServer,
+                None,
+            ),
+            InstanceSyncStatus::SyncErrorDiskSpace {
+                required,
+                available,
+            } => StatusMessage::new(
+                Severity::Error,
+                LangMessage::NotEnoughDiskSpace {
+                    required: *required,
+                    available: *available,
+                },
+                None,
+            ),
+            _ => return None,
+        })
+    }
+
     pub fn reset_status(&mut self) {
         self.status = InstanceSyncStatus::NotSynced;
+        self.verify_result = None;
+        self.launch_offline = false;
+    }
+
+    fn is_failed(&self) -> bool {
+        matches!(
+            self.status,
+            InstanceSyncStatus::SyncError(_)
+                | InstanceSyncStatus::SyncErrorServer(_, _)
+                | InstanceSyncStatus::SyncErrorOffline
+                | InstanceSyncStatus::SyncErrorDiskSpace { .. }
+        )
+    }
+
+    // the user chose to play the outdated local files after the sync failed
+    pub fn launch_offline_requested(&self) -> bool {
+        self.launch_offline && self.is_failed()
     }
 
     pub fn set_up_to_date(&mut self) {
@@ -129,6 +370,83 @@ impl InstanceSyncState {
         config: &Config,
         ctx: &egui::Context,
     ) {
+        let instance_name = selected_version_metadata.get_name();
+        let game_dir = config.get_game_dir(instance_name);
+        let preserved_paths = config.get_instance_settings(instance_name).preserved_paths;
+        let fut = async move {
+            Ok(tokio::task::spawn_blocking(move || {
+                let files_to_delete = if force_overwrite
+                    || sync::is_first_sync(&selected_version_metadata, &game_dir)
+                {
+                    sync::get_files_to_delete(
+                        &selected_version_metadata,
+                        force_overwrite,
+                        &GameDir {
+                            path: &game_dir,
+                            preserved_paths: &preserved_paths,
+                        },
+                    )
+                } else {
+                    vec![]
+                };
+                PendingSync {
+                    instance_metadata: selected_version_metadata,
+                    force_overwrite,
+                    files_to_delete,
+                }
+            })
+            .await?)
+        };
+        let ctx = ctx.clone();
+        self.deletion_check_task = Some(BackgroundTask::with_callback(
+            fut,
+            runtime,
+            Box::new(move || ctx.request_repaint()),
+        ));
+    }
+
+    // starts the sync right away when it deletes nothing, asks for confirmation otherwise
+    fn update_deletion_check(&mut self, runtime: &Runtime, config: &Config, ctx: &egui::Context) {
+        if !self
+            .deletion_check_task
+            .as_ref()
+            .is_some_and(|task| task.has_result())
+        {
+            return;
+        }
+        let task = self.deletion_check_task.take().unwrap();
+        match task.take_result() {
+            BackgroundTaskResult::Finished(Ok(pending_sync)) => {
+                if pending_sync.files_to_delete.is_empty() {
+                    self.start_sync(
+                        runtime,
+                        pending_sync.instance_metadata,
+                        pending_sync.force_overwrite,
+                        config,
+                        ctx,
+                    );
+                } else {
+                    self.pending_sync = Some(pending_sync);
+                }
+            }
+            BackgroundTaskResult::Finished(Err(e)) => {
+                error!("Error checking files to delete:\n{:?}", e);
+                self.status = InstanceSyncStatus::SyncError(format!("{:?}", e));
+            }
+            BackgroundTaskResult::Cancelled => {}
+        }
+    }
+
+    fn start_sync(
+        &mut self,
+        runtime: &Runtime,
+        selected_version_metadata: Arc<CompleteVersionMetadata>,
+        force_overwrite: bool,
+        config: &Config,
+        ctx: &egui::Context,
+    ) {
+        self.pending_sync = None;
+        self.last_force_overwrite = force_overwrite;
         self.instance_sync_progress_bar = Arc::new(GuiProgressBar::new(ctx));
         if let Some(task) = self.instance_sync_task.take() {
             task.cancel();
@@ -137,8 +455,68 @@ impl InstanceSyncState {
             runtime,
             selected_version_metadata,
             force_overwrite,
+            config,
+            self.instance_sync_progress_bar.clone(),
+        ));
+    }
+
+    fn schedule_verify(
+        &mut self,
+        runtime: &Runtime,
+        selected_version_metadata: Arc<CompleteVersionMetadata>,
+        config: &Config,
+        ctx: &egui::Context,
+    ) {
+        self.instance_sync_progress_bar = Arc::new(GuiProgressBar::new(ctx));
+        self.verify_result = None;
+        if let Some(task) = self.verify_task.take() {
+            task.cancel();
+        }
+        self.verify_task = Some(verify_instance(
+            runtime,
+            selected_version_metadata,
+            config,
+            self.instance_sync_progress_bar.clone(),
+        ));
+    }
+
+    fn schedule_repair(
+        &mut self,
+        runtime: &Runtime,
+        selected_version_metadata: Arc<CompleteVersionMetadata>,
+        report: VerifyReport,
+        config: &Config,
+        ctx: &egui::Context,
+    ) {
+        self.instance_sync_progress_bar = Arc::new(GuiProgressBar::new(ctx));
+        if let Some(task) = self.instance_sync_task.take() {
+            task.cancel();
+        }
+        self.instance_sync_task = Some(repair_instance(
+            runtime,
+            selected_version_metadata,
+            report,
             &config.get_launcher_dir(),
-            &config.get_assets_dir(),
+            config.get_download_options(),
+            self.instance_sync_progress_bar.clone(),
+        ));
+    }
+
+    fn schedule_verify_and_repair(
+        &mut self,
+        runtime: &Runtime,
+        selected_version_metadata: Arc<CompleteVersionMetadata>,
+        config: &Config,
+        ctx: &egui::Context,
+    ) {
+        self.instance_sync_progress_bar = Arc::new(GuiProgressBar::new(ctx));
+        if let Some(task) = self.repair_task.take() {
+            task.cancel();
+        }
+        self.repair_task = Some(verify_and_repair_instance(
+            runtime,
+            selected_version_metadata,
+            config,
             self.instance_sync_progress_bar.clone(),
         ));
     }
@@ -153,8 +531,10 @@ impl InstanceSyncState {
     ) {
         match &self.status {
             InstanceSyncStatus::NotSynced
-            | InstanceSyncStatus::SyncError
-            | InstanceSyncStatus::SyncErrorOffline => {
+            | InstanceSyncStatus::SyncError(_)
+            | InstanceSyncStatus::SyncErrorServer(_, _)
+            | InstanceSyncStatus::SyncErrorOffline
+            | InstanceSyncStatus::SyncErrorDiskSpace { .. } => {
                 self.schedule_sync(
                     runtime,
                     selected_version_metadata,
@@ -163,15 +543,22 @@ impl InstanceSyncState {
                     ctx,
                 );
             }
-            InstanceSyncStatus::Synced => {}
+            InstanceSyncStatus::Synced | InstanceSyncStatus::Repaired(_) => {}
         };
     }
 
-    pub fn render_status(&self, ui: &mut egui::Ui, config: &Config) {
+    pub fn render_status(
+        &mut self,
+        ui: &mut egui::Ui,
+        runtime: &Runtime,
+        config: &Config,
+        selected_version_metadata: Arc<CompleteVersionMetadata>,
+        has_local_files: bool,
+    ) {
         let lang = config.lang;
         let dark_mode = ui.style().visuals.dark_mode;
 
-        ui.label(match &self.status {
+        let text = match &self.status {
             InstanceSyncStatus::NotSynced => {
                 RichText::new(LangMessage::InstanceNotSynced.to_string(lang))
                     .color(colors::action(dark_mode))
@@ -180,15 +567,78 @@ impl InstanceSyncState {
                 RichText::new(LangMessage::InstanceSynced.to_string(lang))
                     .color(colors::ok(dark_mode))
             }
-            InstanceSyncStatus::SyncError => {
+            InstanceSyncStatus::Repaired(repaired) => {
+                RichText::new(LangMessage::FilesRepaired { count: *repaired }.to_string(lang))
+                    .color(colors::ok(dark_mode))
+            }
+            InstanceSyncStatus::SyncError(_) => {
                 RichText::new(LangMessage::InstanceSyncError.to_string(lang))
                     .color(colors::error(dark_mode))
             }
+            InstanceSyncStatus::SyncErrorServer(status, _) => {
+                RichText::new(LangMessage::SyncServerError { status: *status }.to_string(lang))
+                    .color(colors::error(dark_mode))
+            }
             InstanceSyncStatus::SyncErrorOffline => {
                 RichText::new(LangMessage::NoConnectionToSyncServer.to_string(lang))
                     .color(colors::offline(dark_mode))
             }
+            InstanceSyncStatus::SyncErrorDiskSpace {
+                required,
+                available,
+            } => RichText::new(
+                LangMessage::NotEnoughDiskSpace {
+                    required: *required,
+                    available: *available,
+                }
+                .to_string(lang),
+            )
+            .color(colors::error(dark_mode)),
+        };
+        let error = match &self.status {
+            InstanceSyncStatus::SyncError(e) | InstanceSyncStatus::SyncErrorServer(_, e) => {
+                Some(e.as_str())
+            }
+            _ => None,
+        };
+        error_label::render(ui, text, error, lang);
+
+        if !self.is_failed() {
+            return;
+        }
+        let mut retry = false;
+        ui.horizontal(|ui| {
+            retry = ui
+                .add_enabled(
+                    !self.is_syncing() && self.pending_sync.is_none(),
+                    egui::Button::new(LangMessage::Retry.to_string(lang)),
+                )
+                .clicked();
+            if has_local_files
+                && !self.launch_offline
+                && ui
+                    .button(LangMessage::LaunchOfflineWithCurrentFiles.to_string(lang))
+                    .clicked()
+            {
+                self.launch_offline = true;
+            }
         });
+        if self.launch_offline {
+            ui.label(
+                RichText::new(LangMessage::InstanceMayBeOutdated.to_string(lang))
+                    .color(colors::partial_error(dark_mode)),
+            );
+        }
+        if retry {
+            self.launch_offline = false;
+            self.schedule_sync(
+                runtime,
+                selected_version_metadata,
+                self.last_force_overwrite,
+                config,
+                ui.ctx(),
+            );
+        }
     }
 
     pub fn render_windows(
@@ -198,7 +648,10 @@ impl InstanceSyncState {
         config: &Config,
         selected_version_metadata: Option<Arc<CompleteVersionMetadata>>,
     ) {
-        self.render_sync_window(ui, runtime, config, selected_version_metadata);
+        self.render_sync_window(ui, runtime, config, selected_version_metadata.clone());
+        self.render_verify_window(ui, runtime, config, selected_version_metadata);
+        self.update_deletion_check(runtime, config, ui.ctx());
+        self.render_deletion_window(ui, runtime, config);
         self.render_progress_bar_window(ui, config.lang);
     }
 
@@ -213,8 +666,9 @@ impl InstanceSyncState {
 
         if ui
             .add_enabled(
-                self.instance_sync_task.is_none()
+                !self.is_syncing()
                     && !self.instance_sync_window_open
+                    && self.pending_sync.is_none()
                     && selected_version_metadata.is_some(),
                 egui::Button::new(LangMessage::SyncInstance.to_string(lang)),
             )
@@ -222,8 +676,10 @@ impl InstanceSyncState {
         {
             match &self.status {
                 InstanceSyncStatus::NotSynced
-                | InstanceSyncStatus::SyncError
-                | InstanceSyncStatus::SyncErrorOffline => {
+                | InstanceSyncStatus::SyncError(_)
+                | InstanceSyncStatus::SyncErrorServer(_, _)
+                | InstanceSyncStatus::SyncErrorOffline
+                | InstanceSyncStatus::SyncErrorDiskSpace { .. } => {
                     self.schedule_sync(
                         runtime,
                         selected_version_metadata.clone().unwrap(),
@@ -268,13 +724,46 @@ impl InstanceSyncState {
                     {
                         self.schedule_sync(
                             runtime,
-                            selected_version_metadata.unwrap(),
+                            selected_version_metadata.clone().unwrap(),
                             self.force_overwrite_checked,
                             config,
                             ui.ctx(),
                         );
                         close_sync_window = true;
                     }
+
+                    ui.separator();
+                    if ui
+                        .add_enabled(
+                            selected_version_metadata.is_some(),
+                            egui::Button::new(LangMessage::VerifyFiles.to_string(lang)),
+                        )
+                        .clicked()
+                    {
+                        self.schedule_verify(
+                            runtime,
+                            selected_version_metadata.clone().unwrap(),
+                            config,
+                            ui.ctx(),
+                        );
+                        close_sync_window = true;
+                    }
+
+                    if ui
+                        .add_enabled(
+                            selected_version_metadata.is_some(),
+                            egui::Button::new(LangMessage::RepairInstallation.to_string(lang)),
+                        )
+                        .clicked()
+                    {
+                        self.schedule_verify_and_repair(
+                            runtime,
+                            selected_version_metadata.clone().unwrap(),
+                            config,
+                            ui.ctx(),
+                        );
+                        close_sync_window = true;
+                    }
                 });
             });
         self.instance_sync_window_open = instance_sync_window_open;
@@ -283,8 +772,155 @@ impl InstanceSyncState {
         }
     }
 
+    fn render_verify_window(
+        &mut self,
+        ui: &mut egui::Ui,
+        runtime: &Runtime,
+        config: &Config,
+        selected_version_metadata: Option<Arc<CompleteVersionMetadata>>,
+    ) {
+        let lang = config.lang;
+        let dark_mode = ui.style().visuals.dark_mode;
+        let Some(verify_result) = &self.verify_result else {
+            return;
+        };
+
+        let mut verify_window_open = true;
+        let mut repair_clicked = false;
+        egui::Window::new(LangMessage::VerifyFiles.to_string(lang))
+            .open(&mut verify_window_open)
+            .show(ui.ctx(), |ui| match verify_result {
+                Ok(report) if report.is_ok() => {
+                    ui.label(
+                        RichText::new(LangMessage::AllFilesOk.to_string(lang))
+                            .color(colors::ok(dark_mode)),
+                    );
+                }
+                Ok(report) => {
+                    ui.label(
+                        RichText::new(
+                            LangMessage::VerifyResult {
+                                missing: report.missing.len(),
+                                corrupted: report.corrupted.len(),
+                            }
+                            .to_string(lang),
+                        )
+                        .color(colors::partial_error(dark_mode)),
+                    );
+                    egui::ScrollArea::vertical()
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            for path in report.missing.iter().chain(report.corrupted.iter()) {
+                                ui.label(path.display().to_string());
+                            }
+                        });
+                    repair_clicked = ui
+                        .add_enabled(
+                            selected_version_metadata.is_some(),
+                            egui::Button::new(LangMessage::RepairFiles.to_string(lang)),
+                        )
+                        .clicked();
+                }
+                Err(e) => {
+                    error_label::render(
+                        ui,
+                        RichText::new(LangMessage::VerifyError.to_string(lang))
+                            .color(colors::error(dark_mode)),
+                        Some(e),
+                        lang,
+                    );
+                }
+            });
+
+        if repair_clicked {
+            if let Some(Ok(report)) = self.verify_result.take() {
+                self.schedule_repair(
+                    runtime,
+                    selected_version_metadata.unwrap(),
+                    report,
+                    config,
+                    ui.ctx(),
+                );
+            }
+        }
+        if !verify_window_open {
+            self.verify_result = None;
+        }
+    }
+
+    fn render_deletion_window(&mut self, ui: &mut egui::Ui, runtime: &Runtime, config: &Config) {
+        let lang = config.lang;
+        let dark_mode = ui.style().visuals.dark_mode;
+        let Some(pending_sync) = &self.pending_sync else {
+            return;
+        };
+
+        let instance_dir = config.get_game_dir(pending_sync.instance_metadata.get_name());
+        let mut window_open = true;
+        let mut confirmed = false;
+        let mut cancelled = false;
+        egui::Window::new(LangMessage::SyncInstance.to_string(lang))
+            .open(&mut window_open)
+            .show(ui.ctx(), |ui| {
+                let files_to_delete = &pending_sync.files_to_delete;
+                ui.label(
+                    RichText::new(
+                        LangMessage::FilesWillBeDeleted {
+                            count: files_to_delete.len(),
+                        }
+                        .to_string(lang),
+                    )
+                    .color(colors::partial_error(dark_mode)),
+                );
+                for path in files_to_delete.iter().take(MAX_DELETION_SAMPLES) {
+                    let path = path.strip_prefix(&instance_dir).unwrap_or(path);
+                    ui.label(path.display().to_string());
+                }
+                if files_to_delete.len() > MAX_DELETION_SAMPLES {
+                    ui.label(
+                        LangMessage::AndMore {
+                            count: files_to_delete.len() - MAX_DELETION_SAMPLES,
+                        }
+                        .to_string(lang),
+                    );
+                }
+
+                ui.horizontal(|ui| {
+                    confirmed = ui
+                        .button(LangMessage::DeleteAndSync.to_string(lang))
+                        .clicked();
+                    cancelled = ui.button(LangMessage::Cancel.to_string(lang)).clicked();
+                });
+            });
+
+        if confirmed {
+            if let Some(pending_sync) = self.pending_sync.take() {
+                self.start_sync(
+                    runtime,
+                    pending_sync.instance_metadata,
+                    pending_sync.force_overwrite,
+                    config,
+                    ui.ctx(),
+                );
+            }
+        } else if cancelled || !window_open {
+            self.pending_sync = None;
+        }
+    }
+
     fn render_progress_bar_window(&mut self, ui: &mut egui::Ui, lang: Lang) {
-        if self.instance_sync_task.is_some() {
+        if self.deletion_check_task.is_some() {
+            egui::Window::new(LangMessage::InstanceSyncProgress.to_string(lang)).show(
+                ui.ctx(),
+                |ui| {
+                    ui.vertical_centered(|ui| {
+                        ui.label(LangMessage::CheckingFiles.to_string(lang));
+                        ui.spinner();
+                        self.render_cancel_button(ui, lang);
+                    });
+                },
+            );
+        } else if self.is_syncing() {
             egui::Window::new(LangMessage::InstanceSyncProgress.to_string(lang)).show(
                 ui.ctx(),
                 |ui| {
@@ -307,12 +943,25 @@ impl InstanceSyncState {
     }
 
     pub fn cancel_sync(&mut self) {
+        self.pending_sync = None;
+        if let Some(task) = self.deletion_check_task.take() {
+            task.cancel();
+        }
         if let Some(task) = self.instance_sync_task.as_ref() {
             task.cancel();
         }
+        if let Some(task) = self.verify_task.as_ref() {
+            task.cancel();
+        }
+        if let Some(task) = self.repair_task.as_ref() {
+            task.cancel();
+        }
     }
 
     pub fn is_syncing(&self) -> bool {
         self.instance_sync_task.is_some()
+            || self.verify_task.is_some()
+            || self.repair_task.is_some()
+            || self.deletion_check_task.is_some()
     }
 }
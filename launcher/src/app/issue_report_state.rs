@@ -0,0 +1,242 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use shared::java::JavaPackageType;
+use shared::paths::get_logs_dir;
+use tokio::runtime::Runtime;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::config::runtime_config::Config;
+use crate::config::settings_transfer;
+use crate::crash_report;
+use crate::lang::{Lang, LangMessage};
+use crate::launcher::launch;
+use crate::utils;
+
+use super::background_task::{BackgroundTask, BackgroundTaskResult};
+use super::colors;
+use super::diagnostics;
+use super::error_label;
+use super::status_bar::StatusMessage;
+
+const REPORTS_DIR: &str = "reports";
+const MAX_LAUNCHER_LOGS: usize = 3;
+
+struct ReportInput {
+    config_json: String,
+    java_version: Option<String>,
+    java_path: Option<String>,
+    package_type: JavaPackageType,
+    last_error: Option<String>,
+    launcher_dir: PathBuf,
+    minecraft_log_path: Option<PathBuf>,
+}
+
+// the rotating logger writes several files a day, only the most recent ones matter
+fn get_latest_launcher_logs(logs_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(logs_dir) else {
+        return Vec::new();
+    };
+    let mut logs: Vec<_> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.starts_with("launcher") && name.ends_with(".log")
+        })
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .collect();
+    logs.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    logs.into_iter()
+        .take(MAX_LAUNCHER_LOGS)
+        .map(|(_, path)| path)
+        .collect()
+}
+
+async fn get_report_summary(input: &ReportInput) -> String {
+    let mut lines = vec![diagnostics::get_launcher_info()];
+    lines.push(format!("Data directory: {}", input.launcher_dir.display()));
+    match &input.java_version {
+        Some(java_version) => {
+            let java =
+                diagnostics::check_java(java_version, input.package_type, &utils::get_data_dir())
+                    .await
+                    .unwrap_or_else(|e| format!("{:#}", e));
+            lines.push(format!("Java {}: {}", java_version, java));
+            if let Some(java_path) = &input.java_path {
+                lines.push(format!("Selected Java path: {}", java_path));
+            }
+        }
+        None => lines.push("Java: no instance selected".to_string()),
+    }
+    lines.push(format!(
+        "Last error: {}",
+        input.last_error.as_deref().unwrap_or("none")
+    ));
+    lines.join("\n")
+}
+
+async fn create_report(input: ReportInput) -> anyhow::Result<PathBuf> {
+    let data_dir = utils::get_data_dir();
+    let reports_dir = data_dir.join(REPORTS_DIR);
+    std::fs::create_dir_all(&reports_dir)?;
+    let report_path = reports_dir.join(format!("issue_report_{}.zip", utils::get_unix_timestamp()));
+
+    let summary = get_report_summary(&input).await;
+
+    let mut files = get_latest_launcher_logs(&get_logs_dir(&data_dir));
+    files.extend(input.minecraft_log_path);
+    files.push(crash_report::get_crash_report_path());
+
+    let mut zip = ZipWriter::new(std::fs::File::create(&report_path)?);
+    let options = SimpleFileOptions::default();
+    zip.start_file("report.txt", options)?;
+    zip.write_all(summary.as_bytes())?;
+    zip.start_file("config.json", options)?;
+    zip.write_all(input.config_json.as_bytes())?;
+    for path in files {
+        let Ok(content) = std::fs::read(&path) else {
+            continue;
+        };
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        zip.start_file(name.to_string_lossy(), options)?;
+        zip.write_all(&content)?;
+    }
+    zip.finish()?;
+
+    info!("Created issue report {:?}", report_path);
+    Ok(report_path)
+}
+
+pub struct IssueReportState {
+    task: Option<BackgroundTask<anyhow::Result<PathBuf>>>,
+    result: Option<Result<PathBuf, String>>,
+}
+
+impl IssueReportState {
+    pub fn new() -> Self {
+        IssueReportState {
+            task: None,
+            result: None,
+        }
+    }
+
+    fn start(
+        &mut self,
+        ctx: &egui::Context,
+        runtime: &Runtime,
+        config: &Config,
+        java_version: Option<String>,
+        instance_name: Option<&str>,
+        last_error: Option<&StatusMessage>,
+    ) -> anyhow::Result<()> {
+        let input = ReportInput {
+            config_json: serde_json::to_string_pretty(&settings_transfer::get_redacted_config(
+                config,
+            )?)?,
+            java_version,
+            java_path: instance_name.and_then(|name| config.java_paths.get(name).cloned()),
+            package_type: config.java_package_type,
+            last_error: last_error.map(|error| match &error.details {
+                Some(details) => format!("{}\n{}", error.message.to_string(Lang::English), details),
+                None => error.message.to_string(Lang::English),
+            }),
+            launcher_dir: config.get_launcher_dir(),
+            minecraft_log_path: instance_name
+                .map(|name| launch::get_minecraft_log_path(&config.get_launcher_dir(), name)),
+        };
+
+        let ctx = ctx.clone();
+        self.result = None;
+        self.task = Some(BackgroundTask::with_callback(
+            create_report(input),
+            runtime,
+            Box::new(move || ctx.request_repaint()),
+        ));
+        Ok(())
+    }
+
+    fn update(&mut self) {
+        if !self.task.as_ref().is_some_and(|task| task.has_result()) {
+            return;
+        }
+        self.result = match self.task.take().unwrap().take_result() {
+            BackgroundTaskResult::Finished(Ok(path)) => {
+                if let Some(reports_dir) = path.parent() {
+                    if let Err(e) = open::that(reports_dir) {
+                        warn!("Failed to open {:?}:\n{:?}", reports_dir, e);
+                    }
+                }
+                Some(Ok(path))
+            }
+            BackgroundTaskResult::Finished(Err(e)) => Some(Err(format!("{:?}", e))),
+            BackgroundTaskResult::Cancelled => None,
+        };
+    }
+
+    pub fn render_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        runtime: &Runtime,
+        config: &Config,
+        java_version: Option<String>,
+        instance_name: Option<&str>,
+        last_error: Option<&StatusMessage>,
+    ) {
+        self.update();
+
+        let lang = config.lang;
+        let dark_mode = ui.style().visuals.dark_mode;
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    self.task.is_none(),
+                    egui::Button::new(LangMessage::ReportIssue.to_string(lang)),
+                )
+                .clicked()
+            {
+                if let Err(e) = self.start(
+                    ui.ctx(),
+                    runtime,
+                    config,
+                    java_version,
+                    instance_name,
+                    last_error,
+                ) {
+                    self.result = Some(Err(format!("{:?}", e)));
+                }
+            }
+            if self.task.is_some() {
+                ui.spinner();
+            }
+        });
+
+        match &self.result {
+            Some(Ok(path)) => {
+                ui.label(
+                    egui::RichText::new(
+                        LangMessage::IssueReportCreated {
+                            path: path.display().to_string(),
+                        }
+                        .to_string(lang),
+                    )
+                    .color(colors::ok(dark_mode)),
+                );
+            }
+            Some(Err(e)) => {
+                error_label::render(
+                    ui,
+                    egui::RichText::new(LangMessage::IssueReportError.to_string(lang))
+                        .color(colors::error(dark_mode)),
+                    Some(e),
+                    lang,
+                );
+            }
+            None => {}
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use egui::RichText;
-use log::error;
+use log::{error, warn};
 use shared::paths::get_java_dir;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::runtime::Runtime;
@@ -10,51 +11,91 @@ use crate::lang::{Lang, LangMessage};
 use crate::utils;
 use crate::version::complete_version_metadata::CompleteVersionMetadata;
 
-use shared::java;
+use shared::java::{self, JavaPackage, JavaPackageType};
 use shared::progress::{ProgressBar, Unit};
 
 use super::background_task::{BackgroundTask, BackgroundTaskResult};
 use super::colors;
+use super::error_label;
 use super::progress_bar::GuiProgressBar;
+use super::status_bar::{Severity, StatusMessage};
 
 #[derive(Clone, PartialEq)]
 pub enum JavaDownloadStatus {
     CheckingJava,
     NotDownloaded,
     Downloaded,
-    UnknownDownloadError,
+    UnknownDownloadError(String),
     DownloadErrorOffline,
+    DownloadErrorDiskSpace {
+        required: u64,
+        available: u64,
+    },
+    DownloadErrorUnavailable {
+        required_version: String,
+        error: String,
+    },
+    VerificationFailed(String),
+}
+
+fn get_platform_name() -> String {
+    format!("{} {}", std::env::consts::OS, std::env::consts::ARCH)
 }
 
 struct JavaCheckResult {
     java_path: Option<PathBuf>,
+    verification_error: Option<String>,
+}
+
+async fn find_java(
+    java_version: &str,
+    package_type: JavaPackageType,
+    java_dir: &Path,
+    existing_path: Option<String>,
+) -> Option<PathBuf> {
+    if let Some(path) = existing_path {
+        let path = PathBuf::from(path);
+        let package_matches = package_type != JavaPackageType::Jdk || java::is_jdk(&path);
+        if package_matches && java::check_java(java_version, &path).await {
+            return Some(path);
+        }
+    }
+    java::get_java(java_version, package_type, java_dir)
+        .await
+        .map(|j| j.path)
 }
 
 fn check_java(
     runtime: &Runtime,
     java_version: &str,
+    package_type: JavaPackageType,
     java_dir: &Path,
     existing_path: Option<&str>,
+    verified_paths: &HashSet<PathBuf>,
     ctx: &egui::Context,
 ) -> BackgroundTask<JavaCheckResult> {
     let java_version = java_version.to_string();
     let java_dir = java_dir.to_path_buf();
     let existing_path = existing_path.map(|s| s.to_string());
+    let verified_paths = verified_paths.clone();
     let ctx = ctx.clone();
 
     let fut = async move {
-        if let Some(path) = existing_path {
-            let path = PathBuf::from(path);
-            if java::check_java(&java_version, &path).await {
+        let java_path = find_java(&java_version, package_type, &java_dir, existing_path).await;
+        // a Java that wasn't started yet this session gets a smoke test before the game relies on it
+        if let Some(path) = java_path.as_ref().filter(|x| !verified_paths.contains(*x)) {
+            if let Err(e) = java::smoke_test_java(path).await {
+                warn!("Java verification failed:\n{:?}", e);
                 return JavaCheckResult {
-                    java_path: Some(path),
+                    java_path: None,
+                    verification_error: Some(format!("{:?}", e)),
                 };
             }
         }
-        let java_path = java::get_java(&java_version, &java_dir)
-            .await
-            .map(|j| j.path);
-        JavaCheckResult { java_path }
+        JavaCheckResult {
+            java_path,
+            verification_error: None,
+        }
     };
 
     BackgroundTask::with_callback(
@@ -74,7 +115,10 @@ struct JavaDownloadResult {
 fn download_java(
     runtime: &Runtime,
     required_version: &str,
+    package_type: JavaPackageType,
+    package: Option<JavaPackage>,
     java_dir: &Path,
+    max_rate_kbps: Option<u64>,
     progress_bar: Arc<dyn ProgressBar<LangMessage>>,
 ) -> BackgroundTask<JavaDownloadResult> {
     let progress_bar_clone = progress_bar.clone();
@@ -82,18 +126,46 @@ fn download_java(
     let java_dir = java_dir.to_path_buf();
     let fut = async move {
         progress_bar_clone.set_message(LangMessage::DownloadingJava);
-        let result = java::download_java(&required_version, &java_dir, progress_bar_clone).await;
+        let result = java::download_java(
+            &required_version,
+            package_type,
+            package.as_ref(),
+            &java_dir,
+            max_rate_kbps,
+            progress_bar_clone,
+        )
+        .await;
+        let result = match result {
+            Ok(java_installation) => java::smoke_test_java(&java_installation.path)
+                .await
+                .map(|_| java_installation),
+            Err(e) => Err(e),
+        };
         match result {
             Ok(java_installation) => JavaDownloadResult {
                 status: JavaDownloadStatus::Downloaded,
                 java_installation: Some(java_installation),
             },
             Err(e) => JavaDownloadResult {
-                status: if utils::is_connect_error(&e) {
+                status: if let Some((required, available)) = utils::get_disk_space_error(&e) {
+                    JavaDownloadStatus::DownloadErrorDiskSpace {
+                        required,
+                        available,
+                    }
+                } else if utils::is_connect_error(&e) {
                     JavaDownloadStatus::DownloadErrorOffline
+                } else if java::is_unavailable_for_platform(&e) {
+                    warn!(
+                        "Java {} is not available for this platform: {}",
+                        required_version, e
+                    );
+                    JavaDownloadStatus::DownloadErrorUnavailable {
+                        required_version: required_version.clone(),
+                        error: format!("{:?}", e),
+                    }
                 } else {
                     error!("Error downloading Java:\n{:?}", e);
-                    JavaDownloadStatus::UnknownDownloadError
+                    JavaDownloadStatus::UnknownDownloadError(format!("{:?}", e))
                 },
                 java_installation: None,
             },
@@ -114,7 +186,13 @@ pub struct JavaState {
     check_java_task: Option<BackgroundTask<JavaCheckResult>>,
     java_download_task: Option<BackgroundTask<JavaDownloadResult>>,
     java_download_progress_bar: Arc<GuiProgressBar>,
+    downloaded_path: Option<PathBuf>,
+    verified_java_paths: HashSet<PathBuf>,
     settings_opened: bool,
+
+    package_list_key: Option<(String, JavaPackageType)>,
+    package_list_task: Option<BackgroundTask<anyhow::Result<Vec<JavaPackage>>>>,
+    package_list: Option<Result<Vec<JavaPackage>, String>>,
 }
 
 impl JavaState {
@@ -129,7 +207,13 @@ impl JavaState {
             check_java_task: None,
             java_download_task: None,
             java_download_progress_bar,
+            downloaded_path: None,
+            verified_java_paths: HashSet::new(),
             settings_opened: false,
+
+            package_list_key: None,
+            package_list_task: None,
+            package_list: None,
         }
     }
 
@@ -144,10 +228,14 @@ impl JavaState {
 
         self.java_download_progress_bar.reset();
 
+        let java_version = metadata.get_java_version();
         self.java_download_task = Some(download_java(
             runtime,
-            &metadata.get_java_version(),
+            &java_version,
+            config.java_package_type,
+            config.java_packages.get(&java_version).cloned(),
             &java_dir,
+            config.max_download_rate_kbps,
             self.java_download_progress_bar.clone(),
         ));
     }
@@ -166,11 +254,13 @@ impl JavaState {
         self.check_java_task = Some(check_java(
             runtime,
             &metadata.get_java_version(),
+            config.java_package_type,
             &java_dir,
             config
                 .java_paths
                 .get(metadata.get_name())
                 .map(|s| s.as_str()),
+            &self.verified_java_paths,
             ctx,
         ));
 
@@ -201,7 +291,13 @@ impl JavaState {
                                 java_path.to_string_lossy().to_string(),
                             );
                             config.save();
+                            self.verified_java_paths.insert(java_path);
                             self.status = JavaDownloadStatus::Downloaded;
+                        } else if let Some(e) = result.verification_error {
+                            config.java_paths.remove(metadata.get_name());
+                            config.save();
+                            self.status = JavaDownloadStatus::VerificationFailed(e);
+                            self.schedule_download(runtime, metadata, config);
                         } else {
                             config.java_paths.remove(metadata.get_name());
                             config.save();
@@ -231,6 +327,8 @@ impl JavaState {
                                 path.to_string_lossy().to_string(),
                             );
                             config.save();
+                            self.verified_java_paths.insert(path.clone());
+                            self.downloaded_path = Some(path);
                         }
                     }
                     BackgroundTaskResult::Cancelled => {
@@ -248,8 +346,11 @@ impl JavaState {
         matches!(
             self.status,
             JavaDownloadStatus::NotDownloaded
-                | JavaDownloadStatus::UnknownDownloadError
+                | JavaDownloadStatus::UnknownDownloadError(_)
                 | JavaDownloadStatus::DownloadErrorOffline
+                | JavaDownloadStatus::DownloadErrorDiskSpace { .. }
+                | JavaDownloadStatus::DownloadErrorUnavailable { .. }
+                | JavaDownloadStatus::VerificationFailed(_)
         )
     }
 
@@ -264,6 +365,254 @@ impl JavaState {
         }
     }
 
+    pub fn recheck(&mut self) {
+        self.status = JavaDownloadStatus::CheckingJava;
+        self.check_java_task = None;
+    }
+
+    pub fn take_downloaded_path(&mut self) -> Option<PathBuf> {
+        self.downloaded_path.take()
+    }
+
+    fn get_error_text(&self, lang: Lang, dark_mode: bool) -> Option<RichText> {
+        Some(match &self.status {
+            JavaDownloadStatus::UnknownDownloadError(_) => {
+                RichText::new(LangMessage::UnknownErrorDownloadingJava.to_string(lang))
+                    .color(colors::error(dark_mode))
+            }
+            JavaDownloadStatus::DownloadErrorOffline => {
+                RichText::new(LangMessage::NoConnectionToJavaServer.to_string(lang))
+                    .color(colors::offline(dark_mode))
+            }
+            JavaDownloadStatus::VerificationFailed(_) => {
+                RichText::new(LangMessage::JavaVerificationFailed.to_string(lang))
+                    .color(colors::partial_error(dark_mode))
+            }
+            JavaDownloadStatus::DownloadErrorUnavailable {
+                required_version, ..
+            } => RichText::new(
+                LangMessage::JavaUnavailableForPlatform {
+                    required_version: required_version.clone(),
+                    platform: get_platform_name(),
+                }
+                .to_string(lang),
+            )
+            .color(colors::error(dark_mode)),
+            JavaDownloadStatus::DownloadErrorDiskSpace {
+                required,
+                available,
+            } => RichText::new(
+                LangMessage::NotEnoughDiskSpace {
+                    required: *required,
+                    available: *available,
+                }
+                .to_string(lang),
+            )
+            .color(colors::error(dark_mode)),
+            _ => return None,
+        })
+    }
+
+    pub fn get_status_message(&self) -> Option<StatusMessage> {
+        Some(match &self.status {
+            JavaDownloadStatus::UnknownDownloadError(e) => StatusMessage::new(
+                Severity::Error,
+                LangMessage::UnknownErrorDownloadingJava,
+                Some(e),
+            ),
+            JavaDownloadStatus::DownloadErrorOffline => StatusMessage::new(
+                Severity::Offline,
+                LangMessage::NoConnectionToJavaServer,
+                None,
+            ),
+            JavaDownloadStatus::VerificationFailed(e) => StatusMessage::new(
+                Severity::Warning,
+                LangMessage::JavaVerificationFailed,
+                Some(e),
+            ),
+            JavaDownloadStatus::DownloadErrorUnavailable {
+                required_version,
+                error,
+            } => StatusMessage::new(
+                Severity::Error,
+                LangMessage::JavaUnavailableForPlatform {
+                    required_version: required_version.clone(),
+                    platform: get_platform_name(),
+                },
+                Some(error),
+            ),
+            JavaDownloadStatus::DownloadErrorDiskSpace {
+                required,
+                available,
+            } => StatusMessage::new(
+                Severity::Error,
+                LangMessage::NotEnoughDiskSpace {
+                    required: *required,
+                    available: *available,
+                },
+                None,
+            ),
+            _ => return None,
+        })
+    }
+
+    fn get_error(&self) -> Option<&str> {
+        match &self.status {
+            JavaDownloadStatus::UnknownDownloadError(e)
+            | JavaDownloadStatus::DownloadErrorUnavailable { error: e, .. }
+            | JavaDownloadStatus::VerificationFailed(e) => Some(e.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn render_download_button(
+        &mut self,
+        ui: &mut egui::Ui,
+        runtime: &Runtime,
+        config: &mut Config,
+        selected_metadata: Option<&CompleteVersionMetadata>,
+    ) {
+        let lang = config.lang;
+        let dark_mode = ui.style().visuals.dark_mode;
+
+        ui.horizontal(|ui| {
+            let button = egui::Button::new(LangMessage::DownloadJava.to_string(lang));
+            if ui
+                .add_enabled(
+                    selected_metadata.is_some() && self.java_download_task.is_none(),
+                    button,
+                )
+                .clicked()
+            {
+                if let Some(selected_metadata) = selected_metadata {
+                    self.schedule_download(runtime, selected_metadata, config);
+                }
+            }
+
+            if self.java_download_task.is_some() {
+                ui.spinner();
+                ui.label(LangMessage::DownloadingJava.to_string(lang));
+            } else if let Some(text) = self.get_error_text(lang, dark_mode) {
+                error_label::render(ui, text, self.get_error(), lang);
+            }
+        });
+    }
+
+    fn update_package_list(&mut self, java_version: &str, package_type: JavaPackageType) {
+        let key = (java_version.to_string(), package_type);
+        if self.package_list_key.as_ref() != Some(&key) {
+            self.package_list_key = Some(key);
+            self.package_list_task = None;
+            self.package_list = None;
+        }
+
+        if !self
+            .package_list_task
+            .as_ref()
+            .is_some_and(|task| task.has_result())
+        {
+            return;
+        }
+        self.package_list = match self.package_list_task.take().unwrap().take_result() {
+            BackgroundTaskResult::Finished(Ok(packages)) => Some(Ok(packages)),
+            BackgroundTaskResult::Finished(Err(e)) => {
+                warn!("Failed to list Java builds:\n{:?}", e);
+                Some(Err(format!("{:?}", e)))
+            }
+            BackgroundTaskResult::Cancelled => None,
+        };
+    }
+
+    pub fn render_package_picker(
+        &mut self,
+        ui: &mut egui::Ui,
+        runtime: &Runtime,
+        config: &mut Config,
+        selected_metadata: Option<&CompleteVersionMetadata>,
+    ) {
+        let Some(selected_metadata) = selected_metadata else {
+            return;
+        };
+        let lang = config.lang;
+        let dark_mode = ui.style().visuals.dark_mode;
+        let java_version = selected_metadata.get_java_version();
+        let package_type = config.java_package_type;
+        self.update_package_list(&java_version, package_type);
+
+        let selected = config.java_packages.get(&java_version).cloned();
+        let selected_text = selected
+            .as_ref()
+            .map_or(LangMessage::LatestJavaBuild.to_string(lang), |package| {
+                package.name.clone()
+            });
+
+        ui.horizontal(|ui| {
+            ui.label(LangMessage::JavaBuild.to_string(lang));
+            let Some(Ok(packages)) = &self.package_list else {
+                ui.label(selected_text);
+                if ui
+                    .add_enabled(
+                        self.package_list_task.is_none(),
+                        egui::Button::new(LangMessage::ListJavaBuilds.to_string(lang)),
+                    )
+                    .clicked()
+                {
+                    let ctx = ui.ctx().clone();
+                    let java_version = java_version.clone();
+                    self.package_list = None;
+                    self.package_list_task = Some(BackgroundTask::with_callback(
+                        async move { java::list_java_packages(&java_version, package_type).await },
+                        runtime,
+                        Box::new(move || ctx.request_repaint()),
+                    ));
+                }
+                if self.package_list_task.is_some() {
+                    ui.spinner();
+                }
+                return;
+            };
+
+            let mut new_selected = selected.clone();
+            egui::ComboBox::from_id_salt("java_packages")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(
+                        &mut new_selected,
+                        None,
+                        LangMessage::LatestJavaBuild.to_string(lang),
+                    );
+                    for package in packages {
+                        ui.selectable_value(
+                            &mut new_selected,
+                            Some(package.clone()),
+                            package.name.as_str(),
+                        );
+                    }
+                });
+            if new_selected != selected {
+                match new_selected {
+                    Some(package) => {
+                        config.java_packages.insert(java_version.clone(), package);
+                    }
+                    None => {
+                        config.java_packages.remove(&java_version);
+                    }
+                }
+                config.save();
+            }
+        });
+
+        if let Some(Err(e)) = &self.package_list {
+            error_label::render(
+                ui,
+                RichText::new(LangMessage::ListJavaBuildsError.to_string(lang))
+                    .color(colors::error(dark_mode)),
+                Some(e),
+                lang,
+            );
+        }
+    }
+
     pub fn render_ui(
         &mut self,
         ui: &mut egui::Ui,
@@ -273,8 +622,8 @@ impl JavaState {
         let lang = config.lang;
         let dark_mode = ui.style().visuals.dark_mode;
 
-        ui.label(if let Some(selected_metadata) = selected_metadata {
-            match self.status {
+        let text = if let Some(selected_metadata) = selected_metadata {
+            match &self.status {
                 JavaDownloadStatus::CheckingJava => {
                     RichText::new(LangMessage::CheckingJava.to_string(lang))
                         .color(colors::in_progress(dark_mode))
@@ -293,13 +642,12 @@ impl JavaState {
                             .color(colors::in_progress(dark_mode))
                     }
                 }
-                JavaDownloadStatus::UnknownDownloadError => {
-                    RichText::new(LangMessage::UnknownErrorDownloadingJava.to_string(lang))
-                        .color(colors::error(dark_mode))
-                }
-                JavaDownloadStatus::DownloadErrorOffline => {
-                    RichText::new(LangMessage::NoConnectionToJavaServer.to_string(lang))
-                        .color(colors::offline(dark_mode))
+                JavaDownloadStatus::UnknownDownloadError(_)
+                | JavaDownloadStatus::DownloadErrorOffline
+                | JavaDownloadStatus::DownloadErrorDiskSpace { .. }
+                | JavaDownloadStatus::DownloadErrorUnavailable { .. }
+                | JavaDownloadStatus::VerificationFailed(_) => {
+                    self.get_error_text(lang, dark_mode).unwrap()
                 }
                 JavaDownloadStatus::Downloaded => RichText::new(
                     LangMessage::JavaInstalled {
@@ -312,7 +660,9 @@ impl JavaState {
         } else {
             RichText::new(LangMessage::UnknownJavaVersion.to_string(lang))
                 .color(colors::in_progress(dark_mode))
-        });
+        };
+        let error = selected_metadata.and(self.get_error());
+        error_label::render(ui, text, error, lang);
 
         self.render_progress_bar_window(ui, lang);
     }
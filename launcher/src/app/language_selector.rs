@@ -15,16 +15,13 @@ impl LanguageSelector {
         egui::ComboBox::from_id_salt("language_selector")
             .selected_text("🌐 ".to_string() + &LangMessage::LanguageName.to_string(config.lang))
             .show_ui(ui, |ui| {
-                ui.selectable_value(
-                    &mut lang,
-                    Lang::English,
-                    LangMessage::LanguageName.to_string(Lang::English),
-                );
-                ui.selectable_value(
-                    &mut lang,
-                    Lang::Russian,
-                    LangMessage::LanguageName.to_string(Lang::Russian),
-                );
+                for option in Lang::ALL {
+                    ui.selectable_value(
+                        &mut lang,
+                        option,
+                        LangMessage::LanguageName.to_string(option),
+                    );
+                }
             });
         if lang != config.lang {
             config.lang = lang;
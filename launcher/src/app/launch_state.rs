@@ -1,30 +1,67 @@
 use std::{
+    collections::HashMap,
     process::{exit, ExitStatus},
     sync::Arc,
+    time::{Duration, Instant},
 };
 
-use log::error;
+use log::{error, warn};
+use shared::command;
 use shared::paths::get_logs_dir;
 use tokio::{process::Child, runtime::Runtime, sync::Mutex};
 
+use super::background_task::{BackgroundTask, BackgroundTaskResult};
+use super::error_label;
+use super::game_output::GameOutput;
+use super::status_bar::{Severity, StatusMessage};
 use crate::{
-    auth::user_info::AuthData, config::runtime_config::Config, lang::LangMessage, launcher::launch,
+    auth::user_info::AuthData,
+    config::runtime_config::Config,
+    lang::LangMessage,
+    launcher::discord::DiscordPresence,
+    launcher::instance_lock::{InstanceLock, InstanceLockError},
+    launcher::launch::{self, LaunchError},
+    utils,
     version::complete_version_metadata::CompleteVersionMetadata,
 };
 
+// a non-zero exit this soon after the launch means the game never started
+const STARTUP_CRASH_TIME: Duration = Duration::from_secs(15);
+
+// the instance is locked while the pre-launch command runs, the game starts once it succeeds
+struct PendingLaunch {
+    instance: Arc<CompleteVersionMetadata>,
+    auth_data: AuthData,
+    online: bool,
+    pre_launch_task: BackgroundTask<anyhow::Result<()>>,
+    lock: InstanceLock,
+}
+
+struct RunningInstance {
+    child: Arc<Mutex<Child>>,
+    started_at: Instant,
+    // resolves to the exit status and the moment the process exited
+    watcher_handle: tokio::task::JoinHandle<(ExitStatus, Instant)>,
+    _lock: InstanceLock,
+    _discord_presence: Option<DiscordPresence>,
+}
+
 enum LauncherStatus {
     NotLaunched,
-    Running { child: Arc<Mutex<Child>> },
-    Error,
+    PreLaunch(PendingLaunch),
+    Running(RunningInstance),
+    AlreadyRunning,
+    Error(String),
     ProcessErrorCode(String),
+    CrashedOnStartup { code: String, output_tail: String },
 }
 
 pub struct LaunchState {
-    status: LauncherStatus,
+    statuses: HashMap<String, LauncherStatus>,
     force_launch: bool,
     launch_from_start: bool,
     ctx: egui::Context,
-    watcher_handle: Option<tokio::task::JoinHandle<ExitStatus>>,
+    game_outputs: HashMap<String, GameOutput>,
 }
 
 pub enum ForceLaunchResultSelect {
@@ -41,30 +78,24 @@ pub struct RenderUiParams {
 impl LaunchState {
     pub fn new(launch_from_start: bool, ctx: egui::Context) -> Self {
         LaunchState {
-            status: LauncherStatus::NotLaunched,
+            statuses: HashMap::new(),
             force_launch: false,
             launch_from_start,
             ctx,
-            watcher_handle: None,
+            game_outputs: HashMap::new(),
         }
     }
 
-    async fn child_watcher(child: Arc<Mutex<Child>>, ctx: egui::Context) -> ExitStatus {
+    async fn child_watcher(child: Arc<Mutex<Child>>, ctx: egui::Context) -> (ExitStatus, Instant) {
         loop {
             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
             let result = child.lock().await.try_wait();
             match result {
                 Ok(Some(status)) => {
-                    if cfg!(windows) {
-                        ctx.send_viewport_cmd(egui::ViewportCommand::Decorations(true));
-                        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(
-                            [670.0, 450.0].into(),
-                        ));
-                    } else {
-                        ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
-                    }
+                    let exited_at = Instant::now();
+                    utils::show_window(&ctx);
                     ctx.request_repaint();
-                    return status;
+                    return (status, exited_at);
                 }
                 Ok(None) => {}
                 Err(_) => {
@@ -77,53 +108,266 @@ impl LaunchState {
     fn launch(
         &mut self,
         runtime: &Runtime,
-        config: &Config,
+        config: &mut Config,
+        selected_instance: Arc<CompleteVersionMetadata>,
+        auth_data: &AuthData,
+        online: bool,
+    ) {
+        let instance_name = selected_instance.get_name().to_string();
+        let instance_dir = config.get_game_dir(&instance_name);
+        let lock = match InstanceLock::acquire(&instance_dir, &instance_name) {
+            Ok(lock) => lock,
+            Err(e) => {
+                let status = if e.downcast_ref::<InstanceLockError>().is_some() {
+                    warn!("{}", e);
+                    LauncherStatus::AlreadyRunning
+                } else {
+                    error!("Error locking instance directory:\n{:?}", e);
+                    LauncherStatus::Error(format!("{:?}", e))
+                };
+                self.statuses.insert(instance_name, status);
+                return;
+            }
+        };
+
+        let instance_settings = config.get_instance_settings(&instance_name);
+        if instance_settings.pre_launch_command.trim().is_empty() {
+            self.start_game(runtime, config, &selected_instance, auth_data, online, lock);
+            return;
+        }
+        let ctx = self.ctx.clone();
+        let pre_launch_task = BackgroundTask::with_callback(
+            async move {
+                command::exec_command(
+                    &instance_settings.pre_launch_command,
+                    Some(&instance_dir),
+                    &instance_settings.env_vars,
+                )
+                .await
+            },
+            runtime,
+            Box::new(move || ctx.request_repaint()),
+        );
+        self.statuses.insert(
+            instance_name,
+            LauncherStatus::PreLaunch(PendingLaunch {
+                instance: selected_instance,
+                auth_data: auth_data.clone(),
+                online,
+                pre_launch_task,
+                lock,
+            }),
+        );
+    }
+
+    fn start_game(
+        &mut self,
+        runtime: &Runtime,
+        config: &mut Config,
         selected_instance: &CompleteVersionMetadata,
         auth_data: &AuthData,
         online: bool,
+        mut lock: InstanceLock,
     ) {
+        let instance_name = selected_instance.get_name().to_string();
+        let instance_dir = config.get_game_dir(&instance_name);
         match runtime.block_on(launch::launch(selected_instance, config, auth_data, online)) {
-            Ok(child) => {
+            Ok(mut child) => {
+                let started_at = Instant::now();
+                if let Some(pid) = child.id() {
+                    if let Err(e) = lock.record_process(pid) {
+                        warn!(
+                            "Failed to record the game process in the instance lock:\n{:?}",
+                            e
+                        );
+                    }
+                }
+                config
+                    .get_instance_settings_mut(&instance_name)
+                    .last_launched = Some(utils::get_unix_timestamp());
+                config.add_recent_instance(&instance_name);
+
+                let log_path =
+                    launch::get_minecraft_log_path(&config.get_launcher_dir(), &instance_name);
+                if let Err(e) = self
+                    .game_outputs
+                    .entry(instance_name.clone())
+                    .or_insert_with(GameOutput::new)
+                    .capture(runtime, &mut child, &log_path, &self.ctx)
+                {
+                    error!("Error capturing Minecraft output:\n{:?}", e);
+                }
+
                 let arc_child = Arc::new(Mutex::new(child));
                 if config.hide_launcher_after_launch {
-                    if cfg!(windows) {
-                        self.ctx
-                            .send_viewport_cmd(egui::ViewportCommand::Decorations(false));
-                        self.ctx
-                            .send_viewport_cmd(egui::ViewportCommand::InnerSize([0.0, 0.0].into()));
-                    } else {
-                        self.ctx
-                            .send_viewport_cmd(egui::ViewportCommand::Visible(false));
+                    utils::hide_window(&self.ctx);
+                }
+                let mut discord_presence = None;
+                if config.discord_rich_presence {
+                    match DiscordPresence::start(&instance_name, selected_instance.get_parent_id())
+                    {
+                        Ok(presence) => discord_presence = Some(presence),
+                        Err(e) => warn!("Failed to set Discord presence:\n{:?}", e),
                     }
                 }
-                self.watcher_handle =
-                    Some(runtime.spawn(Self::child_watcher(arc_child.clone(), self.ctx.clone())));
-                self.status = LauncherStatus::Running {
-                    child: arc_child.clone(),
-                };
+
+                let instance_settings = config.get_instance_settings(&instance_name);
+                let watcher = Self::child_watcher(arc_child.clone(), self.ctx.clone());
+                let watcher_handle = runtime.spawn(async move {
+                    let exit = watcher.await;
+                    if !instance_settings.post_exit_command.trim().is_empty() {
+                        if let Err(e) = command::exec_command(
+                            &instance_settings.post_exit_command,
+                            Some(&instance_dir),
+                            &instance_settings.env_vars,
+                        )
+                        .await
+                        {
+                            error!("Error running post-exit command:\n{:?}", e);
+                        }
+                    }
+                    exit
+                });
+                self.statuses.insert(
+                    instance_name,
+                    LauncherStatus::Running(RunningInstance {
+                        child: arc_child,
+                        started_at,
+                        watcher_handle,
+                        _lock: lock,
+                        _discord_presence: discord_presence,
+                    }),
+                );
             }
             Err(e) => {
                 error!("Error launching Minecraft:\n{:?}", e);
-                self.status = LauncherStatus::Error;
+                self.statuses
+                    .insert(instance_name, LauncherStatus::Error(format!("{:?}", e)));
             }
         }
     }
 
-    pub fn update(&mut self, runtime: &Runtime, config: &Config) {
-        match self.watcher_handle.take_if(|handle| handle.is_finished()) {
-            None => {}
-            Some(handle) => {
-                let exit_status = runtime.block_on(handle).unwrap_or_default();
-                if exit_status.success() {
-                    if config.hide_launcher_after_launch {
-                        exit(0);
-                    }
-                    self.status = LauncherStatus::NotLaunched;
-                } else {
-                    self.status = LauncherStatus::ProcessErrorCode(
-                        exit_status.code().unwrap_or(-1).to_string(),
-                    );
+    pub fn is_running(&self, instance_name: &str) -> bool {
+        matches!(
+            self.statuses.get(instance_name),
+            Some(LauncherStatus::PreLaunch(_) | LauncherStatus::Running(_))
+        )
+    }
+
+    fn any_running(&self) -> bool {
+        self.statuses
+            .values()
+            .any(|status| matches!(status, LauncherStatus::Running(_)))
+    }
+
+    pub fn get_status_message(&self, instance_name: &str) -> Option<StatusMessage> {
+        Some(match self.statuses.get(instance_name)? {
+            LauncherStatus::AlreadyRunning => {
+                StatusMessage::new(Severity::Warning, LangMessage::InstanceAlreadyRunning, None)
+            }
+            LauncherStatus::Error(e) => {
+                StatusMessage::new(Severity::Error, LangMessage::LaunchError, Some(e))
+            }
+            LauncherStatus::ProcessErrorCode(code) => StatusMessage::new(
+                Severity::Error,
+                LangMessage::ProcessErrorCode(code.clone()),
+                None,
+            ),
+            LauncherStatus::CrashedOnStartup { code, output_tail } => StatusMessage::new(
+                Severity::Error,
+                LangMessage::GameCrashedOnStartup(code.clone()),
+                Some(output_tail),
+            ),
+            _ => return None,
+        })
+    }
+
+    /// Launch as soon as the selected instance is ready, same as `--launch`
+    pub fn request_launch(&mut self) {
+        self.launch_from_start = true;
+    }
+
+    pub fn cancel_force_launch(&mut self) {
+        self.force_launch = false;
+        self.launch_from_start = false;
+    }
+
+    fn update_pre_launch(&mut self, runtime: &Runtime, config: &mut Config) {
+        let finished: Vec<String> = self
+            .statuses
+            .iter()
+            .filter(|(_, status)| {
+                matches!(status, LauncherStatus::PreLaunch(pending) if pending.pre_launch_task.has_result())
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        for instance_name in finished {
+            let Some(LauncherStatus::PreLaunch(pending)) = self.statuses.remove(&instance_name)
+            else {
+                continue;
+            };
+            match pending.pre_launch_task.take_result() {
+                BackgroundTaskResult::Finished(Ok(())) => self.start_game(
+                    runtime,
+                    config,
+                    &pending.instance,
+                    &pending.auth_data,
+                    pending.online,
+                    pending.lock,
+                ),
+                BackgroundTaskResult::Finished(Err(e)) => {
+                    let e = LaunchError::PreLaunchCommandFailed(e);
+                    error!("Error launching Minecraft:\n{:?}", e);
+                    self.statuses
+                        .insert(instance_name, LauncherStatus::Error(format!("{:?}", e)));
                 }
+                BackgroundTaskResult::Cancelled => {}
+            }
+        }
+    }
+
+    pub fn update(&mut self, runtime: &Runtime, config: &mut Config) {
+        self.update_pre_launch(runtime, config);
+
+        let finished: Vec<String> = self
+            .statuses
+            .iter()
+            .filter(|(_, status)| {
+                matches!(status, LauncherStatus::Running(running) if running.watcher_handle.is_finished())
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        for instance_name in finished {
+            let Some(LauncherStatus::Running(running)) = self.statuses.remove(&instance_name)
+            else {
+                continue;
+            };
+            let (exit_status, exited_at) = runtime
+                .block_on(running.watcher_handle)
+                .unwrap_or_else(|_| (ExitStatus::default(), Instant::now()));
+            let run_time = exited_at.duration_since(running.started_at);
+            let code = exit_status.code().unwrap_or(-1).to_string();
+            let status = if exit_status.success() {
+                LauncherStatus::NotLaunched
+            } else if run_time < STARTUP_CRASH_TIME {
+                let output_tail = self
+                    .game_outputs
+                    .get(&instance_name)
+                    .map(|game_output| game_output.get_tail())
+                    .unwrap_or_default();
+                warn!(
+                    "Minecraft crashed on startup with code {}:\n{}",
+                    code, output_tail
+                );
+                LauncherStatus::CrashedOnStartup { code, output_tail }
+            } else {
+                LauncherStatus::ProcessErrorCode(code)
+            };
+            self.statuses.insert(instance_name, status);
+            if exit_status.success() && config.hide_launcher_after_launch && !self.any_running() {
+                exit(0);
             }
         }
     }
@@ -148,17 +392,35 @@ impl LaunchState {
         let RenderUiParams { online, disabled } = params;
 
         let lang = config.lang;
+        let instance_name = selected_instance
+            .as_ref()
+            .map(|instance| instance.get_name().to_string());
+        let status = instance_name
+            .as_ref()
+            .and_then(|name| self.statuses.get(name));
+        let game_output = instance_name
+            .as_ref()
+            .and_then(|name| self.game_outputs.get_mut(name));
 
-        match &mut self.status {
-            LauncherStatus::Running { child } => {
+        match status {
+            Some(LauncherStatus::PreLaunch(_)) => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(LangMessage::RunningPreLaunchCommand.to_string(lang));
+                });
+            }
+            Some(LauncherStatus::Running(running)) => {
                 ui.label(LangMessage::Running.to_string(lang));
                 if ui
                     .button(LangMessage::KillMinecraft.to_string(lang))
                     .clicked()
                 {
-                    let mut child_lock = runtime.block_on(child.lock());
+                    let mut child_lock = runtime.block_on(running.child.lock());
                     let _ = runtime.block_on(child_lock.kill());
                 }
+                if let Some(game_output) = game_output {
+                    game_output.render_button(ui, lang);
+                }
             }
             _ => {
                 let button_text = if online {
@@ -181,7 +443,7 @@ impl LaunchState {
                         self.launch(
                             runtime,
                             config,
-                            &selected_instance.unwrap(),
+                            selected_instance.unwrap(),
                             &auth_data.unwrap(),
                             online,
                         );
@@ -190,18 +452,54 @@ impl LaunchState {
             }
         }
 
-        match &self.status {
-            LauncherStatus::Error => {
-                ui.label(LangMessage::LaunchError.to_string(lang));
+        let Some(instance_name) = instance_name else {
+            return;
+        };
+        let game_output = self.game_outputs.get_mut(&instance_name);
+        match self.statuses.get(&instance_name) {
+            Some(LauncherStatus::AlreadyRunning) => {
+                ui.label(LangMessage::InstanceAlreadyRunning.to_string(lang));
+            }
+            Some(LauncherStatus::Error(e)) => {
+                error_label::render(ui, LangMessage::LaunchError.to_string(lang), Some(e), lang);
             }
-            LauncherStatus::ProcessErrorCode(e) => {
+            Some(LauncherStatus::ProcessErrorCode(e)) => {
                 ui.label(LangMessage::ProcessErrorCode(e.clone()).to_string(lang));
                 if ui.button(LangMessage::OpenLogs.to_string(lang)).clicked() {
                     open::that(get_logs_dir(&config.get_launcher_dir())).unwrap();
                 }
+                if let Some(game_output) = game_output {
+                    game_output.render_button(ui, lang);
+                }
+            }
+            Some(LauncherStatus::CrashedOnStartup { code, output_tail }) => {
+                error_label::render(
+                    ui,
+                    LangMessage::GameCrashedOnStartup(code.clone()).to_string(lang),
+                    Some(output_tail),
+                    lang,
+                );
+                if !output_tail.is_empty() {
+                    egui::ScrollArea::vertical()
+                        .max_height(150.0)
+                        .stick_to_bottom(true)
+                        .show(ui, |ui| {
+                            ui.label(egui::RichText::new(output_tail).monospace());
+                        });
+                }
+                if ui.button(LangMessage::OpenLogs.to_string(lang)).clicked() {
+                    open::that(get_logs_dir(&config.get_launcher_dir())).unwrap();
+                }
+                if let Some(game_output) = game_output {
+                    game_output.render_button(ui, lang);
+                }
             }
             _ => {}
         }
+
+        if let Some(game_output) = self.game_outputs.get_mut(&instance_name) {
+            game_output.render_window(&self.ctx, lang);
+        }
     }
 
     pub fn render_download_ui(
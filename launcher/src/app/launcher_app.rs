@@ -1,10 +1,15 @@
 use std::collections::HashSet;
+use std::path::PathBuf;
 
 use eframe::egui;
 use eframe::run_native;
+use log::{error, warn};
 use tokio::runtime::Runtime;
 
 use super::auth_state::AuthState;
+use super::colors;
+use super::crash_report_state::CrashReportState;
+use super::first_run_wizard::{FirstRunWizard, WizardEvent, WizardParams};
 use super::instance_sync_state::InstanceSyncState;
 use super::java_state::JavaState;
 use super::launch_state::ForceLaunchResultSelect;
@@ -14,9 +19,13 @@ use super::manifest_state::ManifestState;
 use super::metadata_state::MetadataState;
 use super::new_instance_state::NewInstanceState;
 use super::settings::SettingsState;
+use super::status_bar::{Severity, StatusBar, StatusMessage, StatusSource};
+use super::tray::{self, Tray, TrayCommand};
 use crate::config::build_config;
 use crate::config::runtime_config::Config;
+use crate::lang::LangMessage;
 use crate::utils;
+use crate::version::assets_gc;
 use crate::version::instance_storage::InstanceStatus;
 use crate::version::instance_storage::InstanceStorage;
 use crate::version::instance_storage::LocalInstance;
@@ -34,7 +43,15 @@ pub struct LauncherApp {
     java_state: JavaState,
     instance_sync_state: InstanceSyncState,
     launch_state: LaunchState,
+    status_bar: StatusBar,
     new_instance_state: NewInstanceState,
+    crash_report_state: CrashReportState,
+    first_run_wizard: FirstRunWizard,
+
+    tray: Option<Tray>,
+    tray_failed: bool,
+    quit_requested: bool,
+    work_offline: bool,
 }
 
 pub fn run_gui(config: Config, launch: bool) {
@@ -74,32 +91,134 @@ impl LauncherApp {
         LauncherApp {
             settings_state: SettingsState::new(),
             auth_state: AuthState::new(ctx, &config),
-            manifest_state: ManifestState::new(&runtime, ctx),
+            manifest_state: ManifestState::new(&runtime, &config, ctx),
             metadata_state: MetadataState::new(),
             java_state: JavaState::new(ctx),
             instance_sync_state: InstanceSyncState::new(ctx),
             launch_state: LaunchState::new(launch, ctx.clone()),
+            status_bar: StatusBar::new(),
             new_instance_state: NewInstanceState::new(&runtime, ctx),
+            crash_report_state: CrashReportState::new(),
+            first_run_wizard: FirstRunWizard::new(),
             instance_storage: runtime.block_on(InstanceStorage::load(&config)),
+            work_offline: config.work_offline,
             config,
             runtime,
+            tray: None,
+            tray_failed: false,
+            quit_requested: false,
+        }
+    }
+
+    fn update_tray(&mut self, ctx: &egui::Context) {
+        if !self.config.minimize_to_tray || !tray::is_supported() {
+            self.tray = None;
+            self.tray_failed = false;
+            return;
+        }
+
+        if self.tray.is_none() && !self.tray_failed {
+            match Tray::new(
+                &self.runtime,
+                ctx,
+                self.config.recent_instances.clone(),
+                self.config.lang,
+            ) {
+                Ok(tray) => self.tray = Some(tray),
+                Err(e) => {
+                    warn!("Failed to create tray icon:\n{:?}", e);
+                    self.tray_failed = true;
+                }
+            }
+        }
+
+        let Some(tray) = &mut self.tray else {
+            return;
+        };
+        tray.update(
+            &self.runtime,
+            &self.config.recent_instances,
+            self.config.lang,
+        );
+
+        let commands: Vec<_> = std::iter::from_fn(|| tray.take_command()).collect();
+        for command in commands {
+            match command {
+                TrayCommand::Show => {}
+                TrayCommand::Launch(instance_name) => {
+                    if self.launch_state.is_running(&instance_name) {
+                        continue;
+                    }
+                    if self.config.selected_instance_name.as_ref() != Some(&instance_name) {
+                        self.config.selected_instance_name = Some(instance_name);
+                        self.config.save();
+                        self.instance_sync_state.cancel_sync();
+                        self.set_metadata_task(ctx);
+                    }
+                    self.launch_state.request_launch();
+                }
+                TrayCommand::Quit => {
+                    self.quit_requested = true;
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                }
+            }
+        }
+
+        if ctx.input(|i| i.viewport().close_requested()) && !self.quit_requested {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            utils::hide_window(ctx);
         }
     }
 
     fn ui(&mut self, ctx: &egui::Context) {
+        self.update_tray(ctx);
+        self.crash_report_state.render_window(ctx, self.config.lang);
+        let wizard_active = FirstRunWizard::is_active(&self.config);
+        if !wizard_active {
+            self.handle_shortcuts(ctx);
+        }
+
+        self.update_status_bar();
+        if !self.status_bar.is_empty() {
+            egui::TopBottomPanel::bottom("status_bar")
+                .resizable(false)
+                .show(ctx, |ui| {
+                    self.status_bar.render(ui, self.config.lang);
+                });
+        }
+
         egui::TopBottomPanel::bottom("bottom_panel")
             .resizable(false)
             .show(ctx, |ui| {
+                if wizard_active {
+                    ui.disable();
+                }
                 ui.add_space(5.0);
                 ui.horizontal(|ui| {
                     let selected_metadata = self.metadata_state.get_version_metadata(&self.config);
                     let selected_metadata_ref = selected_metadata.as_deref();
+                    let pinned = self.is_selected_instance_pinned();
+                    let game_dir = self.get_selected_game_dir();
+                    self.settings_state
+                        .set_sync_running(self.instance_sync_state.is_syncing());
                     self.settings_state.render_ui(
                         ui,
                         &self.runtime,
                         &mut self.config,
+                        &mut self.auth_state,
+                        &mut self.java_state,
                         selected_metadata_ref,
                     );
+                    if pinned != self.is_selected_instance_pinned() {
+                        self.instance_sync_state.cancel_sync();
+                        self.metadata_state.reset(true);
+                        self.set_metadata_task(ctx);
+                    }
+                    if game_dir != self.get_selected_game_dir() {
+                        // the files in the new directory are unknown until the next sync
+                        self.instance_sync_state.cancel_sync();
+                        self.instance_sync_state.reset_status();
+                    }
 
                     self.instance_sync_state.render_sync_button(
                         ui,
@@ -108,9 +227,12 @@ impl LauncherApp {
                         selected_metadata,
                     );
 
-                    if ui.button("🔄").clicked() {
+                    let work_offline = self.config.work_offline;
+                    if ui.button("🔄").clicked() || work_offline != self.work_offline {
+                        self.work_offline = work_offline;
                         self.auth_state.reset(&mut self.config, &self.runtime, ctx);
-                        self.manifest_state.retry_fetch(&self.runtime, ctx);
+                        self.manifest_state
+                            .retry_fetch(&self.runtime, &self.config, ctx);
                         self.metadata_state.reset(true); // just reset the state, not the task
 
                         // metadata is checked after manifest is fetched
@@ -129,13 +251,237 @@ impl LauncherApp {
                     .rounding(egui::Rounding::same(10.0)),
             )
             .show(ctx, |ui| {
-                self.render_central_elements(ui, ctx);
+                if wizard_active {
+                    ui.disable();
+                }
+                self.render_central_elements(ui, ctx, wizard_active);
             });
+
+        if wizard_active {
+            self.render_first_run_wizard(ctx);
+        }
+    }
+
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        // typing into a text field must not launch or sync anything
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let (enter, f5, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::F5),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+
+        if enter && self.ready_for_launch() {
+            let running = self
+                .config
+                .selected_instance_name
+                .as_ref()
+                .is_some_and(|name| self.launch_state.is_running(name));
+            if !running {
+                self.launch_state.request_launch();
+            }
+        }
+        if f5 && !self.instance_sync_state.is_syncing() {
+            if let Some(version_metadata) = self.metadata_state.get_version_metadata(&self.config) {
+                self.instance_sync_state.schedule_sync_if_needed(
+                    &self.runtime,
+                    version_metadata,
+                    false,
+                    &self.config,
+                    ctx,
+                );
+            }
+        }
+        if escape {
+            self.launch_state.cancel_force_launch();
+            self.java_state.cancel_download();
+            self.instance_sync_state.cancel_sync();
+        }
+    }
+
+    fn render_first_run_wizard(&mut self, ctx: &egui::Context) {
+        let (local_instance_names, remote_instance_names) = self.instance_storage.get_all_names();
+        let version_metadata = self.metadata_state.get_version_metadata(&self.config);
+        let auth_backend =
+            version_metadata.and_then(|metadata| metadata.get_auth_backend().cloned());
+
+        let event = self.first_run_wizard.render(
+            ctx,
+            &mut self.config,
+            WizardParams {
+                runtime: &self.runtime,
+                auth_state: &mut self.auth_state,
+                manifest_state: &mut self.manifest_state,
+                local_instance_names: &local_instance_names,
+                remote_instance_names: &remote_instance_names,
+                auth_backend: auth_backend.as_ref(),
+            },
+        );
+        match event {
+            WizardEvent::None => {}
+            WizardEvent::InstanceChanged => {
+                self.instance_sync_state.cancel_sync();
+                self.set_metadata_task(ctx);
+            }
+            WizardEvent::DataDirChanged => {
+                self.instance_storage = self.runtime.block_on(InstanceStorage::load(&self.config));
+                self.instance_sync_state.cancel_sync();
+                self.manifest_state
+                    .retry_fetch(&self.runtime, &self.config, ctx);
+                self.metadata_state.reset(true);
+            }
+            WizardEvent::Finished { launch } => {
+                if launch {
+                    self.launch_state.request_launch();
+                }
+            }
+        }
+    }
+
+    fn update_status_bar(&mut self) {
+        let selected_instance_name = self.config.selected_instance_name.clone();
+        self.status_bar
+            .update(StatusSource::Auth, self.auth_state.get_status_message());
+        self.status_bar.update(
+            StatusSource::Metadata,
+            self.metadata_state.get_status_message(),
+        );
+        self.status_bar.update(
+            StatusSource::Sync,
+            self.instance_sync_state.get_status_message(),
+        );
+        self.status_bar
+            .update(StatusSource::Java, self.java_state.get_status_message());
+        self.status_bar.update(
+            StatusSource::Launch,
+            selected_instance_name.and_then(|name| self.launch_state.get_status_message(&name)),
+        );
+        self.status_bar.update(
+            StatusSource::DataDir,
+            self.config.get_unavailable_data_dir().map(|data_dir| {
+                StatusMessage::new(
+                    Severity::Warning,
+                    LangMessage::DataDirUnavailable {
+                        path: data_dir.display().to_string(),
+                        fallback: self.config.get_launcher_dir().display().to_string(),
+                    },
+                    None,
+                )
+            }),
+        );
+        if let Some(message) = self.status_bar.get_latest() {
+            self.settings_state.set_last_error(message);
+        }
+    }
+
+    fn clean_unused_assets(&self) {
+        // assets a running sync has just downloaded are not referenced by any local instance yet
+        if self.instance_sync_state.is_syncing() {
+            warn!("Not cleaning unused assets while an instance is syncing");
+            return;
+        }
+        let version_infos = self.instance_storage.get_local_version_infos();
+        let launcher_dir = self.config.get_launcher_dir();
+        let assets_dir = self.config.get_assets_dir();
+        let custom_assets_dir = self.config.assets_dir.is_some();
+        self.runtime.spawn(async move {
+            let result = async {
+                let assets = assets_gc::find_unreferenced_assets(
+                    &version_infos,
+                    &launcher_dir,
+                    &assets_dir,
+                    custom_assets_dir,
+                )
+                .await?;
+                assets_gc::delete_assets(&assets, &version_infos, &launcher_dir, &assets_dir).await
+            }
+            .await;
+            if let Err(e) = result {
+                error!("Error cleaning unused assets:\n{:?}", e);
+            }
+        });
     }
 
     fn get_selected_instance(&self, config: &Config) -> Option<LocalInstance> {
         self.instance_storage
-            .get_instance(config.selected_instance_name.as_ref()?)
+            .get_instance(config, config.selected_instance_name.as_ref()?)
+    }
+
+    fn ready_for_launch(&self) -> bool {
+        self.java_state.ready_for_launch()
+            && self
+                .get_selected_instance(&self.config)
+                .is_some_and(|instance| match instance.status {
+                    InstanceStatus::UpToDate => true,
+                    InstanceStatus::Outdated => self.instance_sync_state.launch_offline_requested(),
+                    InstanceStatus::Missing => false,
+                })
+    }
+
+    fn is_selected_instance_pinned(&self) -> bool {
+        self.config
+            .selected_instance_name
+            .as_ref()
+            .is_some_and(|name| self.config.get_instance_settings(name).pin_version)
+    }
+
+    fn get_selected_game_dir(&self) -> Option<PathBuf> {
+        self.config
+            .selected_instance_name
+            .as_ref()
+            .map(|name| self.config.get_game_dir(name))
+    }
+
+    fn render_instance_version(&self, ui: &mut egui::Ui) {
+        let Some(selected_instance) = self.get_selected_instance(&self.config) else {
+            return;
+        };
+        let lang = self.config.lang;
+        let version_info = &selected_instance.version_info;
+
+        let local_version_info = self
+            .instance_storage
+            .get_local_version_info(&version_info.get_name());
+        match local_version_info {
+            Some(local_version_info) if selected_instance.status == InstanceStatus::Outdated => {
+                ui.label(
+                    LangMessage::InstanceVersion {
+                        minecraft_version: local_version_info.get_minecraft_version().to_string(),
+                        build: local_version_info.get_build_id().to_string(),
+                    }
+                    .to_string(lang),
+                );
+                ui.label(
+                    egui::RichText::new(
+                        LangMessage::InstanceUpdateAvailable {
+                            local_build: local_version_info.get_build_id().to_string(),
+                            remote_build: version_info.get_build_id().to_string(),
+                        }
+                        .to_string(lang),
+                    )
+                    .color(colors::action(ui.style().visuals.dark_mode)),
+                );
+            }
+            _ => {
+                ui.label(
+                    LangMessage::InstanceVersion {
+                        minecraft_version: version_info.get_minecraft_version().to_string(),
+                        build: version_info.get_build_id().to_string(),
+                    }
+                    .to_string(lang),
+                );
+            }
+        }
+        if version_info.is_snapshot() {
+            ui.label(
+                egui::RichText::new(LangMessage::SnapshotInstance.to_string(lang))
+                    .color(colors::partial_error(ui.style().visuals.dark_mode)),
+            );
+        }
     }
 
     fn set_metadata_task(&mut self, ctx: &egui::Context) {
@@ -146,10 +492,17 @@ impl LauncherApp {
                 &selected_instance.version_info,
                 ctx,
             );
+        } else {
+            self.metadata_state.cancel();
         }
     }
 
-    fn render_central_elements(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+    fn render_central_elements(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        wizard_active: bool,
+    ) {
         let (manifest, updated) = self.manifest_state.take_manifest(&mut self.config);
         if let Some(manifest) = manifest {
             self.instance_storage.set_remote_manifest(Some(manifest));
@@ -165,7 +518,11 @@ impl LauncherApp {
             );
         }
 
+        // the wizard renders the instance selection and accounts itself
         ui.horizontal(|ui| {
+            if wizard_active {
+                return;
+            }
             ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
                 let (local_instance_names, remote_instance_names) =
                     self.instance_storage.get_all_names();
@@ -173,16 +530,26 @@ impl LauncherApp {
                 let mut all_names: HashSet<String> =
                     local_instance_names.clone().into_iter().collect();
                 all_names.extend(remote_instance_names.clone());
+                // a running game still uses its instance directory
+                let deletable_instance_names: Vec<String> = local_instance_names
+                    .iter()
+                    .filter(|name| !self.launch_state.is_running(name))
+                    .cloned()
+                    .collect();
                 let new_instance_result = self.new_instance_state.render_ui(
                     &self.runtime,
                     ui,
                     &mut self.config,
                     &all_names,
-                    &local_instance_names,
+                    &deletable_instance_names,
                 );
 
-                if let Some(instance_to_delete) = new_instance_result.instance_to_delete {
+                if let Some(instance_to_delete) = new_instance_result
+                    .instance_to_delete
+                    .filter(|name| !self.launch_state.is_running(name))
+                {
                     self.config.auth_profiles.remove(&instance_to_delete);
+                    self.config.instance_settings.remove(&instance_to_delete);
                     self.config.save();
                     self.runtime.block_on(
                         self.instance_storage
@@ -190,6 +557,10 @@ impl LauncherApp {
                     );
                     self.instance_sync_state.reset_status();
                     self.metadata_state.reset(false);
+
+                    if new_instance_result.clean_unused_assets {
+                        self.clean_unused_assets();
+                    }
                 }
 
                 let selected_version_changed = self.manifest_state.render_combo_box(
@@ -208,9 +579,20 @@ impl LauncherApp {
         self.auth_state.update(&self.runtime, &mut self.config);
 
         ui.vertical_centered(|ui| {
+            self.render_instance_version(ui);
+
             let selected_instance = self.metadata_state.get_version_metadata(&self.config);
-            if selected_instance.is_some() {
-                self.instance_sync_state.render_status(ui, &self.config);
+            if let Some(selected_instance) = selected_instance.clone() {
+                let has_local_files = self
+                    .get_selected_instance(&self.config)
+                    .is_some_and(|instance| instance.status != InstanceStatus::Missing);
+                self.instance_sync_state.render_status(
+                    ui,
+                    &self.runtime,
+                    &self.config,
+                    selected_instance,
+                    has_local_files,
+                );
             } else {
                 self.metadata_state.render_status(ui, &self.config);
             }
@@ -223,6 +605,9 @@ impl LauncherApp {
         });
 
         ui.horizontal(|ui| {
+            if wizard_active {
+                return;
+            }
             ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
                 let version_metadata = self.metadata_state.get_version_metadata(&self.config);
                 let auth_backend =
@@ -238,7 +623,10 @@ impl LauncherApp {
         });
 
         if let Some(selected_instance) = self.get_selected_instance(&self.config) {
-            if self.metadata_state.update() {
+            if self.metadata_state.update(&self.runtime, &self.config, ctx) {
+                if self.metadata_state.take_fallback_launch_request() {
+                    self.launch_state.request_launch();
+                }
                 if self.manifest_state.online()
                     && self.metadata_state.online()
                     && selected_instance.status == InstanceStatus::UpToDate
@@ -251,6 +639,20 @@ impl LauncherApp {
                 if let Some(version_metadata) =
                     self.metadata_state.get_version_metadata(&self.config)
                 {
+                    let size_estimate = version_metadata.get_size_estimate();
+                    if size_estimate > 0
+                        && self
+                            .config
+                            .instance_size_estimates
+                            .get(version_metadata.get_name())
+                            != Some(&size_estimate)
+                    {
+                        self.config
+                            .instance_size_estimates
+                            .insert(version_metadata.get_name().to_string(), size_estimate);
+                        self.config.save();
+                    }
+
                     self.java_state.set_check_java_task(
                         &self.runtime,
                         &version_metadata,
@@ -262,6 +664,10 @@ impl LauncherApp {
 
             if let Some(version_metadata) = self.metadata_state.get_version_metadata(&self.config) {
                 if self.instance_sync_state.update() {
+                    self.config
+                        .get_instance_settings_mut(version_metadata.get_name())
+                        .last_synced = Some(utils::get_unix_timestamp());
+                    self.config.save();
                     self.runtime.block_on(
                         self.instance_storage
                             .mark_downloaded(&self.config, version_metadata.get_name()),
@@ -278,13 +684,9 @@ impl LauncherApp {
             self.java_state
                 .render_ui(ui, &mut self.config, selected_instance.as_deref());
 
-            self.launch_state.update(&self.runtime, &self.config);
+            self.launch_state.update(&self.runtime, &mut self.config);
 
-            if self.java_state.ready_for_launch()
-                && self
-                    .get_selected_instance(&self.config)
-                    .is_some_and(|instance| instance.status == InstanceStatus::UpToDate)
-            {
+            if self.ready_for_launch() {
                 let auth_data = self.auth_state.get_auth_data(&self.config);
                 let selected_instance = self.metadata_state.get_version_metadata(&self.config);
 
@@ -1,17 +1,21 @@
+use std::path::Path;
+
 use crate::{
     config::{build_config, runtime_config::Config},
     lang::LangMessage,
-    utils,
 };
 
 use egui::RichText;
 use log::error;
+use shared::adaptive_download::fetch_with_mirrors;
+use shared::paths::{get_manifest_cache_path, get_manifest_cache_validators_path};
 use shared::version::version_manifest::VersionManifest;
 use tokio::runtime::Runtime;
 
 use super::{
     background_task::{BackgroundTask, BackgroundTaskResult},
-    colors,
+    colors, error_label,
+    remote_fetch::{fetch_with_fallback, FetchResult},
 };
 
 #[derive(PartialEq)]
@@ -25,31 +29,58 @@ enum FetchStatus {
 struct ManifestFetchResult {
     status: FetchStatus,
     manifest: Option<VersionManifest>,
+    from_cache: bool,
 }
 
 fn fetch_manifest<Callback>(
     runtime: &tokio::runtime::Runtime,
+    data_dir: &Path,
+    work_offline: bool,
     callback: Callback,
 ) -> BackgroundTask<ManifestFetchResult>
 where
     Callback: FnOnce() + Send + 'static,
 {
+    let manifest_path = get_manifest_cache_path(data_dir);
+    let validators_path = get_manifest_cache_validators_path(data_dir);
+    let manifest_url = build_config::get_version_manifest_url();
+    let mirrors = build_config::get_download_mirrors();
     let fut = async move {
-        let result = VersionManifest::fetch(&build_config::get_version_manifest_url()).await;
+        let (manifest_path, validators_path) = (&manifest_path, &validators_path);
+        let result = fetch_with_fallback(
+            work_offline,
+            || {
+                fetch_with_mirrors(&manifest_url, &mirrors, |url| async move {
+                    VersionManifest::fetch_cached(&url, manifest_path, validators_path).await
+                })
+            },
+            || VersionManifest::read_local(manifest_path),
+        )
+        .await;
         match result {
-            Ok(manifest) => ManifestFetchResult {
+            FetchResult::Remote(manifest) => ManifestFetchResult {
                 status: FetchStatus::Fetched,
                 manifest: Some(manifest),
+                from_cache: false,
             },
-            Err(e) => ManifestFetchResult {
-                status: if utils::is_connect_error(&e) {
-                    FetchStatus::FetchErrorOffline
-                } else {
-                    error!("Error fetching version manifest:\n{:?}", e);
-                    FetchStatus::FetchError(e.to_string())
-                },
-                manifest: None,
-            },
+            FetchResult::Offline { local } => {
+                let manifest = local.ok();
+                ManifestFetchResult {
+                    status: FetchStatus::FetchErrorOffline,
+                    from_cache: manifest.is_some(),
+                    manifest,
+                }
+            }
+            FetchResult::RemoteError { error, local } => {
+                error!("Error fetching version manifest:\n{:?}", error);
+                // the last fetched manifest still lists the instances
+                let manifest = local.ok();
+                ManifestFetchResult {
+                    status: FetchStatus::FetchError(format!("{:?}", error)),
+                    from_cache: manifest.is_some(),
+                    manifest,
+                }
+            }
         }
     };
 
@@ -59,22 +90,29 @@ where
 pub struct ManifestState {
     status: FetchStatus,
     fetch_task: Option<BackgroundTask<ManifestFetchResult>>,
+    from_cache: bool,
 }
 
 impl ManifestState {
-    fn set_fetch_task(&mut self, runtime: &Runtime, ctx: &egui::Context) {
+    fn set_fetch_task(&mut self, runtime: &Runtime, config: &Config, ctx: &egui::Context) {
         let ctx = ctx.clone();
-        self.fetch_task = Some(fetch_manifest(runtime, move || {
-            ctx.request_repaint();
-        }));
+        self.fetch_task = Some(fetch_manifest(
+            runtime,
+            &config.get_launcher_dir(),
+            config.work_offline,
+            move || {
+                ctx.request_repaint();
+            },
+        ));
     }
 
-    pub fn new(runtime: &Runtime, ctx: &egui::Context) -> ManifestState {
+    pub fn new(runtime: &Runtime, config: &Config, ctx: &egui::Context) -> ManifestState {
         let mut result = ManifestState {
             status: FetchStatus::NotFetched,
             fetch_task: None,
+            from_cache: false,
         };
-        result.set_fetch_task(runtime, ctx);
+        result.set_fetch_task(runtime, config, ctx);
 
         result
     }
@@ -96,6 +134,7 @@ impl ManifestState {
                             }
                         }
                         self.status = result.status;
+                        self.from_cache = result.from_cache;
 
                         return (result.manifest, true);
                     }
@@ -113,10 +152,12 @@ impl ManifestState {
         &mut self,
         ui: &mut egui::Ui,
         config: &mut Config,
-        local_instance_names: &Vec<String>,
+        local_instance_names: &[String],
         remote_instance_names: &Vec<String>,
     ) -> bool {
         let mut selected_instance_name = config.selected_instance_name.clone();
+        let mut local_instance_names = local_instance_names.to_vec();
+        config.sort_instance_names(&mut local_instance_names);
         let dark_mode = ui.style().visuals.dark_mode;
 
         ui.horizontal(|ui| {
@@ -134,7 +175,11 @@ impl ManifestState {
                     FetchStatus::FetchErrorOffline => RichText::new(format!(
                         "{} ({})",
                         instance_text,
-                        LangMessage::Offline.to_string(config.lang)
+                        if self.from_cache {
+                            LangMessage::OfflineCachedList.to_string(config.lang)
+                        } else {
+                            LangMessage::Offline.to_string(config.lang)
+                        }
                     ))
                     .color(colors::offline(dark_mode)),
                     FetchStatus::FetchError(_) => RichText::new(format!(
@@ -149,23 +194,37 @@ impl ManifestState {
                     .color(colors::action(dark_mode))
             };
 
+            if let FetchStatus::FetchError(e) = &self.status {
+                error_label::render_copy_button(ui, e, config.lang);
+            }
+
+            let get_label =
+                |instance_name: &String| match config.instance_size_estimates.get(instance_name) {
+                    Some(&size) => format!(
+                        "{} ({})",
+                        instance_name,
+                        LangMessage::ApproximateSize { size }.to_string(config.lang)
+                    ),
+                    None => instance_name.clone(),
+                };
+
             egui::ComboBox::from_id_salt("instances")
                 .width(ui.available_width())
                 .selected_text(selected_text)
                 .show_ui(ui, |ui| {
                     if !local_instance_names.is_empty() || !remote_instance_names.is_empty() {
-                        for instance_name in local_instance_names {
+                        for instance_name in &local_instance_names {
                             ui.selectable_value(
                                 &mut selected_instance_name,
                                 Some(instance_name.clone()),
-                                instance_name,
+                                get_label(instance_name),
                             );
                         }
                         for instance_name in remote_instance_names {
                             ui.selectable_value(
                                 &mut selected_instance_name,
                                 Some(instance_name.clone()),
-                                egui::WidgetText::from(instance_name).italics(),
+                                egui::WidgetText::from(get_label(instance_name)).italics(),
                             );
                         }
                     } else {
@@ -183,9 +242,9 @@ impl ManifestState {
         }
     }
 
-    pub fn retry_fetch(&mut self, runtime: &Runtime, ctx: &egui::Context) {
+    pub fn retry_fetch(&mut self, runtime: &Runtime, config: &Config, ctx: &egui::Context) {
         self.status = FetchStatus::NotFetched;
-        self.set_fetch_task(runtime, ctx);
+        self.set_fetch_task(runtime, config, ctx);
     }
 
     pub fn online(&self) -> bool {
@@ -1,4 +1,9 @@
-use std::{collections::HashMap, path::Path, sync::Arc};
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use egui::RichText;
 use log::{error, info};
@@ -6,22 +11,24 @@ use shared::version::version_manifest::VersionInfo;
 use tokio::runtime::Runtime;
 
 use crate::{
-    config::runtime_config::Config, lang::LangMessage, utils,
+    config::runtime_config::Config, lang::LangMessage,
     version::complete_version_metadata::CompleteVersionMetadata,
 };
 
 use super::{
     background_task::{BackgroundTask, BackgroundTaskResult},
-    colors,
+    colors, error_label,
+    remote_fetch::{fetch_with_fallback, FetchResult},
+    status_bar::{Severity, StatusMessage},
 };
 
 #[derive(PartialEq)]
 enum GetStatus {
     NoMetadata,
     UpToDate,
-    ReadLocalRemoteError,
+    ReadLocalRemoteError(String),
     ReadLocalOffline,
-    ErrorGetting,
+    ErrorGetting(String),
 }
 
 struct MetadataFetchResult {
@@ -36,6 +43,7 @@ fn get_metadata(
     data_dir: &Path,
     ctx: &egui::Context,
     existing_metadata: Option<Arc<CompleteVersionMetadata>>,
+    work_offline: bool,
 ) -> BackgroundTask<MetadataFetchResult> {
     let version_info = version_info.clone();
     let data_dir = data_dir.to_path_buf();
@@ -48,34 +56,49 @@ fn get_metadata(
                 metadata: Some(metadata),
             };
         }
-        let result = CompleteVersionMetadata::read_or_download(&version_info, &data_dir).await;
-        match result {
-            Ok(metadata) => MetadataFetchResult {
-                status: GetStatus::UpToDate,
-                version_info,
-                metadata: Some(Arc::new(metadata)),
+        let result = fetch_with_fallback(
+            work_offline,
+            || CompleteVersionMetadata::read_or_download(&version_info, &data_dir),
+            || CompleteVersionMetadata::read_local(&version_info, &data_dir),
+        )
+        .await;
+        let (status, metadata) = match result {
+            FetchResult::Remote(metadata) => (GetStatus::UpToDate, Some(metadata)),
+            // unreachable servers only matter when there is nothing to fall back to in offline mode
+            FetchResult::Offline { local } => match local {
+                Err(e) if work_offline => {
+                    error!("Error reading local metadata:\n{:?}", e);
+                    (GetStatus::ErrorGetting(format!("{:?}", e)), None)
+                }
+                local => (GetStatus::ReadLocalOffline, local.ok()),
             },
-            Err(e) => {
-                let local_metadata =
-                    CompleteVersionMetadata::read_local(&version_info, &data_dir).await;
-                MetadataFetchResult {
-                    status: if utils::is_connect_error(&e) {
-                        info!("Metadata offline mode");
-                        GetStatus::ReadLocalOffline
-                    } else if let Some(local_error) = local_metadata.as_ref().err() {
-                        error!(
-                            "Error getting metadata:\n{:?}\nlocal metadata error:\n{:?}",
-                            e, local_error
-                        );
-                        GetStatus::ErrorGetting
-                    } else {
-                        error!("Error getting metadata:\n{:?}\n(read local)", e);
-                        GetStatus::ReadLocalRemoteError
-                    },
-                    version_info,
-                    metadata: local_metadata.ok().map(Arc::new),
+            FetchResult::RemoteError { error, local } => match local {
+                Err(local_error) => {
+                    error!(
+                        "Error getting metadata:\n{:?}\nlocal metadata error:\n{:?}",
+                        error, local_error
+                    );
+                    (
+                        GetStatus::ErrorGetting(format!(
+                            "{:?}\nlocal metadata error:\n{:?}",
+                            error, local_error
+                        )),
+                        None,
+                    )
                 }
-            }
+                Ok(metadata) => {
+                    error!("Error getting metadata:\n{:?}\n(read local)", error);
+                    (
+                        GetStatus::ReadLocalRemoteError(format!("{:?}", error)),
+                        Some(metadata),
+                    )
+                }
+            },
+        };
+        MetadataFetchResult {
+            status,
+            version_info,
+            metadata: metadata.map(Arc::new),
         }
     };
 
@@ -83,10 +106,42 @@ fn get_metadata(
     BackgroundTask::with_callback(fut, runtime, Box::new(move || ctx.request_repaint()))
 }
 
+fn read_local_metadata(
+    runtime: &Runtime,
+    version_info: &VersionInfo,
+    data_dir: &Path,
+    ctx: &egui::Context,
+) -> BackgroundTask<Option<Arc<CompleteVersionMetadata>>> {
+    let version_info = version_info.clone();
+    let data_dir = data_dir.to_path_buf();
+
+    let fut = async move {
+        CompleteVersionMetadata::read_local(&version_info, &data_dir)
+            .await
+            .ok()
+            .map(Arc::new)
+    };
+
+    let ctx = ctx.clone();
+    BackgroundTask::with_callback(fut, runtime, Box::new(move || ctx.request_repaint()))
+}
+
+// a local copy offered while the remote metadata is taking too long
+struct LocalFallback {
+    version_info: VersionInfo,
+    started: Instant,
+    task: Option<BackgroundTask<Option<Arc<CompleteVersionMetadata>>>>,
+    metadata: Option<Arc<CompleteVersionMetadata>>,
+    checked: bool,
+    use_requested: bool,
+}
+
 pub struct MetadataState {
     status: GetStatus,
     get_task: Option<BackgroundTask<MetadataFetchResult>>,
     metadata_storage: HashMap<String, Arc<CompleteVersionMetadata>>,
+    local_fallback: Option<LocalFallback>,
+    fallback_launch_requested: bool,
 }
 
 impl MetadataState {
@@ -95,6 +150,8 @@ impl MetadataState {
             status: GetStatus::NoMetadata,
             get_task: None,
             metadata_storage: HashMap::new(),
+            local_fallback: None,
+            fallback_launch_requested: false,
         }
     }
 
@@ -105,7 +162,8 @@ impl MetadataState {
         version_info: &VersionInfo,
         ctx: &egui::Context,
     ) {
-        self.status = GetStatus::NoMetadata;
+        // dropping the previous task cancels it
+        self.cancel();
         let name = version_info.get_name();
         let existing_metadata = self.metadata_storage.get(&name).cloned();
         let launcher_dir = config.get_launcher_dir();
@@ -115,13 +173,39 @@ impl MetadataState {
             &launcher_dir,
             ctx,
             existing_metadata,
+            config.work_offline,
         ));
+        self.local_fallback = Some(LocalFallback {
+            version_info: version_info.clone(),
+            started: Instant::now(),
+            task: None,
+            metadata: None,
+            checked: false,
+            use_requested: false,
+        });
+    }
+
+    pub fn get_status_message(&self) -> Option<StatusMessage> {
+        Some(match &self.status {
+            GetStatus::ReadLocalRemoteError(e) => StatusMessage::new(
+                Severity::Warning,
+                LangMessage::ReadLocalRemoteError,
+                Some(e),
+            ),
+            GetStatus::ReadLocalOffline => {
+                StatusMessage::new(Severity::Offline, LangMessage::ReadLocalOffline, None)
+            }
+            GetStatus::ErrorGetting(e) => {
+                StatusMessage::new(Severity::Error, LangMessage::ErrorGettingMetadata, Some(e))
+            }
+            _ => return None,
+        })
     }
 
     pub fn render_status(&mut self, ui: &mut egui::Ui, config: &Config) {
         let dark_mode = ui.style().visuals.dark_mode;
 
-        ui.label(match self.status {
+        let text = match &self.status {
             GetStatus::NoMetadata => RichText::new(
                 if self.get_task.is_some() {
                     LangMessage::GettingMetadata
@@ -135,7 +219,7 @@ impl MetadataState {
                 RichText::new(LangMessage::MetadataUpToDate.to_string(config.lang))
                     .color(colors::ok(dark_mode))
             }
-            GetStatus::ReadLocalRemoteError => {
+            GetStatus::ReadLocalRemoteError(_) => {
                 RichText::new(LangMessage::ReadLocalRemoteError.to_string(config.lang))
                     .color(colors::partial_error(dark_mode))
             }
@@ -143,22 +227,105 @@ impl MetadataState {
                 RichText::new(LangMessage::ReadLocalOffline.to_string(config.lang))
                     .color(colors::offline(dark_mode))
             }
-            GetStatus::ErrorGetting => {
+            GetStatus::ErrorGetting(_) => {
                 RichText::new(LangMessage::ErrorGettingMetadata.to_string(config.lang))
                     .color(colors::error(dark_mode))
             }
-        });
+        };
+        let error = match &self.status {
+            GetStatus::ReadLocalRemoteError(e) | GetStatus::ErrorGetting(e) => Some(e.as_str()),
+            _ => None,
+        };
+        error_label::render(ui, text, error, config.lang);
+
+        if let Some(local_fallback) = &mut self.local_fallback {
+            if local_fallback.metadata.is_some()
+                && ui
+                    .button(LangMessage::LaunchWithCachedVersion.to_string(config.lang))
+                    .clicked()
+            {
+                local_fallback.use_requested = true;
+            }
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        self.status = GetStatus::NoMetadata;
+        self.get_task = None;
+        self.local_fallback = None;
+    }
+
+    fn update_local_fallback(&mut self, runtime: &Runtime, config: &Config, ctx: &egui::Context) {
+        let timeout_secs = config.network.metadata_timeout_secs;
+        let Some(local_fallback) = &mut self.local_fallback else {
+            return;
+        };
+        if self.get_task.is_none() || timeout_secs == 0 {
+            return;
+        }
+
+        if !local_fallback.checked {
+            let timeout = Duration::from_secs(timeout_secs);
+            let elapsed = local_fallback.started.elapsed();
+            if elapsed < timeout {
+                ctx.request_repaint_after(timeout - elapsed);
+                return;
+            }
+            local_fallback.checked = true;
+            local_fallback.task = Some(read_local_metadata(
+                runtime,
+                &local_fallback.version_info,
+                &config.get_launcher_dir(),
+                ctx,
+            ));
+        }
+
+        if let Some(task) = local_fallback.task.as_ref() {
+            if task.has_result() {
+                let task = local_fallback.task.take().unwrap();
+                if let BackgroundTaskResult::Finished(metadata) = task.take_result() {
+                    local_fallback.metadata = metadata;
+                }
+            }
+        }
     }
 
-    pub fn update(&mut self) -> bool {
+    pub fn update(&mut self, runtime: &Runtime, config: &Config, ctx: &egui::Context) -> bool {
+        self.update_local_fallback(runtime, config, ctx);
+
+        if let Some(local_fallback) = self.local_fallback.take_if(|f| f.use_requested) {
+            let name = local_fallback.version_info.get_name();
+            if config.selected_instance_name.as_ref() == Some(&name) {
+                if let Some(metadata) = local_fallback.metadata {
+                    info!(
+                        "Using local metadata for {}, remote is taking too long",
+                        name
+                    );
+                    self.get_task = None;
+                    self.status = GetStatus::ReadLocalOffline;
+                    self.metadata_storage.insert(name, metadata);
+                    self.fallback_launch_requested = true;
+                    return true;
+                }
+            }
+        }
+
         if let Some(task) = self.get_task.as_ref() {
             if task.has_result() {
+                self.local_fallback = None;
                 let task = self.get_task.take().unwrap();
                 let result = task.take_result();
                 match result {
                     BackgroundTaskResult::Finished(result) => {
-                        self.status = result.status;
                         let name = result.version_info.get_name();
+                        if config.selected_instance_name.as_ref() != Some(&name) {
+                            info!(
+                                "Discarding metadata fetched for {}, selection changed",
+                                name
+                            );
+                            return false;
+                        }
+                        self.status = result.status;
                         if let Some(metadata) = result.metadata {
                             self.metadata_storage.insert(name, metadata);
                         } else {
@@ -191,6 +358,10 @@ impl MetadataState {
         self.get_task.is_some()
     }
 
+    pub fn take_fallback_launch_request(&mut self) -> bool {
+        std::mem::take(&mut self.fallback_launch_requested)
+    }
+
     pub fn reset(&mut self, clear_storage: bool) {
         self.status = GetStatus::NoMetadata;
         if clear_storage {
@@ -1,7 +1,17 @@
+mod assets_gc_state;
 mod auth_state;
 mod background_task;
+mod clear_caches_state;
 mod colors;
+mod crash_report_state;
+mod diagnostics;
+mod disk_usage_state;
+pub mod error_label;
+mod first_run_wizard;
+mod game_output;
+mod import_state;
 mod instance_sync_state;
+mod issue_report_state;
 mod java_state;
 mod language_selector;
 mod launch_state;
@@ -10,4 +20,7 @@ mod manifest_state;
 mod metadata_state;
 mod new_instance_state;
 pub mod progress_bar;
+mod remote_fetch;
 mod settings;
+mod status_bar;
+mod tray;
@@ -4,6 +4,8 @@ use std::sync::Arc;
 
 use log::error;
 use maplit::hashmap;
+use shared::endpoints;
+use shared::files;
 use shared::generate::extra::ExtraMetadataGenerator;
 use shared::generate::manifest::get_version_info;
 use shared::loader_generator::fabric::{FabricGenerator, FabricVersionsMeta};
@@ -12,9 +14,9 @@ use shared::loader_generator::forge::{
 };
 use shared::loader_generator::generator::VersionGenerator;
 use shared::loader_generator::vanilla::VanillaGenerator;
-use shared::paths::get_instance_dir;
+use shared::paths::{get_instance_dir, is_safe_name};
 use shared::progress::NoProgressBar;
-use shared::utils::{get_vanilla_version_info, VANILLA_MANIFEST_URL};
+use shared::utils::get_vanilla_version_info;
 use shared::version::version_manifest::{VersionInfo, VersionManifest};
 use tokio::runtime::Runtime;
 
@@ -38,8 +40,9 @@ fn fetch_all_metadata(
     ctx: &egui::Context,
 ) -> BackgroundTask<anyhow::Result<AllVersionsMetadata>> {
     let fut = async {
+        let vanilla_manifest_url = endpoints::get_vanilla_manifest_url();
         let result = futures::try_join!(
-            VersionManifest::fetch(VANILLA_MANIFEST_URL),
+            VersionManifest::fetch(&vanilla_manifest_url),
             ForgeMavenMetadata::fetch(),
             ForgePromotions::fetch(),
             NeoforgeMavenMetadata::fetch(),
@@ -151,6 +154,7 @@ where
 
 pub struct RenderUIResult {
     pub instance_to_delete: Option<String>,
+    pub clean_unused_assets: bool,
 }
 
 const VANILLA_LOADER: &str = "Vanilla";
@@ -161,6 +165,7 @@ const NEOFORGE_LOADER: &str = "NeoForge";
 struct NewInstanceParams {
     instance_name: String,
     minecraft_version: String,
+    include_snapshots: bool,
     loader: String,
     loader_version: String,
 }
@@ -175,6 +180,7 @@ fn create_new_instance(
     let NewInstanceParams {
         instance_name,
         minecraft_version,
+        include_snapshots,
         loader,
         loader_version,
     } = new_instance_params;
@@ -182,7 +188,9 @@ fn create_new_instance(
     let launcher_dir = launcher_dir.to_path_buf();
     let version_manifest = version_manifest.clone();
     let fut = async move {
-        let vanilla_info = get_vanilla_version_info(&version_manifest, &minecraft_version)?;
+        let vanilla_info =
+            get_vanilla_version_info(&version_manifest, &minecraft_version, include_snapshots)?;
+        let version_type = vanilla_info.version_type.clone();
 
         let generator: Box<dyn VersionGenerator + Send> = match loader.as_str() {
             VANILLA_LOADER => {
@@ -230,16 +238,19 @@ fn create_new_instance(
             None,
             generator_result.extra_libs_paths,
             None,
+            None,
         );
         let _ = extra_generator.generate(&launcher_dir).await?;
 
-        let version_info = get_version_info(
+        let mut version_info = get_version_info(
             &launcher_dir,
             &generator_result.metadata,
             &instance_name,
             None,
         )
         .await?;
+        // marks snapshot instances so they are easy to tell apart from stable ones
+        version_info.version_type = version_type;
 
         Ok(version_info)
     };
@@ -264,6 +275,7 @@ pub struct NewInstanceState {
     window_open: bool,
     new_instance_name: String,
     instance_version: String,
+    include_snapshots: bool,
     instance_loader: String,
     instance_loader_version: String,
 
@@ -277,6 +289,10 @@ pub struct NewInstanceState {
     delete_window_open: bool,
     selected_instance_to_delete: String,
     confirm_delete: bool,
+    clean_unused_assets: bool,
+    // size of the selected instance directory, keyed by the instance name
+    delete_size_task: Option<(String, BackgroundTask<u64>)>,
+    delete_size: Option<(String, u64)>,
 }
 
 impl NewInstanceState {
@@ -285,6 +301,7 @@ impl NewInstanceState {
             window_open: false,
             new_instance_name: String::new(),
             instance_version: String::new(),
+            include_snapshots: false,
             instance_loader: String::new(),
             instance_loader_version: String::new(),
 
@@ -298,6 +315,9 @@ impl NewInstanceState {
             delete_window_open: false,
             selected_instance_to_delete: String::new(),
             confirm_delete: false,
+            clean_unused_assets: false,
+            delete_size_task: None,
+            delete_size: None,
         }
     }
 
@@ -336,7 +356,7 @@ impl NewInstanceState {
         ui: &mut egui::Ui,
         config: &mut Config,
         existing_names: &HashSet<String>,
-        local_instance_names: &Vec<String>,
+        deletable_instance_names: &Vec<String>,
     ) -> RenderUIResult {
         let lang = config.lang;
 
@@ -356,8 +376,7 @@ impl NewInstanceState {
 
         if let Some(selected_instance_name) = &config.selected_instance_name {
             if ui.button("📂").clicked() {
-                let launcher_dir = config.get_launcher_dir();
-                let _ = open::that(get_instance_dir(&launcher_dir, selected_instance_name));
+                let _ = open::that(config.get_game_dir(selected_instance_name));
             }
         }
 
@@ -392,6 +411,7 @@ impl NewInstanceState {
                         .vanilla_manifest
                         .versions
                         .iter()
+                        .filter(|i| self.include_snapshots || !i.is_snapshot())
                         .map(|i| i.get_name())
                         .collect::<Vec<_>>();
                     let mut selected_version = self.instance_version.clone();
@@ -403,6 +423,8 @@ impl NewInstanceState {
                             }
                         });
 
+                    ui.checkbox(&mut self.include_snapshots, LangMessage::IncludeSnapshots.to_string(lang));
+
                     if selected_version != self.instance_version {
                         self.instance_version = selected_version;
                         self.current_version_metadata_task = Some(fetch_per_version_metadata(
@@ -504,6 +526,8 @@ impl NewInstanceState {
                 if !self.new_instance_name.is_empty() && (self.instance_loader == VANILLA_LOADER || versions.contains_key(&self.instance_loader)) {
                     if existing_names.contains(&self.new_instance_name) {
                         ui.label(LangMessage::InstanceNameExists.to_string(lang));
+                    } else if !is_safe_name(&self.new_instance_name) {
+                        ui.label(LangMessage::InvalidInstanceName.to_string(lang));
                     } else {
                         ui.horizontal(|ui| {
                             if self.instance_generate_task.is_none() {
@@ -511,6 +535,7 @@ impl NewInstanceState {
                                     let params = NewInstanceParams {
                                         instance_name: self.new_instance_name.clone(),
                                         minecraft_version: self.instance_version.clone(),
+                                        include_snapshots: self.include_snapshots,
                                         loader: self.instance_loader.clone(),
                                         loader_version: self.instance_loader_version.clone(),
                                     };
@@ -547,9 +572,12 @@ impl NewInstanceState {
             });
         self.window_open = new_instance_window_open;
 
+        self.update_delete_size(runtime, ui.ctx(), config);
+
         let mut delete_window_open = self.delete_window_open;
         let mut close_delete_window = false;
         let mut instance_to_delete = None;
+        let mut clean_unused_assets = false;
         egui::Window::new(LangMessage::DeleteInstance.to_string(lang))
             .open(&mut delete_window_open)
             .show(ui.ctx(), |ui| {
@@ -561,7 +589,7 @@ impl NewInstanceState {
                         self.selected_instance_to_delete.clone()
                     })
                     .show_ui(ui, |ui| {
-                        for instance_name in local_instance_names {
+                        for instance_name in deletable_instance_names {
                             ui.selectable_value(
                                 &mut self.selected_instance_to_delete,
                                 instance_name.clone(),
@@ -570,14 +598,37 @@ impl NewInstanceState {
                         }
                     });
 
+                if !self.selected_instance_to_delete.is_empty() {
+                    let instance_dir = get_instance_dir(
+                        &config.get_launcher_dir(),
+                        &self.selected_instance_to_delete,
+                    );
+                    ui.code(instance_dir.display().to_string());
+                    match &self.delete_size {
+                        Some((name, size)) if name == &self.selected_instance_to_delete => {
+                            ui.label(LangMessage::SpaceToFree { size: *size }.to_string(lang));
+                        }
+                        _ => {
+                            ui.spinner();
+                        }
+                    }
+                }
+
+                ui.checkbox(
+                    &mut self.clean_unused_assets,
+                    LangMessage::CleanUnusedAssets.to_string(lang),
+                );
+
                 ui.checkbox(
                     &mut self.confirm_delete,
                     LangMessage::ConfirmDelete.to_string(lang),
                 );
 
                 ui.horizontal(|ui| {
-                    let delete_enabled =
-                        !self.selected_instance_to_delete.is_empty() && self.confirm_delete;
+                    // the instance may have been launched after it was selected
+                    let delete_enabled = deletable_instance_names
+                        .contains(&self.selected_instance_to_delete)
+                        && self.confirm_delete;
                     if ui
                         .add_enabled(
                             delete_enabled,
@@ -586,6 +637,8 @@ impl NewInstanceState {
                         .clicked()
                     {
                         instance_to_delete = Some(self.selected_instance_to_delete.clone());
+                        clean_unused_assets = self.clean_unused_assets;
+                        self.delete_size = None;
                         self.selected_instance_to_delete.clear();
                         self.confirm_delete = false;
                         close_delete_window = true;
@@ -599,6 +652,46 @@ impl NewInstanceState {
             self.delete_window_open = delete_window_open;
         }
 
-        RenderUIResult { instance_to_delete }
+        RenderUIResult {
+            instance_to_delete,
+            clean_unused_assets,
+        }
+    }
+
+    fn update_delete_size(&mut self, runtime: &Runtime, ctx: &egui::Context, config: &Config) {
+        if let Some((name, task)) = self.delete_size_task.take() {
+            if !task.has_result() {
+                self.delete_size_task = Some((name, task));
+            } else if let BackgroundTaskResult::Finished(size) = task.take_result() {
+                self.delete_size = Some((name, size));
+            }
+        }
+
+        let selected = &self.selected_instance_to_delete;
+        if !self.delete_window_open
+            || selected.is_empty()
+            || self
+                .delete_size
+                .as_ref()
+                .is_some_and(|(name, _)| name == selected)
+            || self
+                .delete_size_task
+                .as_ref()
+                .is_some_and(|(name, _)| name == selected)
+        {
+            return;
+        }
+
+        let instance_dir = get_instance_dir(&config.get_launcher_dir(), selected);
+        let fut = async move {
+            tokio::task::spawn_blocking(move || files::get_dir_size(&instance_dir))
+                .await
+                .unwrap_or(0)
+        };
+        let ctx = ctx.clone();
+        self.delete_size_task = Some((
+            selected.clone(),
+            BackgroundTask::with_callback(fut, runtime, Box::new(move || ctx.request_repaint())),
+        ));
     }
 }
@@ -1,37 +1,75 @@
 use egui::Widget as _;
 
 use crate::lang::{Lang, LangMessage};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use shared::progress::{ProgressBar, Unit};
 
 pub struct GuiProgressBar {
+    progress: Arc<AtomicU64>,
+    total: Arc<AtomicU64>,
+    finished: Arc<AtomicBool>,
     state: Arc<Mutex<ProgressBarState>>,
+    samples: Arc<Mutex<VecDeque<(Instant, u64)>>>,
     ctx: egui::Context,
-    last_update: Arc<Mutex<std::time::Instant>>,
 }
 
 #[derive(Clone)]
 pub struct ProgressBarState {
-    pub progress: u64,
-    pub total: u64,
     pub message: Option<LangMessage>,
-    pub finished: bool,
     pub unit: Option<Unit>,
 }
 
+const RATE_WINDOW: Duration = Duration::from_secs(5);
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+pub const REPAINT_INTERVAL: Duration = Duration::from_millis(33);
+
+// samples are only taken by the GUI thread while rendering, so workers never touch this lock
+fn add_sample(samples: &mut VecDeque<(Instant, u64)>, progress: u64) {
+    let now = Instant::now();
+    if let Some((last_time, last_progress)) = samples.back() {
+        if progress < *last_progress {
+            samples.clear();
+        } else if now.duration_since(*last_time) < SAMPLE_INTERVAL {
+            return;
+        }
+    }
+    samples.push_back((now, progress));
+    while let Some((timestamp, _)) = samples.front() {
+        if now.duration_since(*timestamp) > RATE_WINDOW {
+            samples.pop_front();
+        } else {
+            break;
+        }
+    }
+}
+
+/// Average progress per second over the last few seconds
+fn get_rate(samples: &VecDeque<(Instant, u64)>) -> Option<f64> {
+    let (first_time, first_progress) = samples.front()?;
+    let (last_time, last_progress) = samples.back()?;
+    let elapsed = last_time.duration_since(*first_time).as_secs_f64();
+    if elapsed < 0.5 || last_progress <= first_progress {
+        return None;
+    }
+    Some((last_progress - first_progress) as f64 / elapsed)
+}
+
 impl GuiProgressBar {
     pub fn new(ctx: &egui::Context) -> Self {
         Self {
+            progress: Arc::new(AtomicU64::new(0)),
+            total: Arc::new(AtomicU64::new(0)),
+            finished: Arc::new(AtomicBool::new(false)),
             state: Arc::new(Mutex::new(ProgressBarState {
-                progress: 0,
-                total: 0,
                 message: None,
-                finished: false,
                 unit: None,
             })),
+            samples: Arc::new(Mutex::new(VecDeque::new())),
             ctx: ctx.clone(),
-            last_update: Arc::new(Mutex::new(std::time::Instant::now())),
         }
     }
 
@@ -39,13 +77,9 @@ impl GuiProgressBar {
         self.state.lock().unwrap().clone()
     }
 
+    // egui keeps the earliest pending repaint, so frequent updates coalesce into one frame per interval
     fn update_gui_if_needed(&self) {
-        let now = std::time::Instant::now();
-        let mut last_update = self.last_update.lock().unwrap();
-        if now.duration_since(*last_update).as_millis() > 10 {
-            self.ctx.request_repaint();
-            *last_update = now;
-        }
+        self.ctx.request_repaint_after(REPAINT_INTERVAL);
     }
 }
 
@@ -56,22 +90,19 @@ impl ProgressBar<LangMessage> for GuiProgressBar {
     }
 
     fn set_length(&self, length: u64) {
-        let mut state = self.state.lock().unwrap();
-        state.total = length;
-        state.progress = 0;
-        state.finished = false;
+        self.total.store(length, Ordering::Relaxed);
+        self.progress.store(0, Ordering::Relaxed);
+        self.finished.store(false, Ordering::Relaxed);
         self.update_gui_if_needed();
     }
 
     fn inc(&self, amount: u64) {
-        let mut state = self.state.lock().unwrap();
-        state.progress += amount;
+        self.progress.fetch_add(amount, Ordering::Relaxed);
         self.update_gui_if_needed();
     }
 
     fn finish(&self) {
-        let mut state = self.state.lock().unwrap();
-        state.finished = true;
+        self.finished.store(true, Ordering::Relaxed);
         self.ctx.request_repaint();
     }
 
@@ -84,6 +115,13 @@ impl ProgressBar<LangMessage> for GuiProgressBar {
 impl GuiProgressBar {
     pub fn render(&self, ui: &mut egui::Ui, lang: Lang) {
         let progress_bar_state = self.get_state();
+        let progress = self.progress.load(Ordering::Relaxed);
+        let total = self.total.load(Ordering::Relaxed);
+        let rate = {
+            let mut samples = self.samples.lock().unwrap();
+            add_sample(&mut samples, progress);
+            get_rate(&samples)
+        };
         if let Some(message) = &progress_bar_state.message {
             ui.label(message.to_string(lang));
         }
@@ -93,22 +131,38 @@ impl GuiProgressBar {
             .as_ref()
             .map(|u| u.size as f32)
             .unwrap_or(1.0);
-        let unit_name = progress_bar_state.unit.map(|u| u.name);
+        let unit_name = progress_bar_state.unit.as_ref().map(|u| u.name.clone());
 
-        let progress_string = if let Some(unit_name) = unit_name {
-            let progress = progress_bar_state.progress as f32 / unit_size;
-            let total = progress_bar_state.total as f32 / unit_size;
-            format!("{:.2} / {:.2} {}", progress, total, unit_name)
-        } else {
+        let progress_string = if let Some(unit_name) = &unit_name {
             format!(
-                "{} / {}",
-                progress_bar_state.progress, progress_bar_state.total
+                "{:.2} / {:.2} {}",
+                progress as f32 / unit_size,
+                total as f32 / unit_size,
+                unit_name
             )
+        } else {
+            format!("{} / {}", progress, total)
         };
-        egui::ProgressBar::new(
-            progress_bar_state.progress as f32 / progress_bar_state.total as f32,
-        )
-        .text(progress_string)
-        .ui(ui);
+        egui::ProgressBar::new(progress as f32 / total as f32)
+            .text(progress_string)
+            .ui(ui);
+
+        if self.finished.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(rate) = rate {
+            let rate_string = match &unit_name {
+                Some(unit_name) => format!("{:.2} {}/s", rate / unit_size as f64, unit_name),
+                // without a unit the bar counts files
+                None => LangMessage::FilesPerSecond(rate).to_string(lang),
+            };
+            let remaining = total.saturating_sub(progress);
+            let remaining_seconds = (remaining as f64 / rate).ceil() as u64;
+            ui.label(format!(
+                "{}, {}",
+                rate_string,
+                LangMessage::TimeRemaining(remaining_seconds).to_string(lang)
+            ));
+        }
     }
 }
@@ -0,0 +1,52 @@
+use std::future::Future;
+
+use log::info;
+
+use crate::utils;
+
+pub enum FetchResult<T> {
+    Remote(T),
+    /// The server couldn't be reached or the launcher works offline.
+    Offline {
+        local: anyhow::Result<T>,
+    },
+    /// The server was reached but the request failed.
+    RemoteError {
+        error: anyhow::Error,
+        local: anyhow::Result<T>,
+    },
+}
+
+/// Fetches the remote copy, falling back to the local one.
+/// The manifest and the metadata both go through here, so the same errors count as offline for both.
+pub async fn fetch_with_fallback<T, Remote, RemoteFut, Local, LocalFut>(
+    work_offline: bool,
+    fetch_remote: Remote,
+    read_local: Local,
+) -> FetchResult<T>
+where
+    Remote: FnOnce() -> RemoteFut,
+    RemoteFut: Future<Output = anyhow::Result<T>>,
+    Local: FnOnce() -> LocalFut,
+    LocalFut: Future<Output = anyhow::Result<T>>,
+{
+    if work_offline {
+        return FetchResult::Offline {
+            local: read_local().await,
+        };
+    }
+
+    match fetch_remote().await {
+        Ok(value) => FetchResult::Remote(value),
+        Err(e) if utils::is_connect_error(&e) => {
+            info!("Server unreachable, using the local copy:\n{:?}", e);
+            FetchResult::Offline {
+                local: read_local().await,
+            }
+        }
+        Err(error) => FetchResult::RemoteError {
+            error,
+            local: read_local().await,
+        },
+    }
+}
@@ -1,18 +1,91 @@
-use crate::config::runtime_config::Config;
+use crate::config::build_config;
+use crate::config::runtime_config::{Config, InstanceSort};
+use crate::config::settings_transfer;
+use crate::constants;
 use crate::lang::LangMessage;
+use crate::launcher::jvm_presets::JvmArgsPreset;
 use crate::utils;
 use crate::version::complete_version_metadata::CompleteVersionMetadata;
 
-use shared::java;
+use log::error;
+use shared::endpoints;
+use shared::http;
+use shared::java::{self, JavaPackageType};
+use shared::paths::get_logs_dir;
+use std::collections::HashMap;
 use tokio::runtime::Runtime;
 
+use super::assets_gc_state::AssetsGcState;
+use super::auth_state::AuthState;
+use super::background_task::{BackgroundTask, BackgroundTaskResult};
+use super::clear_caches_state::ClearCachesState;
+use super::colors;
+use super::diagnostics::DiagnosticsState;
+use super::disk_usage_state::DiskUsageState;
+use super::error_label;
+use super::import_state::ImportState;
+use super::issue_report_state::IssueReportState;
+use super::java_state::JavaState;
 use super::language_selector::LanguageSelector;
+use super::status_bar::StatusMessage;
+use super::tray;
+
+enum SettingsTransferStatus {
+    Exported,
+    Imported {
+        with_accounts: bool,
+        unknown_fields: Vec<String>,
+    },
+    Error(String),
+}
 
 pub struct SettingsState {
     language_selector: LanguageSelector,
     settings_opened: bool,
     picked_java_path: Option<String>,
     selected_xmx: Option<String>,
+    instance_xmx: String,
+    instance_xms: String,
+    total_memory_mb: Option<u32>,
+    instance_name: Option<String>,
+    extra_jvm_args: String,
+    env_vars: Vec<(String, String)>,
+    pre_launch_command: String,
+    post_exit_command: String,
+    wrapper_command: String,
+    quick_play_server: String,
+    width: String,
+    height: String,
+    game_dir_error: bool,
+    connection_test_task: Option<BackgroundTask<anyhow::Result<()>>>,
+    connection_test_result: Option<Result<(), String>>,
+    export_include_secrets: bool,
+    import_replace: bool,
+    transfer_status: Option<SettingsTransferStatus>,
+    diagnostics: DiagnosticsState,
+    issue_report: IssueReportState,
+    last_error: Option<StatusMessage>,
+    sync_running: bool,
+    import: ImportState,
+    assets_gc: AssetsGcState,
+    clear_caches: ClearCachesState,
+    disk_usage: DiskUsageState,
+}
+
+// any response proves the server is reachable, its root may well be a 404
+async fn test_connection() -> anyhow::Result<()> {
+    let mut server_base = reqwest::Url::parse(&build_config::get_version_manifest_url())?;
+    server_base.set_path("/");
+    server_base.set_query(None);
+    let response = http::send(http::with_server_headers(
+        http::client().get(server_base.as_str()),
+        server_base.as_str(),
+    ))
+    .await?;
+    if response.status() == reqwest::StatusCode::PROXY_AUTHENTICATION_REQUIRED {
+        response.error_for_status()?;
+    }
+    Ok(())
 }
 
 impl SettingsState {
@@ -22,13 +95,52 @@ impl SettingsState {
             settings_opened: false,
             picked_java_path: None,
             selected_xmx: None,
+            instance_xmx: String::new(),
+            instance_xms: String::new(),
+            total_memory_mb: utils::get_total_memory_mb(),
+            instance_name: None,
+            extra_jvm_args: String::new(),
+            env_vars: Vec::new(),
+            pre_launch_command: String::new(),
+            post_exit_command: String::new(),
+            wrapper_command: String::new(),
+            quick_play_server: String::new(),
+            width: String::new(),
+            height: String::new(),
+            game_dir_error: false,
+            connection_test_task: None,
+            connection_test_result: None,
+            export_include_secrets: false,
+            import_replace: false,
+            transfer_status: None,
+            diagnostics: DiagnosticsState::new(),
+            issue_report: IssueReportState::new(),
+            last_error: None,
+            sync_running: false,
+            import: ImportState::new(),
+            assets_gc: AssetsGcState::new(),
+            clear_caches: ClearCachesState::new(),
+            disk_usage: DiskUsageState::new(),
+        }
+    }
+    pub fn set_sync_running(&mut self, sync_running: bool) {
+        self.sync_running = sync_running;
+    }
+
+    /// Kept after the status bar clears, so issue reports include it.
+    pub fn set_last_error(&mut self, last_error: &StatusMessage) {
+        if self.last_error.as_ref() != Some(last_error) {
+            self.last_error = Some(last_error.clone());
         }
     }
+
     pub fn render_ui(
         &mut self,
         ui: &mut egui::Ui,
         runtime: &Runtime,
         config: &mut Config,
+        auth_state: &mut AuthState,
+        java_state: &mut JavaState,
         selected_metadata: Option<&CompleteVersionMetadata>,
     ) {
         if ui.button("⚙").clicked() {
@@ -40,6 +152,7 @@ impl SettingsState {
                 None
             };
             self.selected_xmx = Some(config.xmx.clone());
+            self.instance_name = None;
         }
 
         if ui.button("📂").clicked() {
@@ -48,7 +161,18 @@ impl SettingsState {
 
         self.language_selector.render_ui(ui, config);
 
-        self.render_settings_window(ui, runtime, config, selected_metadata);
+        if let Some(path) = java_state.take_downloaded_path() {
+            self.picked_java_path = Some(path.display().to_string());
+        }
+
+        self.render_settings_window(
+            ui,
+            runtime,
+            config,
+            auth_state,
+            java_state,
+            selected_metadata,
+        );
     }
 
     fn render_settings_window(
@@ -56,6 +180,8 @@ impl SettingsState {
         ui: &mut egui::Ui,
         runtime: &Runtime,
         config: &mut Config,
+        auth_state: &mut AuthState,
+        java_state: &mut JavaState,
         selected_metadata: Option<&CompleteVersionMetadata>,
     ) {
         let lang = config.lang;
@@ -97,31 +223,911 @@ impl SettingsState {
                         }
                     }
                 }
+                let mut use_jdk = config.java_package_type == JavaPackageType::Jdk;
+                if ui
+                    .checkbox(&mut use_jdk, LangMessage::DownloadFullJdk.to_string(lang))
+                    .changed()
+                {
+                    config.java_package_type = if use_jdk {
+                        JavaPackageType::Jdk
+                    } else {
+                        JavaPackageType::Jre
+                    };
+                    // the picked builds are of the other package type
+                    config.java_packages.clear();
+                    config.save();
+                    java_state.recheck();
+                }
+                java_state.render_download_button(ui, runtime, config, selected_metadata);
+                java_state.render_package_picker(ui, runtime, config, selected_metadata);
 
                 ui.label(LangMessage::JavaXMX.to_string(lang));
                 ui.text_edit_singleline(self.selected_xmx.as_mut().unwrap());
 
-                if utils::validate_xmx(self.selected_xmx.as_ref().unwrap())
-                    && config.xmx != self.selected_xmx.as_ref().unwrap().as_str()
+                match utils::parse_xmx_mb(self.selected_xmx.as_ref().unwrap()) {
+                    Some(mb) => {
+                        let xmx = format!("{}m", mb);
+                        if check_system_memory(ui, config, mb, self.total_memory_mb)
+                            && config.xmx != xmx
+                        {
+                            config.xmx = xmx;
+                            config.save();
+                        }
+                    }
+                    None => {
+                        let dark_mode = ui.style().visuals.dark_mode;
+                        ui.label(
+                            egui::RichText::new(
+                                LangMessage::InvalidJavaXMX {
+                                    current: config.xmx.clone(),
+                                }
+                                .to_string(lang),
+                            )
+                            .color(colors::error(dark_mode)),
+                        );
+                    }
+                }
+
+                if ui
+                    .checkbox(
+                        &mut config.work_offline,
+                        LangMessage::WorkOffline.to_string(lang),
+                    )
+                    .changed()
                 {
-                    config.xmx = self.selected_xmx.as_ref().unwrap().clone();
                     config.save();
                 }
 
-                self.render_close_launcher_checkbox(ui, config);
+                if let Some(selected_metadata) = selected_metadata {
+                    self.render_instance_memory(ui, config, selected_metadata);
+                    self.import
+                        .render_ui(ui, runtime, config, selected_metadata.get_name());
+                }
+
+                self.render_keep_launcher_open_checkbox(ui, config);
+
+                if tray::is_supported()
+                    && ui
+                        .checkbox(
+                            &mut config.minimize_to_tray,
+                            LangMessage::MinimizeToTray.to_string(lang),
+                        )
+                        .changed()
+                {
+                    config.save();
+                }
+
+                if build_config::get_discord_client_id().is_some()
+                    && ui
+                        .checkbox(
+                            &mut config.discord_rich_presence,
+                            LangMessage::DiscordRichPresence.to_string(lang),
+                        )
+                        .changed()
+                {
+                    config.save();
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label(LangMessage::SortInstances.to_string(lang));
+                    let mut instance_sort = config.instance_sort;
+                    egui::ComboBox::from_id_salt("instance_sort")
+                        .selected_text(LangMessage::InstanceSortName(instance_sort).to_string(lang))
+                        .show_ui(ui, |ui| {
+                            for option in [
+                                InstanceSort::LastPlayed,
+                                InstanceSort::RecentlySynced,
+                                InstanceSort::Alphabetical,
+                            ] {
+                                ui.selectable_value(
+                                    &mut instance_sort,
+                                    option,
+                                    LangMessage::InstanceSortName(option).to_string(lang),
+                                );
+                            }
+                        });
+                    if instance_sort != config.instance_sort {
+                        config.instance_sort = instance_sort;
+                        config.save();
+                    }
+                });
+
+                if ui
+                    .button(LangMessage::OpenLauncherLogs.to_string(lang))
+                    .clicked()
+                {
+                    open::that(get_logs_dir(&utils::get_data_dir())).unwrap();
+                }
+                self.issue_report.render_ui(
+                    ui,
+                    runtime,
+                    config,
+                    selected_metadata.map(|metadata| metadata.get_java_version()),
+                    selected_metadata.map(|metadata| metadata.get_name()),
+                    self.last_error.as_ref(),
+                );
+
+                self.render_folder_buttons(ui, config, selected_metadata);
+                self.disk_usage.render_ui(ui, runtime, config);
+
+                self.render_advanced_settings(ui, runtime, config, selected_metadata);
+
+                ui.separator();
+                self.render_settings_transfer(ui, config, auth_state);
             });
 
+        self.diagnostics.render_window(ui.ctx(), lang);
+
         self.settings_opened = settings_opened;
     }
 
-    fn render_close_launcher_checkbox(&mut self, ui: &mut egui::Ui, config: &mut Config) {
-        let old_close_launcher_after_launch = config.hide_launcher_after_launch;
+    fn render_advanced_settings(
+        &mut self,
+        ui: &mut egui::Ui,
+        runtime: &Runtime,
+        config: &mut Config,
+        selected_metadata: Option<&CompleteVersionMetadata>,
+    ) {
+        let lang = config.lang;
+        let response = egui::CollapsingHeader::new(LangMessage::AdvancedSettings.to_string(lang))
+            .id_salt("advanced_settings")
+            .default_open(config.advanced_settings_open)
+            .show(ui, |ui| {
+                self.render_download_rate_limit(ui, config);
+                self.render_request_timeout(ui, config);
+                self.render_metadata_timeout(ui, config);
+                self.render_proxy_settings(ui, runtime, config);
+
+                if let Some(selected_metadata) = selected_metadata {
+                    self.render_instance_settings(ui, config, selected_metadata.get_name());
+                }
+
+                if ui
+                    .checkbox(
+                        &mut config.auto_open_auth_urls,
+                        LangMessage::AutoOpenAuthUrls.to_string(lang),
+                    )
+                    .changed()
+                {
+                    config.save();
+                }
+
+                self.assets_gc
+                    .render_ui(ui, runtime, config, self.sync_running);
+                self.clear_caches.render_ui(ui, runtime, config);
+
+                self.diagnostics.render_button(
+                    ui,
+                    runtime,
+                    config,
+                    selected_metadata.map(|metadata| metadata.get_java_version()),
+                );
+            });
+        if response.header_response.clicked() {
+            config.advanced_settings_open = !config.advanced_settings_open;
+            config.save();
+        }
+    }
+
+    fn render_settings_transfer(
+        &mut self,
+        ui: &mut egui::Ui,
+        config: &mut Config,
+        auth_state: &mut AuthState,
+    ) {
+        let lang = config.lang;
+        let dark_mode = ui.style().visuals.dark_mode;
+
         ui.checkbox(
-            &mut config.hide_launcher_after_launch,
-            LangMessage::HideLauncherAfterLaunch.to_string(config.lang),
+            &mut self.export_include_secrets,
+            LangMessage::IncludeAccountsInExport.to_string(lang),
         );
-        if old_close_launcher_after_launch != config.hide_launcher_after_launch {
+        if self.export_include_secrets {
+            ui.label(
+                egui::RichText::new(LangMessage::ExportSecretsWarning.to_string(lang))
+                    .color(colors::partial_error(dark_mode)),
+            );
+        }
+        ui.checkbox(
+            &mut self.import_replace,
+            LangMessage::ReplaceSettingsOnImport.to_string(lang),
+        );
+
+        ui.horizontal(|ui| {
+            if ui
+                .button(LangMessage::ExportSettings.to_string(lang))
+                .clicked()
+            {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("JSON", &["json"])
+                    .set_file_name("settings.json")
+                    .save_file()
+                {
+                    let auth_data = self
+                        .export_include_secrets
+                        .then(|| auth_state.export_auth_data());
+                    self.transfer_status = Some(
+                        match settings_transfer::export_settings(config, auth_data, &path) {
+                            Ok(()) => SettingsTransferStatus::Exported,
+                            Err(e) => SettingsTransferStatus::Error(format!("{:?}", e)),
+                        },
+                    );
+                }
+            }
+
+            if ui
+                .button(LangMessage::ImportSettings.to_string(lang))
+                .clicked()
+            {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("JSON", &["json"])
+                    .pick_file()
+                {
+                    self.transfer_status = Some(
+                        match settings_transfer::import_settings(config, &path, self.import_replace)
+                        {
+                            Ok(imported) => {
+                                *config = imported.config;
+                                config.save();
+                                http::set_client_config(config.network.get_client_config());
+                                endpoints::set_endpoints(config.network.get_endpoints());
+                                self.selected_xmx = Some(config.xmx.clone());
+
+                                let with_accounts = imported.auth_data.is_some();
+                                if let Some(auth_data) = imported.auth_data {
+                                    auth_state.import_auth_data(config, auth_data);
+                                }
+                                SettingsTransferStatus::Imported {
+                                    with_accounts,
+                                    unknown_fields: imported.unknown_fields,
+                                }
+                            }
+                            Err(e) => SettingsTransferStatus::Error(format!("{:?}", e)),
+                        },
+                    );
+                }
+            }
+        });
+
+        match &self.transfer_status {
+            Some(SettingsTransferStatus::Exported) => {
+                ui.label(
+                    egui::RichText::new(LangMessage::SettingsExported.to_string(lang))
+                        .color(colors::ok(dark_mode)),
+                );
+            }
+            Some(SettingsTransferStatus::Imported {
+                with_accounts,
+                unknown_fields,
+            }) => {
+                ui.label(
+                    egui::RichText::new(LangMessage::SettingsImported.to_string(lang))
+                        .color(colors::ok(dark_mode)),
+                );
+                if !with_accounts {
+                    ui.label(LangMessage::ReauthorizeAfterImport.to_string(lang));
+                }
+                if !unknown_fields.is_empty() {
+                    ui.label(
+                        egui::RichText::new(
+                            LangMessage::UnknownSettingsIgnored {
+                                fields: unknown_fields.join(", "),
+                            }
+                            .to_string(lang),
+                        )
+                        .color(colors::partial_error(dark_mode)),
+                    );
+                }
+            }
+            Some(SettingsTransferStatus::Error(e)) => {
+                error_label::render(
+                    ui,
+                    egui::RichText::new(LangMessage::SettingsTransferError.to_string(lang))
+                        .color(colors::error(dark_mode)),
+                    Some(e),
+                    lang,
+                );
+            }
+            None => {}
+        }
+    }
+
+    fn render_folder_buttons(
+        &self,
+        ui: &mut egui::Ui,
+        config: &Config,
+        selected_metadata: Option<&CompleteVersionMetadata>,
+    ) {
+        let lang = config.lang;
+
+        let mut folders = vec![];
+        if let Some(selected_metadata) = selected_metadata {
+            let instance_dir = config.get_game_dir(selected_metadata.get_name());
+            folders.push((LangMessage::OpenInstanceFolder, instance_dir.clone()));
+            folders.push((LangMessage::OpenGameLogs, instance_dir.join("logs")));
+            folders.push((
+                LangMessage::OpenCrashReports,
+                instance_dir.join("crash-reports"),
+            ));
+        }
+        folders.push((LangMessage::OpenAssetsFolder, config.get_assets_dir()));
+
+        ui.horizontal_wrapped(|ui| {
+            for (message, path) in folders {
+                if ui
+                    .add_enabled(path.exists(), egui::Button::new(message.to_string(lang)))
+                    .clicked()
+                {
+                    if let Err(e) = open::that(&path) {
+                        error!("Error opening {:?}:\n{:?}", path, e);
+                    }
+                }
+            }
+        });
+    }
+
+    fn render_download_rate_limit(&mut self, ui: &mut egui::Ui, config: &mut Config) {
+        let mut max_download_rate_kbps = config.max_download_rate_kbps.unwrap_or(0);
+        ui.horizontal(|ui| {
+            ui.label(LangMessage::MaxDownloadRate.to_string(config.lang));
+            if ui
+                .add(egui::DragValue::new(&mut max_download_rate_kbps).suffix(" KB/s"))
+                .changed()
+            {
+                config.max_download_rate_kbps =
+                    Some(max_download_rate_kbps).filter(|&rate| rate > 0);
+                config.save();
+            }
+        });
+    }
+
+    fn render_request_timeout(&mut self, ui: &mut egui::Ui, config: &mut Config) {
+        let mut request_timeout_secs = config.network.request_timeout_secs.unwrap_or(0);
+        ui.horizontal(|ui| {
+            ui.label(LangMessage::RequestTimeout.to_string(config.lang));
+            if ui
+                .add(egui::DragValue::new(&mut request_timeout_secs).suffix(" s"))
+                .changed()
+            {
+                config.network.request_timeout_secs =
+                    Some(request_timeout_secs).filter(|&timeout| timeout > 0);
+                config.save();
+                http::set_client_config(config.network.get_client_config());
+            }
+        });
+    }
+
+    fn render_metadata_timeout(&mut self, ui: &mut egui::Ui, config: &mut Config) {
+        ui.horizontal(|ui| {
+            ui.label(LangMessage::MetadataTimeout.to_string(config.lang));
+            if ui
+                .add(egui::DragValue::new(&mut config.network.metadata_timeout_secs).suffix(" s"))
+                .changed()
+            {
+                config.save();
+            }
+        });
+    }
+
+    fn render_proxy_settings(&mut self, ui: &mut egui::Ui, runtime: &Runtime, config: &mut Config) {
+        let lang = config.lang;
+        let dark_mode = ui.style().visuals.dark_mode;
+
+        let mut changed = false;
+        egui::Grid::new("proxy_settings")
+            .num_columns(2)
+            .show(ui, |ui| {
+                let network = &mut config.network;
+                for (label, value, password) in [
+                    (LangMessage::ProxyUrl, &mut network.proxy_url, false),
+                    (
+                        LangMessage::ProxyUsername,
+                        &mut network.proxy_username,
+                        false,
+                    ),
+                    (
+                        LangMessage::ProxyPassword,
+                        &mut network.proxy_password,
+                        true,
+                    ),
+                ] {
+                    ui.label(label.to_string(lang));
+                    let mut text = value.clone().unwrap_or_default();
+                    if ui
+                        .add(egui::TextEdit::singleline(&mut text).password(password))
+                        .changed()
+                    {
+                        *value = Some(text).filter(|text| !text.is_empty());
+                        changed = true;
+                    }
+                    ui.end_row();
+                }
+            });
+
+        let proxy_valid = config
+            .network
+            .get_proxy_config()
+            .is_none_or(|proxy| proxy.to_proxy().is_ok());
+        if !proxy_valid {
+            ui.label(
+                egui::RichText::new(LangMessage::InvalidProxyUrl.to_string(lang))
+                    .color(colors::error(dark_mode)),
+            );
+        }
+        if changed {
             config.save();
+            if proxy_valid {
+                http::set_client_config(config.network.get_client_config());
+            }
+        }
+
+        if let Some(task) = self.connection_test_task.as_ref() {
+            if task.has_result() {
+                let task = self.connection_test_task.take().unwrap();
+                if let BackgroundTaskResult::Finished(result) = task.take_result() {
+                    self.connection_test_result = Some(result.map_err(|e| format!("{:?}", e)));
+                }
+            }
+        }
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    self.connection_test_task.is_none() && proxy_valid,
+                    egui::Button::new(LangMessage::TestConnection.to_string(lang)),
+                )
+                .clicked()
+            {
+                self.connection_test_result = None;
+                let ctx = ui.ctx().clone();
+                self.connection_test_task = Some(BackgroundTask::with_callback(
+                    test_connection(),
+                    runtime,
+                    Box::new(move || ctx.request_repaint()),
+                ));
+            }
+
+            if self.connection_test_task.is_some() {
+                ui.spinner();
+            }
+            match &self.connection_test_result {
+                Some(Ok(())) => {
+                    ui.label(LangMessage::ConnectionOk.to_string(lang));
+                }
+                Some(Err(e)) => {
+                    error_label::render(
+                        ui,
+                        egui::RichText::new(LangMessage::ConnectionFailed.to_string(lang))
+                            .color(colors::error(dark_mode)),
+                        Some(e),
+                        lang,
+                    );
+                }
+                None => {}
+            }
+        });
+    }
+
+    fn render_instance_settings(
+        &mut self,
+        ui: &mut egui::Ui,
+        config: &mut Config,
+        instance_name: &str,
+    ) {
+        let lang = config.lang;
+        let dark_mode = ui.style().visuals.dark_mode;
+
+        if self.instance_name.as_deref() != Some(instance_name) {
+            self.instance_name = Some(instance_name.to_string());
+            let instance_settings = config.get_instance_settings(instance_name);
+            self.extra_jvm_args = instance_settings.extra_jvm_args;
+            self.env_vars = instance_settings.env_vars.into_iter().collect();
+            self.env_vars.sort();
+            self.pre_launch_command = instance_settings.pre_launch_command;
+            self.post_exit_command = instance_settings.post_exit_command;
+            self.wrapper_command = instance_settings.wrapper_command.unwrap_or_default();
+            self.quick_play_server = instance_settings.quick_play_server.unwrap_or_default();
+            self.instance_xmx = instance_settings.xmx.unwrap_or_default();
+            self.instance_xms = instance_settings.xms.unwrap_or_default();
+            self.width = instance_settings
+                .width
+                .map(|width| width.to_string())
+                .unwrap_or_default();
+            self.height = instance_settings
+                .height
+                .map(|height| height.to_string())
+                .unwrap_or_default();
+            self.game_dir_error = false;
+        }
+
+        ui.label(LangMessage::JvmArgsPreset.to_string(lang));
+        let mut preset = config.get_instance_settings(instance_name).jvm_args_preset;
+        egui::ComboBox::from_id_salt("jvm_args_preset")
+            .selected_text(LangMessage::JvmArgsPresetName(preset).to_string(lang))
+            .show_ui(ui, |ui| {
+                for option in [
+                    JvmArgsPreset::Default,
+                    JvmArgsPreset::Aikar,
+                    JvmArgsPreset::Custom,
+                ] {
+                    ui.selectable_value(
+                        &mut preset,
+                        option,
+                        LangMessage::JvmArgsPresetName(option).to_string(lang),
+                    );
+                }
+            });
+        if config.get_instance_settings(instance_name).jvm_args_preset != preset {
+            config
+                .get_instance_settings_mut(instance_name)
+                .jvm_args_preset = preset;
+            config.save();
+        }
+
+        ui.label(LangMessage::ExtraJvmArgs.to_string(lang));
+        ui.add(egui::TextEdit::multiline(&mut self.extra_jvm_args).desired_rows(2));
+        if shell_words::split(&self.extra_jvm_args).is_ok() {
+            if config.get_instance_settings(instance_name).extra_jvm_args != self.extra_jvm_args {
+                config
+                    .get_instance_settings_mut(instance_name)
+                    .extra_jvm_args = self.extra_jvm_args.clone();
+                config.save();
+            }
+        } else {
+            ui.label(
+                egui::RichText::new(LangMessage::InvalidArguments.to_string(lang))
+                    .color(colors::error(dark_mode)),
+            );
+        }
+
+        self.render_env_vars(ui, config, instance_name);
+
+        ui.label(LangMessage::PreLaunchCommand.to_string(lang));
+        ui.text_edit_singleline(&mut self.pre_launch_command);
+        ui.label(LangMessage::PostExitCommand.to_string(lang));
+        ui.text_edit_singleline(&mut self.post_exit_command);
+
+        let instance_settings = config.get_instance_settings(instance_name);
+        if instance_settings.pre_launch_command != self.pre_launch_command
+            || instance_settings.post_exit_command != self.post_exit_command
+        {
+            let instance_settings = config.get_instance_settings_mut(instance_name);
+            instance_settings.pre_launch_command = self.pre_launch_command.clone();
+            instance_settings.post_exit_command = self.post_exit_command.clone();
+            config.save();
+        }
+
+        ui.label(LangMessage::WrapperCommand.to_string(lang));
+        ui.add(egui::TextEdit::singleline(&mut self.wrapper_command).hint_text("gamemoderun"));
+        if shell_words::split(&self.wrapper_command).is_ok() {
+            let wrapper_command = Some(self.wrapper_command.trim().to_string())
+                .filter(|wrapper_command| !wrapper_command.is_empty());
+            if config.get_instance_settings(instance_name).wrapper_command != wrapper_command {
+                config
+                    .get_instance_settings_mut(instance_name)
+                    .wrapper_command = wrapper_command;
+                config.save();
+            }
+        } else {
+            ui.label(
+                egui::RichText::new(LangMessage::InvalidArguments.to_string(lang))
+                    .color(colors::error(dark_mode)),
+            );
+        }
+
+        ui.label(LangMessage::QuickPlayServer.to_string(lang));
+        ui.add(
+            egui::TextEdit::singleline(&mut self.quick_play_server)
+                .hint_text("mc.example.com:25565"),
+        );
+        let quick_play_server = Some(self.quick_play_server.trim().to_string())
+            .filter(|quick_play_server| !quick_play_server.is_empty());
+        if config
+            .get_instance_settings(instance_name)
+            .quick_play_server
+            != quick_play_server
+        {
+            config
+                .get_instance_settings_mut(instance_name)
+                .quick_play_server = quick_play_server;
+            config.save();
+        }
+
+        self.render_resolution(ui, config, instance_name);
+        self.render_game_dir(ui, config, instance_name);
+
+        let mut pin_version = config.get_instance_settings(instance_name).pin_version;
+        if ui
+            .checkbox(
+                &mut pin_version,
+                LangMessage::PinInstanceVersion.to_string(lang),
+            )
+            .changed()
+        {
+            config.get_instance_settings_mut(instance_name).pin_version = pin_version;
+            config.save();
+        }
+    }
+
+    fn render_game_dir(&mut self, ui: &mut egui::Ui, config: &mut Config, instance_name: &str) {
+        let lang = config.lang;
+        let dark_mode = ui.style().visuals.dark_mode;
+
+        let game_dir = config.get_game_dir(instance_name);
+        ui.label(LangMessage::GameDir.to_string(lang));
+        ui.code(game_dir.display().to_string());
+        ui.horizontal(|ui| {
+            if ui
+                .button(LangMessage::ChangeGameDir.to_string(lang))
+                .clicked()
+            {
+                if let Some(path) = rfd::FileDialog::new()
+                    .set_directory(&game_dir)
+                    .pick_folder()
+                {
+                    self.game_dir_error = !utils::is_dir_writable(&path);
+                    if !self.game_dir_error {
+                        config.get_instance_settings_mut(instance_name).game_dir =
+                            Some(path.display().to_string());
+                        config.save();
+                    }
+                }
+            }
+            if config
+                .get_instance_settings(instance_name)
+                .game_dir
+                .is_some()
+                && ui
+                    .button(LangMessage::ResetGameDir.to_string(lang))
+                    .clicked()
+            {
+                config.get_instance_settings_mut(instance_name).game_dir = None;
+                config.save();
+                self.game_dir_error = false;
+            }
+        });
+        if self.game_dir_error {
+            ui.label(
+                egui::RichText::new(LangMessage::GameDirNotWritable.to_string(lang))
+                    .color(colors::error(dark_mode)),
+            );
+        }
+    }
+
+    fn render_instance_memory(
+        &mut self,
+        ui: &mut egui::Ui,
+        config: &mut Config,
+        selected_metadata: &CompleteVersionMetadata,
+    ) {
+        let lang = config.lang;
+        let dark_mode = ui.style().visuals.dark_mode;
+        let instance_name = selected_metadata.get_name();
+
+        let default_xmx_mb = config.get_default_xmx_mb(selected_metadata.get_recommended_xmx());
+
+        ui.label(LangMessage::InstanceMemory.to_string(lang));
+        ui.horizontal(|ui| {
+            ui.label("Xmx");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.instance_xmx)
+                    .hint_text(format!("{}m", default_xmx_mb))
+                    .desired_width(60.0),
+            );
+            ui.label("Xms");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.instance_xms)
+                    .hint_text(format!("{}m", constants::DEFAULT_JAVA_XMS_MB))
+                    .desired_width(60.0),
+            );
+        });
+
+        let parse_memory = |value: &str| -> Result<Option<String>, ()> {
+            let value = value.trim();
+            if value.is_empty() {
+                return Ok(None);
+            }
+            match utils::parse_xmx_mb(value) {
+                Some(mb) => Ok(Some(format!("{}m", mb))),
+                None => Err(()),
+            }
+        };
+        match (
+            parse_memory(&self.instance_xmx),
+            parse_memory(&self.instance_xms),
+        ) {
+            (Ok(xmx), Ok(xms)) => {
+                let accepted = xmx
+                    .as_deref()
+                    .and_then(utils::parse_xmx_mb)
+                    .is_none_or(|mb| check_system_memory(ui, config, mb, self.total_memory_mb));
+                let instance_settings = config.get_instance_settings(instance_name);
+                if accepted && (instance_settings.xmx != xmx || instance_settings.xms != xms) {
+                    let instance_settings = config.get_instance_settings_mut(instance_name);
+                    instance_settings.xmx = xmx;
+                    instance_settings.xms = xms;
+                    config.save();
+                }
+            }
+            _ => {
+                ui.label(
+                    egui::RichText::new(
+                        LangMessage::InvalidJavaXMX {
+                            current: format!(
+                                "{}m",
+                                config.get_xmx_mb(
+                                    instance_name,
+                                    selected_metadata.get_recommended_xmx()
+                                )
+                            ),
+                        }
+                        .to_string(lang),
+                    )
+                    .color(colors::error(dark_mode)),
+                );
+            }
+        }
+    }
+
+    fn render_resolution(&mut self, ui: &mut egui::Ui, config: &mut Config, instance_name: &str) {
+        let lang = config.lang;
+        let dark_mode = ui.style().visuals.dark_mode;
+
+        ui.label(LangMessage::WindowResolution.to_string(lang));
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.width)
+                    .hint_text("854")
+                    .desired_width(60.0),
+            );
+            ui.label("×");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.height)
+                    .hint_text("480")
+                    .desired_width(60.0),
+            );
+        });
+
+        let parse_dimension = |value: &str| -> Result<Option<u32>, ()> {
+            let value = value.trim();
+            if value.is_empty() {
+                return Ok(None);
+            }
+            match value.parse::<u32>() {
+                Ok(value) if value > 0 => Ok(Some(value)),
+                _ => Err(()),
+            }
+        };
+        match (parse_dimension(&self.width), parse_dimension(&self.height)) {
+            // the game only takes the size when both are given
+            (Ok(Some(_)), Ok(None)) | (Ok(None), Ok(Some(_))) => {
+                ui.label(
+                    egui::RichText::new(LangMessage::IncompleteResolution.to_string(lang))
+                        .color(colors::error(dark_mode)),
+                );
+            }
+            (Ok(width), Ok(height)) => {
+                let instance_settings = config.get_instance_settings(instance_name);
+                if instance_settings.width != width || instance_settings.height != height {
+                    let instance_settings = config.get_instance_settings_mut(instance_name);
+                    instance_settings.width = width;
+                    instance_settings.height = height;
+                    config.save();
+                }
+            }
+            _ => {
+                ui.label(
+                    egui::RichText::new(LangMessage::InvalidResolution.to_string(lang))
+                        .color(colors::error(dark_mode)),
+                );
+            }
+        }
+
+        let mut fullscreen = config.get_instance_settings(instance_name).fullscreen;
+        if ui
+            .checkbox(&mut fullscreen, LangMessage::Fullscreen.to_string(lang))
+            .changed()
+        {
+            config.get_instance_settings_mut(instance_name).fullscreen = fullscreen;
+            config.save();
+        }
+    }
+
+    fn render_env_vars(&mut self, ui: &mut egui::Ui, config: &mut Config, instance_name: &str) {
+        let lang = config.lang;
+
+        ui.label(LangMessage::EnvironmentVariables.to_string(lang));
+        let mut removed_index = None;
+        egui::Grid::new("env_vars_grid").show(ui, |ui| {
+            for (i, (name, value)) in self.env_vars.iter_mut().enumerate() {
+                ui.add(egui::TextEdit::singleline(name).hint_text("NAME"));
+                ui.add(egui::TextEdit::singleline(value).hint_text("value"));
+                if ui.button("🗑").clicked() {
+                    removed_index = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(i) = removed_index {
+            self.env_vars.remove(i);
+        }
+        if ui.button("➕").clicked() {
+            self.env_vars.push((String::new(), String::new()));
+        }
+
+        // invalid entries are never saved, the game would fail to start or get other variables
+        let mut env_vars = HashMap::new();
+        let mut error = None;
+        for (name, value) in &self.env_vars {
+            let name = name.trim();
+            if name.is_empty() {
+                continue;
+            }
+            if name.contains(['=', '\0']) || value.contains('\0') {
+                error = Some(LangMessage::InvalidEnvVar {
+                    name: name.to_string(),
+                });
+                break;
+            }
+            if env_vars.insert(name.to_string(), value.clone()).is_some() {
+                error = Some(LangMessage::DuplicateEnvVar {
+                    name: name.to_string(),
+                });
+                break;
+            }
+        }
+        if let Some(error) = error {
+            let dark_mode = ui.style().visuals.dark_mode;
+            ui.label(egui::RichText::new(error.to_string(lang)).color(colors::error(dark_mode)));
+        } else if config.get_instance_settings(instance_name).env_vars != env_vars {
+            config.get_instance_settings_mut(instance_name).env_vars = env_vars;
+            config.save();
+        }
+    }
+
+    fn render_keep_launcher_open_checkbox(&mut self, ui: &mut egui::Ui, config: &mut Config) {
+        let mut keep_launcher_open = !config.hide_launcher_after_launch;
+        if ui
+            .checkbox(
+                &mut keep_launcher_open,
+                LangMessage::KeepLauncherOpenAfterLaunch.to_string(config.lang),
+            )
+            .changed()
+        {
+            config.hide_launcher_after_launch = !keep_launcher_open;
+            config.save();
+        }
+    }
+}
+
+// more than the physical memory is not accepted, getting close to it only shows a warning
+fn check_system_memory(
+    ui: &mut egui::Ui,
+    config: &Config,
+    xmx_mb: u32,
+    total_memory_mb: Option<u32>,
+) -> bool {
+    let Some(total_mb) = total_memory_mb else {
+        return true;
+    };
+    let dark_mode = ui.style().visuals.dark_mode;
+    if xmx_mb > total_mb {
+        ui.label(
+            egui::RichText::new(
+                LangMessage::JavaXMXExceedsSystemMemory { total_mb }.to_string(config.lang),
+            )
+            .color(colors::error(dark_mode)),
+        );
+        false
+    } else {
+        if xmx_mb as u64 * 100 > total_mb as u64 * constants::HIGH_MEMORY_PERCENT {
+            ui.label(
+                egui::RichText::new(
+                    LangMessage::JavaXMXHighMemory { total_mb }.to_string(config.lang),
+                )
+                .color(colors::partial_error(dark_mode)),
+            );
         }
+        true
     }
 }
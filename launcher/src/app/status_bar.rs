@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+
+use crate::lang::{Lang, LangMessage};
+
+use super::colors;
+use super::error_label;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Offline,
+    Timeout,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct StatusMessage {
+    pub severity: Severity,
+    pub message: LangMessage,
+    pub details: Option<String>,
+}
+
+impl StatusMessage {
+    pub fn new(severity: Severity, message: LangMessage, details: Option<&str>) -> Self {
+        StatusMessage {
+            severity,
+            message,
+            details: details.map(str::to_string),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatusSource {
+    Auth,
+    Metadata,
+    Sync,
+    Java,
+    Launch,
+    DataDir,
+}
+
+pub struct StatusBar {
+    // the counter orders messages by when they first appeared
+    messages: HashMap<StatusSource, (u64, StatusMessage)>,
+    counter: u64,
+}
+
+impl StatusBar {
+    pub fn new() -> Self {
+        StatusBar {
+            messages: HashMap::new(),
+            counter: 0,
+        }
+    }
+
+    pub fn update(&mut self, source: StatusSource, message: Option<StatusMessage>) {
+        let Some(message) = message else {
+            self.messages.remove(&source);
+            return;
+        };
+        if self
+            .messages
+            .get(&source)
+            .is_some_and(|(_, current)| current == &message)
+        {
+            return;
+        }
+        self.counter += 1;
+        self.messages.insert(source, (self.counter, message));
+    }
+
+    pub fn get_latest(&self) -> Option<&StatusMessage> {
+        self.messages
+            .values()
+            .max_by_key(|(counter, _)| *counter)
+            .map(|(_, message)| message)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    pub fn render(&self, ui: &mut egui::Ui, lang: Lang) {
+        let Some(message) = self.get_latest() else {
+            return;
+        };
+        let dark_mode = ui.style().visuals.dark_mode;
+        let color = match message.severity {
+            Severity::Error => colors::error(dark_mode),
+            Severity::Warning => colors::partial_error(dark_mode),
+            Severity::Offline => colors::offline(dark_mode),
+            Severity::Timeout => colors::timeout(dark_mode),
+        };
+        error_label::render(
+            ui,
+            egui::RichText::new(message.message.to_string(lang)).color(color),
+            message.details.as_deref(),
+            lang,
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use std::sync::mpsc;
+
+use tokio::runtime::Runtime;
+
+use crate::config::build_config;
+use crate::lang::{Lang, LangMessage};
+use crate::utils;
+
+pub enum TrayCommand {
+    Show,
+    Launch(String),
+    Quit,
+}
+
+pub fn is_supported() -> bool {
+    cfg!(any(windows, target_os = "linux"))
+}
+
+fn send_command(sender: &mpsc::Sender<TrayCommand>, ctx: &egui::Context, command: TrayCommand) {
+    if !matches!(command, TrayCommand::Quit) {
+        // the window may be hidden, in which case the app won't poll the commands by itself
+        utils::show_window(ctx);
+        ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+    }
+    let _ = sender.send(command);
+    ctx.request_repaint();
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use ksni::TrayMethods as _;
+
+    use super::*;
+
+    struct LinuxTray {
+        instance_names: Vec<String>,
+        lang: Lang,
+        icon: ksni::Icon,
+        sender: mpsc::Sender<TrayCommand>,
+        ctx: egui::Context,
+    }
+
+    impl ksni::Tray for LinuxTray {
+        fn id(&self) -> String {
+            build_config::get_data_launcher_name()
+        }
+
+        fn title(&self) -> String {
+            build_config::get_launcher_name()
+        }
+
+        fn icon_pixmap(&self) -> Vec<ksni::Icon> {
+            vec![self.icon.clone()]
+        }
+
+        fn activate(&mut self, _x: i32, _y: i32) {
+            send_command(&self.sender, &self.ctx, TrayCommand::Show);
+        }
+
+        fn menu(&self) -> Vec<ksni::MenuItem<Self>> {
+            let mut items: Vec<ksni::MenuItem<Self>> = vec![];
+            for instance_name in &self.instance_names {
+                let instance_name = instance_name.clone();
+                items.push(
+                    ksni::menu::StandardItem {
+                        label: instance_name.replace('_', "__"),
+                        activate: Box::new(move |tray: &mut Self| {
+                            send_command(
+                                &tray.sender,
+                                &tray.ctx,
+                                TrayCommand::Launch(instance_name.clone()),
+                            );
+                        }),
+                        ..Default::default()
+                    }
+                    .into(),
+                );
+            }
+            if !items.is_empty() {
+                items.push(ksni::MenuItem::Separator);
+            }
+            items.push(
+                ksni::menu::StandardItem {
+                    label: LangMessage::ShowLauncher.to_string(self.lang),
+                    activate: Box::new(|tray: &mut Self| {
+                        send_command(&tray.sender, &tray.ctx, TrayCommand::Show);
+                    }),
+                    ..Default::default()
+                }
+                .into(),
+            );
+            items.push(
+                ksni::menu::StandardItem {
+                    label: LangMessage::Quit.to_string(self.lang),
+                    activate: Box::new(|tray: &mut Self| {
+                        send_command(&tray.sender, &tray.ctx, TrayCommand::Quit);
+                    }),
+                    ..Default::default()
+                }
+                .into(),
+            );
+            items
+        }
+    }
+
+    pub struct PlatformTray {
+        handle: ksni::Handle<LinuxTray>,
+    }
+
+    impl PlatformTray {
+        pub fn new(
+            runtime: &Runtime,
+            ctx: &egui::Context,
+            sender: mpsc::Sender<TrayCommand>,
+            instance_names: Vec<String>,
+            lang: Lang,
+        ) -> anyhow::Result<Self> {
+            let icon_data = utils::get_icon_data();
+            // ksni expects ARGB32 in network byte order
+            let data = icon_data
+                .rgba
+                .chunks_exact(4)
+                .flat_map(|pixel| [pixel[3], pixel[0], pixel[1], pixel[2]])
+                .collect();
+            let tray = LinuxTray {
+                instance_names,
+                lang,
+                icon: ksni::Icon {
+                    width: icon_data.width as i32,
+                    height: icon_data.height as i32,
+                    data,
+                },
+                sender,
+                ctx: ctx.clone(),
+            };
+            let handle = runtime.block_on(tray.spawn())?;
+            Ok(PlatformTray { handle })
+        }
+
+        pub fn update(&self, runtime: &Runtime, instance_names: Vec<String>, lang: Lang) {
+            runtime.block_on(self.handle.update(|tray| {
+                tray.instance_names = instance_names;
+                tray.lang = lang;
+            }));
+        }
+    }
+
+    impl Drop for PlatformTray {
+        fn drop(&mut self) {
+            drop(self.handle.shutdown());
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use tray_icon::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+    use tray_icon::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
+
+    use super::*;
+
+    const SHOW_ID: &str = "show";
+    const QUIT_ID: &str = "quit";
+    const LAUNCH_ID_PREFIX: &str = "launch:";
+
+    fn build_menu(instance_names: &[String], lang: Lang) -> anyhow::Result<Menu> {
+        let menu = Menu::new();
+        for instance_name in instance_names {
+            menu.append(&MenuItem::with_id(
+                format!("{}{}", LAUNCH_ID_PREFIX, instance_name),
+                instance_name,
+                true,
+                None,
+            ))?;
+        }
+        if !instance_names.is_empty() {
+            menu.append(&PredefinedMenuItem::separator())?;
+        }
+        menu.append(&MenuItem::with_id(
+            SHOW_ID,
+            LangMessage::ShowLauncher.to_string(lang),
+            true,
+            None,
+        ))?;
+        menu.append(&MenuItem::with_id(
+            QUIT_ID,
+            LangMessage::Quit.to_string(lang),
+            true,
+            None,
+        ))?;
+        Ok(menu)
+    }
+
+    pub struct PlatformTray {
+        tray_icon: TrayIcon,
+    }
+
+    impl PlatformTray {
+        pub fn new(
+            _runtime: &Runtime,
+            ctx: &egui::Context,
+            sender: mpsc::Sender<TrayCommand>,
+            instance_names: Vec<String>,
+            lang: Lang,
+        ) -> anyhow::Result<Self> {
+            let icon_data = utils::get_icon_data();
+            let icon =
+                tray_icon::Icon::from_rgba(icon_data.rgba, icon_data.width, icon_data.height)?;
+            let tray_icon = TrayIconBuilder::new()
+                .with_menu(Box::new(build_menu(&instance_names, lang)?))
+                .with_tooltip(build_config::get_launcher_name())
+                .with_icon(icon)
+                .build()?;
+
+            let menu_sender = sender.clone();
+            let menu_ctx = ctx.clone();
+            MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+                let command = match event.id.0.as_str() {
+                    SHOW_ID => TrayCommand::Show,
+                    QUIT_ID => TrayCommand::Quit,
+                    id => match id.strip_prefix(LAUNCH_ID_PREFIX) {
+                        Some(instance_name) => TrayCommand::Launch(instance_name.to_string()),
+                        None => return,
+                    },
+                };
+                send_command(&menu_sender, &menu_ctx, command);
+            }));
+
+            let tray_ctx = ctx.clone();
+            TrayIconEvent::set_event_handler(Some(move |event: TrayIconEvent| {
+                if let TrayIconEvent::Click {
+                    button: MouseButton::Left,
+                    button_state: MouseButtonState::Up,
+                    ..
+                } = event
+                {
+                    send_command(&sender, &tray_ctx, TrayCommand::Show);
+                }
+            }));
+
+            Ok(PlatformTray { tray_icon })
+        }
+
+        pub fn update(&self, _runtime: &Runtime, instance_names: Vec<String>, lang: Lang) {
+            match build_menu(&instance_names, lang) {
+                Ok(menu) => self.tray_icon.set_menu(Some(Box::new(menu))),
+                Err(e) => log::error!("Error updating tray menu:\n{:?}", e),
+            }
+        }
+    }
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+mod platform {
+    use super::*;
+
+    #[derive(thiserror::Error, Debug)]
+    pub enum TrayError {
+        #[error("Tray icon is not supported on this platform")]
+        Unsupported,
+    }
+
+    pub struct PlatformTray;
+
+    impl PlatformTray {
+        pub fn new(
+            _runtime: &Runtime,
+            _ctx: &egui::Context,
+            _sender: mpsc::Sender<TrayCommand>,
+            _instance_names: Vec<String>,
+            _lang: Lang,
+        ) -> anyhow::Result<Self> {
+            Err(TrayError::Unsupported.into())
+        }
+
+        pub fn update(&self, _runtime: &Runtime, _instance_names: Vec<String>, _lang: Lang) {}
+    }
+}
+
+pub struct Tray {
+    platform_tray: platform::PlatformTray,
+    receiver: mpsc::Receiver<TrayCommand>,
+    instance_names: Vec<String>,
+    lang: Lang,
+}
+
+impl Tray {
+    pub fn new(
+        runtime: &Runtime,
+        ctx: &egui::Context,
+        instance_names: Vec<String>,
+        lang: Lang,
+    ) -> anyhow::Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let platform_tray =
+            platform::PlatformTray::new(runtime, ctx, sender, instance_names.clone(), lang)?;
+        Ok(Tray {
+            platform_tray,
+            receiver,
+            instance_names,
+            lang,
+        })
+    }
+
+    pub fn update(&mut self, runtime: &Runtime, instance_names: &[String], lang: Lang) {
+        if self.instance_names != instance_names || self.lang != lang {
+            self.instance_names = instance_names.to_vec();
+            self.lang = lang;
+            self.platform_tray
+                .update(runtime, self.instance_names.clone(), lang);
+        }
+    }
+
+    pub fn take_command(&self) -> Option<TrayCommand> {
+        self.receiver.try_recv().ok()
+    }
+}
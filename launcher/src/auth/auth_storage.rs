@@ -6,6 +6,8 @@ use crate::config::runtime_config::Config;
 
 use super::user_info::AuthData;
 
+pub type AuthDataMap = HashMap<String, HashMap<String, AuthData>>; // backend id -> username -> auth data
+
 #[derive(PartialEq)]
 pub enum AuthDataSource {
     Persistent,
@@ -19,9 +21,9 @@ pub struct StorageEntry {
 
 pub struct AuthStorage {
     // saved auth data from previous launches, may not be up to date
-    persistent_storage: HashMap<String, HashMap<String, AuthData>>, // backend id -> username -> auth data
+    persistent_storage: AuthDataMap,
     // auth data from current launch, up to date
-    runtime_storage: HashMap<String, HashMap<String, AuthData>>, // backend id -> username -> auth data
+    runtime_storage: AuthDataMap,
 }
 
 impl AuthStorage {
@@ -30,8 +32,7 @@ impl AuthStorage {
         let auth_data_path = get_auth_data_path(&launcher_dir);
         let persistent_storage = match std::fs::read_to_string(&auth_data_path) {
             Ok(data) => {
-                let auth_data: HashMap<String, HashMap<String, AuthData>> =
-                    serde_json::from_str(&data).unwrap_or(HashMap::new());
+                let auth_data: AuthDataMap = serde_json::from_str(&data).unwrap_or(HashMap::new());
                 auth_data
             }
             Err(_) => HashMap::new(),
@@ -115,10 +116,30 @@ impl AuthStorage {
         self.save(config);
     }
 
+    pub fn get_persistent_data(&self) -> AuthDataMap {
+        let mut data = self.persistent_storage.clone();
+        for (id, user_map) in &self.runtime_storage {
+            data.entry(id.clone()).or_default().extend(user_map.clone());
+        }
+        data
+    }
+
+    // imported entries are only persisted, they get refreshed on the next authorization
+    pub fn import(&mut self, config: &Config, data: AuthDataMap) {
+        for (id, user_map) in data {
+            self.persistent_storage
+                .entry(id)
+                .or_default()
+                .extend(user_map);
+        }
+
+        self.save(config);
+    }
+
     pub fn get_all_entries(&self) -> Vec<(String, String)> {
         let mut entries = HashMap::new();
 
-        let mut collect_entries = |storage: &HashMap<String, HashMap<String, AuthData>>| {
+        let mut collect_entries = |storage: &AuthDataMap| {
             for (id, user_map) in storage {
                 for username in user_map.keys() {
                     entries.insert(id.clone(), username.clone());
@@ -5,8 +5,8 @@ use hyper::server::conn::http1;
 use hyper::service::service_fn;
 use hyper::{Request, Response};
 use hyper_util::rt::TokioIo;
-use reqwest::Client;
 use serde::Deserialize;
+use shared::http;
 use std::net::SocketAddr;
 use std::time::Duration;
 use tokio::net::TcpListener;
@@ -48,26 +48,13 @@ struct AuthQuery {
     code: String,
 }
 
-#[derive(Clone)]
-pub struct TokioExecutor;
-
-impl<F> hyper::rt::Executor<F> for TokioExecutor
-where
-    F: std::future::Future + Send + 'static,
-    F::Output: Send + 'static,
-{
-    fn execute(&self, fut: F) {
-        tokio::task::spawn(fut);
-    }
-}
-
 async fn exchange_code(
     client_id: &str,
     client_secret: &str,
     code: &str,
     redirect_uri: &str,
 ) -> anyhow::Result<String> {
-    let client = Client::new();
+    let client = http::client();
     let resp = client
         .post("https://account.ely.by/api/oauth2/v1/token")
         .form(&[
@@ -166,7 +153,6 @@ impl ElyByAuthProvider {
             "https://account.ely.by/oauth2/v1?client_id={}&redirect_uri={}&response_type=code&scope=account_info%20minecraft_server_session&prompt=select_account",
             &self.client_id, redirect_uri
         );
-        let _ = open::that(&url);
         message_provider
             .set_message(LangMessage::AuthMessage { url })
             .await;
@@ -242,7 +228,7 @@ impl AuthProvider for ElyByAuthProvider {
     }
 
     async fn get_user_info(&self, token: &str) -> anyhow::Result<AuthState> {
-        let client = Client::new();
+        let client = http::client();
         let resp: UserInfo = client
             .get("https://account.ely.by/api/account/v1/info")
             .header("Authorization", format!("Bearer {}", token))
@@ -4,24 +4,33 @@ use super::user_info::UserInfo;
 use crate::lang::LangMessage;
 use crate::vendor::minecraft_msa_auth::MinecraftAuthorizationFlow;
 use async_trait::async_trait;
-use oauth2::reqwest::async_http_client;
+use log::warn;
 use oauth2::{
-    AuthUrl, ClientId, DeviceAuthorizationUrl, DeviceCodeErrorResponseType, RefreshToken,
-    RequestTokenError, Scope, StandardDeviceAuthorizationResponse, TokenResponse, TokenUrl,
+    AuthUrl, ClientId, DeviceAuthorizationUrl, DeviceCodeErrorResponseType, HttpRequest,
+    HttpResponse, RefreshToken, RequestTokenError, Scope, StandardDeviceAuthorizationResponse,
+    TokenResponse, TokenUrl,
 };
-use reqwest::{Client, Url};
+use reqwest::multipart::{Form, Part};
+use reqwest::Url;
 use serde::Deserialize;
-use std::time::Duration;
+use shared::http;
+use std::time::{Duration, Instant};
 
 const MSA_DEVICE_CODE_URL: &str = "https://login.live.com/oauth20_connect.srf";
 const MSA_TOKEN_URL: &str = "https://login.live.com/oauth20_token.srf";
 const MSA_CLIENT_ID: &str = "00000000441cc96b";
 const MSA_SCOPE: &str = "service::user.auth.xboxlive.com::MBI_SSL";
+const SKIN_UPLOAD_URL: &str = "https://api.minecraftservices.com/minecraft/profile/skins";
+pub const SKIN_PAGE_URL: &str = "https://www.minecraft.net/msaprofile/mygames/editskin";
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const DEVICE_CODE_TIMEOUT: Duration = Duration::from_secs(60 * 5);
 
 #[derive(thiserror::Error, Debug)]
 pub enum AuthError {
     #[error("Timeout during authentication")]
     AuthTimeout,
+    #[error("Skin file is not a PNG image")]
+    InvalidSkinFile,
 }
 
 pub struct MicrosoftAuthProvider {}
@@ -44,6 +53,54 @@ fn get_oauth_client() -> oauth2::basic::BasicClient {
     )
 }
 
+type OAuthHttpError = oauth2::reqwest::Error<reqwest::Error>;
+
+// oauth2's own client ignores the configured proxy and timeouts, and is built on an older http crate
+async fn oauth_http_client(request: HttpRequest) -> Result<HttpResponse, OAuthHttpError> {
+    let method = reqwest::Method::from_bytes(request.method.as_str().as_bytes())
+        .map_err(|e| OAuthHttpError::Other(e.to_string()))?;
+    // oauth2 requires redirects to be disabled so that codes and tokens never reach another host
+    let client = http::client_builder()
+        .and_then(|builder| {
+            Ok(builder
+                .redirect(reqwest::redirect::Policy::none())
+                .build()?)
+        })
+        .map_err(|e| OAuthHttpError::Other(e.to_string()))?;
+    let mut request_builder = client
+        .request(method, request.url.as_str())
+        .body(request.body);
+    for (name, value) in &request.headers {
+        request_builder = request_builder.header(name.as_str(), value.as_bytes());
+    }
+    let response = request_builder
+        .send()
+        .await
+        .map_err(OAuthHttpError::Reqwest)?;
+
+    let status_code = oauth2::http::StatusCode::from_u16(response.status().as_u16())
+        .map_err(|e| OAuthHttpError::Other(e.to_string()))?;
+    let mut headers = oauth2::http::HeaderMap::new();
+    for (name, value) in response.headers() {
+        if let (Ok(name), Ok(value)) = (
+            oauth2::http::HeaderName::from_bytes(name.as_str().as_bytes()),
+            oauth2::http::HeaderValue::from_bytes(value.as_bytes()),
+        ) {
+            headers.append(name, value);
+        }
+    }
+    let body = response
+        .bytes()
+        .await
+        .map_err(OAuthHttpError::Reqwest)?
+        .to_vec();
+    Ok(HttpResponse {
+        status_code,
+        headers,
+        body,
+    })
+}
+
 async fn get_ms_token(message_provider: &AuthMessageProvider) -> anyhow::Result<AuthResultData> {
     let client = get_oauth_client();
 
@@ -51,36 +108,42 @@ async fn get_ms_token(message_provider: &AuthMessageProvider) -> anyhow::Result<
         .exchange_device_code()?
         .add_scope(Scope::new(MSA_SCOPE.to_string()))
         .add_extra_param("response_type", "device_code")
-        .request_async(async_http_client)
+        .request_async(oauth_http_client)
         .await?;
 
     let code = details.user_code().secret().to_string();
     let url =
         Url::parse_with_params(details.verification_uri(), &[("otc", code.clone())])?.to_string();
 
-    let _ = open::that(&url);
     message_provider
         .set_message(LangMessage::DeviceAuthMessage { url, code })
         .await;
 
-    let token = client
-        .exchange_device_access_token(&details)
-        .request_async(
-            async_http_client,
-            tokio::time::sleep,
-            Some(Duration::from_secs(60 * 5)),
-        )
-        .await
-        .map_err(|e| -> anyhow::Error {
-            match &e {
-                RequestTokenError::ServerResponse(resp)
-                    if *resp.error() == DeviceCodeErrorResponseType::ExpiredToken =>
-                {
-                    AuthError::AuthTimeout.into()
-                }
-                _ => e.into(),
+    // the code stays valid for the whole window, so a dropped connection only pauses the polling
+    let deadline = Instant::now() + DEVICE_CODE_TIMEOUT;
+    let token = loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(AuthError::AuthTimeout.into());
+        }
+        match client
+            .exchange_device_access_token(&details)
+            .request_async(oauth_http_client, tokio::time::sleep, Some(remaining))
+            .await
+        {
+            Ok(token) => break token,
+            Err(RequestTokenError::ServerResponse(resp))
+                if *resp.error() == DeviceCodeErrorResponseType::ExpiredToken =>
+            {
+                return Err(AuthError::AuthTimeout.into());
+            }
+            Err(e @ RequestTokenError::ServerResponse(_)) => return Err(e.into()),
+            Err(e) => {
+                warn!("Error polling for the Microsoft token, retrying:\n{:?}", e);
+                tokio::time::sleep(details.interval().min(remaining)).await;
             }
-        })?;
+        }
+    };
 
     Ok(AuthResultData {
         access_token: token.access_token().secret().to_string(),
@@ -88,6 +151,26 @@ async fn get_ms_token(message_provider: &AuthMessageProvider) -> anyhow::Result<
     })
 }
 
+pub async fn upload_skin(token: &str, skin: Vec<u8>, slim: bool) -> anyhow::Result<()> {
+    if !skin.starts_with(PNG_SIGNATURE) {
+        return Err(AuthError::InvalidSkinFile.into());
+    }
+
+    let variant = if slim { "slim" } else { "classic" };
+    let file = Part::bytes(skin)
+        .file_name("skin.png")
+        .mime_str("image/png")?;
+    let form = Form::new().text("variant", variant).part("file", file);
+    http::client()
+        .post(SKIN_UPLOAD_URL)
+        .header("Authorization", format!("Bearer {}", token))
+        .multipart(form)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
 impl MicrosoftAuthProvider {
     pub fn new() -> Self {
         MicrosoftAuthProvider {}
@@ -102,7 +185,7 @@ impl AuthProvider for MicrosoftAuthProvider {
     ) -> anyhow::Result<AuthState> {
         let ms_token = get_ms_token(message_provider).await?;
         message_provider.clear().await;
-        let mc_flow = MinecraftAuthorizationFlow::new(Client::new());
+        let mc_flow = MinecraftAuthorizationFlow::new(http::client());
         let mc_token = mc_flow
             .exchange_microsoft_token(ms_token.access_token)
             .await?
@@ -121,10 +204,10 @@ impl AuthProvider for MicrosoftAuthProvider {
         let token_response = oauth_client
             .exchange_refresh_token(&RefreshToken::new(refresh_token))
             .add_scope(Scope::new(MSA_SCOPE.to_string()))
-            .request_async(async_http_client)
+            .request_async(oauth_http_client)
             .await?;
 
-        let mc_flow = MinecraftAuthorizationFlow::new(Client::new());
+        let mc_flow = MinecraftAuthorizationFlow::new(http::client());
         let mc_token = mc_flow
             .exchange_microsoft_token(token_response.access_token().secret().to_string())
             .await?
@@ -141,7 +224,7 @@ impl AuthProvider for MicrosoftAuthProvider {
     }
 
     async fn get_user_info(&self, token: &str) -> anyhow::Result<AuthState> {
-        let client = Client::new();
+        let client = http::client();
         let resp: MinecraftProfileResponse = client
             .get("https://api.minecraftservices.com/minecraft/profile")
             .header("Authorization", format!("Bearer {}", token))
@@ -2,7 +2,7 @@ pub mod auth_flow;
 pub mod auth_storage;
 pub mod base;
 mod elyby;
-mod microsoft;
+pub mod microsoft;
 mod offline;
 mod telegram;
 pub mod user_info;
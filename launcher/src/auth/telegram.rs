@@ -8,6 +8,7 @@ use super::{
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::Deserialize;
+use shared::http;
 use std::{collections::HashMap, time::Duration};
 
 #[derive(Deserialize)]
@@ -29,7 +30,7 @@ pub struct TGAuthProvider {
 impl TGAuthProvider {
     pub fn new(base_url: &str) -> Self {
         TGAuthProvider {
-            client: Client::new(),
+            client: http::client(),
             base_url: base_url.to_string(),
         }
     }
@@ -65,7 +66,6 @@ impl AuthProvider for TGAuthProvider {
         let start_resp: LoginStartResponse = serde_json::from_str(&body)?;
 
         let tg_deeplink = format!("https://t.me/{}?start={}", bot_name, start_resp.code);
-        let _ = open::that(&tg_deeplink);
         message_provider
             .set_message(LangMessage::AuthMessage { url: tg_deeplink })
             .await;
@@ -20,6 +20,61 @@ pub fn get_version() -> Option<String> {
     VERSION.map(|version| version.to_string())
 }
 
+pub fn get_discord_client_id() -> Option<String> {
+    DISCORD_CLIENT_ID.map(|client_id| client_id.to_string())
+}
+
+pub fn get_download_mirrors() -> Vec<String> {
+    DOWNLOAD_MIRRORS
+        .map(|mirrors| {
+            mirrors
+                .split(',')
+                .map(|mirror| mirror.trim().to_string())
+                .filter(|mirror| !mirror.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn get_java_metadata_url() -> Option<String> {
+    JAVA_METADATA_URL.map(|url| url.to_string())
+}
+
+pub fn get_vanilla_manifest_url() -> Option<String> {
+    VANILLA_MANIFEST_URL.map(|url| url.to_string())
+}
+
+/// `SERVER_HEADERS` is a `;`-separated list of `Name: value` pairs.
+pub fn get_server_headers() -> Vec<(String, String)> {
+    SERVER_HEADERS
+        .map(|headers| {
+            headers
+                .split(';')
+                .filter_map(|header| header.split_once(':'))
+                .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+                .filter(|(name, _)| !name.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// hosts of the deployment's own servers, the only ones that receive the server headers
+pub fn get_server_hosts() -> Vec<String> {
+    let mut urls = vec![get_version_manifest_url()];
+    urls.extend(get_auto_update_base());
+    urls.extend(get_download_mirrors());
+    urls.extend(get_java_metadata_url());
+    urls.extend(get_vanilla_manifest_url());
+    let mut hosts: Vec<String> = urls
+        .iter()
+        .filter_map(|url| reqwest::Url::parse(url).ok())
+        .filter_map(|url| url.host_str().map(|host| host.to_lowercase()))
+        .collect();
+    hosts.sort();
+    hosts.dedup();
+    hosts
+}
+
 pub const LIBRARY_OVERRIDES: &str = include_str!("../../meta/library-overrides.json");
 
 pub const MOJANG_LIBRARY_PATCHES: &str = include_str!("../../meta/mojang-library-patches.json");
@@ -1,2 +1,3 @@
 pub mod build_config;
 pub mod runtime_config;
+pub mod settings_transfer;
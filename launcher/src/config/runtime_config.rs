@@ -1,11 +1,21 @@
 use log::warn;
 use serde::{Deserialize, Serialize};
-use shared::paths::get_logs_dir;
+use shared::adaptive_download::{
+    DownloadOptions, DEFAULT_INITIAL_CONCURRENCY, DEFAULT_MAX_CONCURRENCY,
+};
+use shared::endpoints::Endpoints;
+use shared::http::{ClientConfig, ProxyConfig};
+use shared::java::{JavaPackage, JavaPackageType};
+use shared::paths::get_instance_dir;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use super::build_config;
-use crate::{constants, lang::Lang, utils::get_data_dir};
+use crate::launcher::jvm_presets::JvmArgsPreset;
+use crate::{constants, lang::Lang, utils, utils::get_data_dir};
+
+const DEFAULT_METADATA_TIMEOUT_SECS: u64 = 10;
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct AuthProfile {
@@ -13,6 +23,134 @@ pub struct AuthProfile {
     pub username: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct InstanceSettings {
+    pub jvm_args_preset: JvmArgsPreset,
+    pub extra_jvm_args: String,
+    /// Set on the game process, overriding inherited variables with the same name.
+    pub env_vars: HashMap<String, String>,
+    pub pre_launch_command: String,
+    pub post_exit_command: String,
+    pub wrapper_command: Option<String>,
+    pub quick_play_server: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fullscreen: bool,
+    /// Keeps the installed version instead of following the latest remote one.
+    pub pin_version: bool,
+    /// Overrides the instance's recommended and the global `xmx` when set.
+    pub xmx: Option<String>,
+    pub xms: Option<String>,
+    /// Paths relative to the instance directory that syncing never overwrites or deletes.
+    pub preserved_paths: Vec<String>,
+    /// Replaces the instance directory inside the launcher data, e.g. to keep saves in a synced folder.
+    /// Deleting the instance never touches it.
+    pub game_dir: Option<String>,
+    /// Unix timestamps in seconds.
+    pub last_launched: Option<u64>,
+    pub last_synced: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceSort {
+    Alphabetical,
+    #[default]
+    LastPlayed,
+    RecentlySynced,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct NetworkSettings {
+    pub connect_timeout_secs: u64,
+    pub read_timeout_secs: u64,
+    /// `None` means no limit on the total request time.
+    pub request_timeout_secs: Option<u64>,
+    pub initial_download_concurrency: usize,
+    pub max_download_concurrency: usize,
+    pub proxy_url: Option<String>,
+    pub proxy_username: Option<String>,
+    pub proxy_password: Option<String>,
+    /// After this long the locally cached metadata is offered for launching, 0 disables it.
+    pub metadata_timeout_secs: u64,
+    /// Extra headers for the deployment's servers, overriding the built-in ones with the same name.
+    pub server_headers: HashMap<String, String>,
+    /// Mirror of the Azul Java metadata API, overriding the built-in one.
+    pub java_metadata_url: Option<String>,
+    /// Mirror of the Mojang version manifest, overriding the built-in one.
+    pub vanilla_manifest_url: Option<String>,
+}
+
+impl Default for NetworkSettings {
+    fn default() -> Self {
+        let client_config = ClientConfig::default();
+        Self {
+            connect_timeout_secs: client_config.connect_timeout.as_secs(),
+            read_timeout_secs: client_config.read_timeout.as_secs(),
+            request_timeout_secs: client_config.request_timeout.map(|t| t.as_secs()),
+            initial_download_concurrency: DEFAULT_INITIAL_CONCURRENCY,
+            max_download_concurrency: DEFAULT_MAX_CONCURRENCY,
+            proxy_url: None,
+            proxy_username: None,
+            proxy_password: None,
+            metadata_timeout_secs: DEFAULT_METADATA_TIMEOUT_SECS,
+            server_headers: HashMap::new(),
+            java_metadata_url: None,
+            vanilla_manifest_url: None,
+        }
+    }
+}
+
+impl NetworkSettings {
+    pub fn get_client_config(&self) -> ClientConfig {
+        ClientConfig {
+            connect_timeout: Duration::from_secs(self.connect_timeout_secs),
+            read_timeout: Duration::from_secs(self.read_timeout_secs),
+            request_timeout: self.request_timeout_secs.map(Duration::from_secs),
+            proxy: self.get_proxy_config(),
+            server_headers: self.get_server_headers(),
+            server_hosts: build_config::get_server_hosts(),
+        }
+    }
+
+    fn get_server_headers(&self) -> Vec<(String, String)> {
+        let mut headers: Vec<(String, String)> = build_config::get_server_headers()
+            .into_iter()
+            .filter(|(name, _)| {
+                !self
+                    .server_headers
+                    .keys()
+                    .any(|x| x.eq_ignore_ascii_case(name))
+            })
+            .collect();
+        headers.extend(self.server_headers.clone());
+        headers
+    }
+
+    pub fn get_endpoints(&self) -> Endpoints {
+        Endpoints {
+            java_metadata_url: self
+                .java_metadata_url
+                .clone()
+                .or_else(build_config::get_java_metadata_url),
+            vanilla_manifest_url: self
+                .vanilla_manifest_url
+                .clone()
+                .or_else(build_config::get_vanilla_manifest_url),
+        }
+    }
+
+    pub fn get_proxy_config(&self) -> Option<ProxyConfig> {
+        self.proxy_url.as_ref().map(|url| ProxyConfig {
+            url: url.clone(),
+            username: self.proxy_username.clone(),
+            password: self.proxy_password.clone(),
+        })
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Config {
     pub java_paths: HashMap<String, String>,
@@ -23,9 +161,59 @@ pub struct Config {
     pub lang: Lang,
     pub hide_launcher_after_launch: bool,
     pub auth_profiles: HashMap<String, AuthProfile>,
+    #[serde(default)]
+    pub instance_settings: HashMap<String, InstanceSettings>,
+    #[serde(default)]
+    pub max_download_rate_kbps: Option<u64>,
+    #[serde(default)]
+    pub network: NetworkSettings,
+    #[serde(default)]
+    pub discord_rich_presence: bool,
+    #[serde(default)]
+    pub minimize_to_tray: bool,
+    #[serde(default)]
+    pub recent_instances: Vec<String>,
+    /// Approximate download sizes in bytes, filled in once an instance's metadata is loaded.
+    #[serde(default)]
+    pub instance_size_estimates: HashMap<String, u64>,
+    /// Skips update checks, manifest and metadata fetching and auth refresh, using cached data.
+    #[serde(default)]
+    pub work_offline: bool,
+    /// Whether downloaded Java is a full JDK rather than the smaller JRE.
+    #[serde(default)]
+    pub java_package_type: JavaPackageType,
+    /// Java builds picked by the user, by required Java version; the latest build is used otherwise.
+    #[serde(default)]
+    pub java_packages: HashMap<String, JavaPackage>,
+    /// Only set for freshly created configs, so existing users never see the wizard.
+    #[serde(default)]
+    pub show_first_run_wizard: bool,
+    /// Opens authorization links in the browser as soon as they are received.
+    #[serde(default = "enabled")]
+    pub auto_open_auth_urls: bool,
+    #[serde(default)]
+    pub instance_sort: InstanceSort,
+    #[serde(default)]
+    pub advanced_settings_open: bool,
+
+    /// Set from the command line or environment, takes precedence over `data_dir`.
+    #[serde(skip)]
+    pub data_dir_override: Option<PathBuf>,
+    /// The saved `data_dir` is not writable right now, e.g. a drive that isn't mounted yet.
+    /// The default is used for this run while the setting itself is kept.
+    #[serde(skip)]
+    pub data_dir_unavailable: bool,
+    /// The command line or environment override that couldn't be used.
+    #[serde(skip)]
+    pub rejected_data_dir_override: Option<PathBuf>,
+}
+
+fn enabled() -> bool {
+    true
 }
 
 const CONFIG_FILENAME: &str = "config.json";
+const MAX_RECENT_INSTANCES: usize = 5;
 
 fn get_config_path() -> PathBuf {
     get_data_dir().join(CONFIG_FILENAME)
@@ -34,33 +222,97 @@ fn get_config_path() -> PathBuf {
 impl Config {
     pub fn load() -> Config {
         let config_path = get_config_path();
-        if config_path.exists() {
+        let config_exists = config_path.exists();
+        if config_exists {
             let config_str =
                 std::fs::read_to_string(&config_path).expect("Failed to read config file");
-            if let Ok(config) = serde_json::from_str(&config_str) {
-                return config;
+            match serde_json::from_str(&config_str) {
+                Ok(config) => return config,
+                Err(e) => warn!("Failed to parse config file, using defaults:\n{:?}", e),
             }
         }
 
-        Config {
+        let config = Config {
             java_paths: HashMap::new(),
             assets_dir: None,
             data_dir: None,
             xmx: String::from(constants::DEFAULT_JAVA_XMX),
             selected_instance_name: None,
-            lang: constants::DEFAULT_LANG,
+            lang: Lang::from_system_locale().unwrap_or(constants::DEFAULT_LANG),
             hide_launcher_after_launch: true,
             auth_profiles: HashMap::new(),
+            instance_settings: HashMap::new(),
+            max_download_rate_kbps: None,
+            network: NetworkSettings::default(),
+            discord_rich_presence: false,
+            minimize_to_tray: false,
+            recent_instances: Vec::new(),
+            instance_size_estimates: HashMap::new(),
+            work_offline: false,
+            java_package_type: JavaPackageType::default(),
+            java_packages: HashMap::new(),
+            show_first_run_wizard: true,
+            auto_open_auth_urls: true,
+            instance_sort: InstanceSort::default(),
+            advanced_settings_open: false,
+            data_dir_override: None,
+            data_dir_unavailable: false,
+            rejected_data_dir_override: None,
+        };
+        // persist the detected language so it is not detected again,
+        // an unreadable config is left alone as it may still hold the user's accounts
+        if !config_exists {
+            config.save();
+        }
+        config
+    }
+
+    pub fn apply_data_dir_override(&mut self, data_dir: Option<PathBuf>) {
+        let data_dir =
+            data_dir.or_else(|| std::env::var_os(constants::DATA_DIR_ENV).map(PathBuf::from));
+        if let Some(data_dir) = data_dir {
+            // an explicit override is usually a directory that doesn't exist yet
+            if let Err(e) = std::fs::create_dir_all(&data_dir) {
+                warn!("Failed to create data directory {:?}:\n{:?}", data_dir, e);
+            }
+            if utils::is_dir_writable(&data_dir) {
+                self.data_dir_override = Some(data_dir);
+            } else {
+                self.rejected_data_dir_override = Some(data_dir);
+            }
+        }
+
+        if let Some(data_dir) = &self.data_dir {
+            self.data_dir_unavailable = !utils::is_dir_writable(Path::new(data_dir));
+        }
+
+        if let Some(data_dir) = self.get_unavailable_data_dir() {
+            warn!(
+                "Data directory {:?} is missing or not writable, using {:?}",
+                data_dir,
+                self.get_launcher_dir()
+            );
+        }
+    }
+
+    /// The data directory that was asked for but is replaced by another one for this run.
+    pub fn get_unavailable_data_dir(&self) -> Option<PathBuf> {
+        if let Some(data_dir) = &self.rejected_data_dir_override {
+            return Some(data_dir.clone());
+        }
+        match (&self.data_dir_override, &self.data_dir) {
+            (None, Some(data_dir)) if self.data_dir_unavailable => Some(PathBuf::from(data_dir)),
+            _ => None,
         }
     }
 
     pub fn get_launcher_dir(&self) -> PathBuf {
-        let data_dir = match &self.data_dir {
-            None => dirs::data_dir()
+        let data_dir = match (&self.data_dir_override, &self.data_dir) {
+            (Some(dir), _) => dir.clone(),
+            (None, Some(dir)) if !self.data_dir_unavailable => PathBuf::from(dir),
+            (None, _) => dirs::data_dir()
                 .expect("Failed to get data directory")
                 .join(build_config::get_data_launcher_name()),
-
-            Some(dir) => PathBuf::from(dir),
         };
         if !data_dir.exists() {
             std::fs::create_dir_all(&data_dir).expect("Failed to create launcher directory");
@@ -68,6 +320,15 @@ impl Config {
         data_dir
     }
 
+    pub fn get_download_options(&self) -> DownloadOptions {
+        DownloadOptions {
+            max_rate_kbps: self.max_download_rate_kbps,
+            mirrors: build_config::get_download_mirrors(),
+            initial_concurrency: self.network.initial_download_concurrency,
+            max_concurrency: self.network.max_download_concurrency,
+        }
+    }
+
     pub fn get_assets_dir(&self) -> PathBuf {
         let assets_dir = match &self.assets_dir {
             Some(dir) => PathBuf::from(dir),
@@ -103,19 +364,76 @@ impl Config {
         }
     }
 
+    pub fn get_instance_settings(&self, instance_name: &str) -> InstanceSettings {
+        self.instance_settings
+            .get(instance_name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn get_instance_settings_mut(&mut self, instance_name: &str) -> &mut InstanceSettings {
+        self.instance_settings
+            .entry(instance_name.to_string())
+            .or_default()
+    }
+
+    /// The directory the instance is synced into and launched in.
+    pub fn get_game_dir(&self, instance_name: &str) -> PathBuf {
+        match self.get_instance_settings(instance_name).game_dir {
+            Some(game_dir) => PathBuf::from(game_dir),
+            None => get_instance_dir(&self.get_launcher_dir(), instance_name),
+        }
+    }
+
+    /// Heap size in MB used without an instance override: the instance's recommendation, then the global value.
+    pub fn get_default_xmx_mb(&self, recommended_xmx: Option<&str>) -> u32 {
+        recommended_xmx
+            .and_then(utils::parse_xmx_mb)
+            .or_else(|| utils::parse_xmx_mb(&self.xmx))
+            .or_else(|| utils::parse_xmx_mb(constants::DEFAULT_JAVA_XMX))
+            .unwrap()
+    }
+
+    pub fn get_xmx_mb(&self, instance_name: &str, recommended_xmx: Option<&str>) -> u32 {
+        self.get_instance_settings(instance_name)
+            .xmx
+            .as_deref()
+            .and_then(utils::parse_xmx_mb)
+            .unwrap_or_else(|| self.get_default_xmx_mb(recommended_xmx))
+    }
+
+    pub fn get_xms_mb(&self, instance_name: &str, xmx_mb: u32) -> u32 {
+        self.get_instance_settings(instance_name)
+            .xms
+            .as_deref()
+            .and_then(utils::parse_xmx_mb)
+            .unwrap_or(constants::DEFAULT_JAVA_XMS_MB)
+            .min(xmx_mb)
+    }
+
+    // names are expected to be sorted alphabetically already, which is kept as the fallback order
+    pub fn sort_instance_names(&self, instance_names: &mut [String]) {
+        let get_timestamp = |instance_name: &String| {
+            let settings = self.instance_settings.get(instance_name);
+            match self.instance_sort {
+                InstanceSort::Alphabetical => None,
+                InstanceSort::LastPlayed => settings.and_then(|x| x.last_launched),
+                InstanceSort::RecentlySynced => settings.and_then(|x| x.last_synced),
+            }
+        };
+        instance_names.sort_by_key(|instance_name| std::cmp::Reverse(get_timestamp(instance_name)));
+    }
+
+    pub fn add_recent_instance(&mut self, instance_name: &str) {
+        self.recent_instances.retain(|name| name != instance_name);
+        self.recent_instances.insert(0, instance_name.to_string());
+        self.recent_instances.truncate(MAX_RECENT_INSTANCES);
+        self.save();
+    }
+
     pub fn save(&self) {
         let config_str = serde_json::to_string_pretty(self).expect("Failed to serialize config");
         let config_path = get_config_path();
         std::fs::write(&config_path, config_str).expect("Failed to write config file");
     }
 }
-
-const LOGS_FILENAME: &str = "launcher.log";
-
-pub fn get_logs_path() -> PathBuf {
-    let logs_dir = get_logs_dir(&get_data_dir());
-    if !logs_dir.exists() {
-        std::fs::create_dir_all(&logs_dir).expect("Failed to create logs directory");
-    }
-    logs_dir.join(LOGS_FILENAME)
-}
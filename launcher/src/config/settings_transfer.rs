@@ -0,0 +1,139 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::auth::auth_storage::AuthDataMap;
+
+use super::runtime_config::Config;
+
+#[derive(Serialize, Deserialize)]
+struct SettingsExport {
+    config: Value,
+    // only present when secrets were included, contains access and refresh tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auth_data: Option<AuthDataMap>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SettingsImportError {
+    #[error("Exported config is not a JSON object")]
+    NotAnObject,
+    #[error("Exported config is incompatible with this launcher version: {0}")]
+    Incompatible(serde_json::Error),
+}
+
+pub struct ImportedSettings {
+    pub config: Config,
+    pub auth_data: Option<AuthDataMap>,
+    pub unknown_fields: Vec<String>,
+}
+
+// proxy urls may carry credentials as "user:pass@host"
+fn remove_userinfo(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut parsed) if parsed.has_host() => {
+            if parsed.username().is_empty() && parsed.password().is_none() {
+                return url.to_string();
+            }
+            let _ = parsed.set_username("");
+            let _ = parsed.set_password(None);
+            parsed.to_string()
+        }
+        _ => url
+            .rsplit_once('@')
+            .map_or(url, |(_, host)| host)
+            .to_string(),
+    }
+}
+
+fn remove_secrets(config: &mut Value) {
+    if let Some(network) = config.get_mut("network").and_then(Value::as_object_mut) {
+        network.remove("proxy_username");
+        network.remove("proxy_password");
+        network.remove("server_headers");
+        if let Some(Value::String(proxy_url)) = network.get_mut("proxy_url") {
+            *proxy_url = remove_userinfo(proxy_url);
+        }
+    }
+}
+
+/// The config without passwords and tokens, safe to share.
+pub fn get_redacted_config(config: &Config) -> anyhow::Result<Value> {
+    let mut config_value = serde_json::to_value(config)?;
+    remove_secrets(&mut config_value);
+    Ok(config_value)
+}
+
+fn merge_values(target: &mut Value, source: Value) {
+    match (target, source) {
+        (Value::Object(target), Value::Object(source)) => {
+            for (key, value) in source {
+                match target.get_mut(&key) {
+                    Some(target_value) => merge_values(target_value, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (target, source) => *target = source,
+    }
+}
+
+pub fn export_settings(
+    config: &Config,
+    auth_data: Option<AuthDataMap>,
+    path: &Path,
+) -> anyhow::Result<()> {
+    let mut config_value = serde_json::to_value(config)?;
+    if auth_data.is_none() {
+        remove_secrets(&mut config_value);
+    }
+
+    let export = SettingsExport {
+        config: config_value,
+        auth_data,
+    };
+    std::fs::write(path, serde_json::to_string_pretty(&export)?)?;
+    Ok(())
+}
+
+pub fn import_settings(
+    current: &Config,
+    path: &Path,
+    replace: bool,
+) -> anyhow::Result<ImportedSettings> {
+    let export: SettingsExport = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    let Value::Object(imported) = export.config else {
+        return Err(SettingsImportError::NotAnObject.into());
+    };
+
+    let mut config_value = serde_json::to_value(current)?;
+    let mut unknown_fields: Vec<String> = imported
+        .keys()
+        .filter(|key| config_value.get(key.as_str()).is_none())
+        .cloned()
+        .collect();
+    unknown_fields.sort();
+
+    let imported = Value::Object(imported);
+    if replace {
+        config_value = imported;
+    } else {
+        merge_values(&mut config_value, imported);
+    }
+
+    let mut config: Config =
+        serde_json::from_value(config_value).map_err(SettingsImportError::Incompatible)?;
+    config.data_dir_override = current.data_dir_override.clone();
+    config.rejected_data_dir_override = current.rejected_data_dir_override.clone();
+    config.data_dir_unavailable =
+        current.data_dir_unavailable && config.data_dir == current.data_dir;
+
+    Ok(ImportedSettings {
+        config,
+        auth_data: export.auth_data,
+        unknown_fields,
+    })
+}
@@ -1,7 +1,11 @@
 use crate::lang::Lang;
 
 pub const DEFAULT_JAVA_XMX: &str = "4096m";
+pub const DEFAULT_JAVA_XMS_MB: u32 = 512;
 pub const DEFAULT_LANG: Lang = Lang::English;
 
 pub const MIN_JAVA_MB: u32 = 256;
 pub const MAX_JAVA_MB: u32 = 65536;
+pub const HIGH_MEMORY_PERCENT: u64 = 80;
+
+pub const DATA_DIR_ENV: &str = "LAUNCHER_DATA_DIR";
@@ -0,0 +1,50 @@
+use std::backtrace::Backtrace;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::error;
+
+use crate::config::build_config;
+use crate::utils::get_data_dir;
+
+const CRASH_REPORT_FILENAME: &str = "crash_report.txt";
+
+pub fn get_crash_report_path() -> PathBuf {
+    get_data_dir().join(CRASH_REPORT_FILENAME)
+}
+
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let report = format!(
+            "{} {} ({} {})\nTimestamp: {}\n\n{}\n\n{}",
+            build_config::get_launcher_name(),
+            build_config::get_version().unwrap_or("dev".to_string()),
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            timestamp,
+            info,
+            Backtrace::force_capture(),
+        );
+        error!("Launcher panicked:\n{}", report);
+        if let Err(e) = std::fs::write(get_crash_report_path(), &report) {
+            error!("Failed to write crash report:\n{:?}", e);
+        }
+
+        default_hook(info);
+    }));
+}
+
+/// Returns the report left by the previous run, removing it so it is only shown once.
+pub fn take_crash_report() -> Option<String> {
+    let path = get_crash_report_path();
+    let report = std::fs::read_to_string(&path).ok()?;
+    if let Err(e) = std::fs::remove_file(&path) {
+        error!("Failed to remove crash report:\n{:?}", e);
+    }
+    Some(report)
+}
@@ -1,15 +1,41 @@
 use serde::{Deserialize, Serialize};
 
+use crate::config::runtime_config::InstanceSort;
+use crate::constants;
+use crate::launcher::jvm_presets::JvmArgsPreset;
+
+/// To add a language, add a variant here and to `Lang::ALL`, then add its arms to
+/// `LangMessage::translate`. Messages without an arm for the language fall back to English.
 #[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
 pub enum Lang {
     English,
     Russian,
+    German,
+}
+
+impl Lang {
+    pub const ALL: [Lang; 3] = [Lang::English, Lang::Russian, Lang::German];
+
+    pub fn from_system_locale() -> Option<Lang> {
+        let locale = sys_locale::get_locale()?.to_lowercase();
+        match locale.split(['-', '_']).next()? {
+            "en" => Some(Lang::English),
+            "ru" => Some(Lang::Russian),
+            "de" => Some(Lang::German),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Debug)]
 pub enum LangMessage {
-    AuthMessage { url: String },
-    DeviceAuthMessage { url: String, code: String },
+    AuthMessage {
+        url: String,
+    },
+    DeviceAuthMessage {
+        url: String,
+        code: String,
+    },
     AuthTimeout,
     UnknownAuthError,
     AuthorizeUsing(String),
@@ -19,15 +45,53 @@ pub enum LangMessage {
     NoInstances,
     CheckingFiles,
     DownloadingFiles,
+    Diagnostics,
+    RunDiagnostics,
+    DiagnosticsRunning,
+    DiagnosticsSkipped,
+    DiagnosticsServer,
+    DiagnosticsVersionManifest,
+    DiagnosticsAutoUpdate,
+    DiagnosticsJavaApi,
+    DiagnosticsJava,
+    DiagnosticsDataDir,
+    NotEnoughDiskSpace {
+        required: u64,
+        available: u64,
+    },
+    DownloadingFilesSummary {
+        already_have: usize,
+        to_download: usize,
+        size: u64,
+    },
     SyncInstance,
     InstanceNotSynced,
     InstanceSynced,
     NoConnectionToSyncServer,
     InstanceSyncError,
+    InstanceVersion {
+        minecraft_version: String,
+        build: String,
+    },
+    PinInstanceVersion,
+    ApproximateSize {
+        size: u64,
+    },
+    InstanceUpdateAvailable {
+        local_build: String,
+        remote_build: String,
+    },
+    SyncServerError {
+        status: u16,
+    },
     CheckingJava,
     DownloadingJava,
-    JavaInstalled { version: String },
-    NeedJava { version: String },
+    JavaInstalled {
+        version: String,
+    },
+    NeedJava {
+        version: String,
+    },
     UnknownErrorDownloadingJava,
     NoConnectionToJavaServer,
     UnknownJavaVersion,
@@ -35,6 +99,41 @@ pub enum LangMessage {
     SelectedJavaPath,
     NoJavaPath,
     JavaXMX,
+    InvalidJavaXMX {
+        current: String,
+    },
+    WorkOffline,
+    MaxDownloadRate,
+    RequestTimeout,
+    ProxyUrl,
+    ProxyUsername,
+    ProxyPassword,
+    InvalidProxyUrl,
+    TestConnection,
+    ConnectionOk,
+    ConnectionFailed,
+    ExportSettings,
+    ImportSettings,
+    IncludeAccountsInExport,
+    ExportSecretsWarning,
+    ReplaceSettingsOnImport,
+    SettingsExported,
+    SettingsImported,
+    ReauthorizeAfterImport,
+    UnknownSettingsIgnored {
+        fields: String,
+    },
+    SettingsTransferError,
+    ExtraJvmArgs,
+    InvalidArguments,
+    EnvironmentVariables,
+    PreLaunchCommand,
+    PostExitCommand,
+    WrapperCommand,
+    QuickPlayServer,
+    WindowResolution,
+    InvalidResolution,
+    Fullscreen,
     SelectJavaPath,
     Launch,
     LaunchError,
@@ -47,18 +146,55 @@ pub enum LangMessage {
     ErrorCheckingForUpdates,
     ErrorDownloadingUpdate,
     NoConnectionToUpdateServer,
-    ErrorReadOnly,
+    ErrorReadOnly {
+        path: String,
+    },
+    UpdateViaPackageManager {
+        version: String,
+    },
     ProceedToLauncher,
     Authorization,
     ForceOverwrite,
     ForceOverwriteWarning,
+    FilesWillBeDeleted {
+        count: usize,
+    },
+    AndMore {
+        count: usize,
+    },
+    DeleteAndSync,
+    VerifyFiles,
+    VerifyResult {
+        missing: usize,
+        corrupted: usize,
+    },
+    AllFilesOk,
+    RepairFiles,
+    RepairInstallation,
+    FilesRepaired {
+        count: usize,
+    },
+    VerifyError,
     KillMinecraft,
-    HideLauncherAfterLaunch,
+    KeepLauncherOpenAfterLaunch,
+    DiscordRichPresence,
+    MinimizeToTray,
+    ShowLauncher,
+    Quit,
     DownloadAndLaunch,
     CancelLaunch,
     CancelDownload,
     Retry,
     OpenLogs,
+    OpenLauncherLogs,
+    OpenInstanceFolder,
+    OpenGameLogs,
+    OpenCrashReports,
+    OpenAssetsFolder,
+    TimeRemaining(u64),
+    GameOutput,
+    CopyAll,
+    Copy,
     LoadingMetadata,
     MetadataErrorOffline,
     MetadataFetchError,
@@ -68,6 +204,7 @@ pub enum LangMessage {
     Loader,
     LoaderVersion,
     InstanceNameExists,
+    InvalidInstanceName,
     CreateInstance,
     CreatingInstance,
     Cancel,
@@ -93,11 +230,152 @@ pub enum LangMessage {
     ReadLocalRemoteError,
     ReadLocalOffline,
     ErrorGettingMetadata,
+    ChangeSkin,
+    OpenSkinPage,
+    SlimSkinModel,
+    UploadSkin,
+    SkinUploaded,
+    SkinUploadError,
+    DownloadJava,
+    DownloadFullJdk,
+    OfflineCachedList,
+    LauncherCrashed,
+    LauncherCrashedDescription,
+    Close,
+    LaunchWithCachedVersion,
+    MetadataTimeout,
+    InstanceMemory,
+    ImportMinecraftDir,
+    ImportingMinecraftDir,
+    ImportOverwriteWarning {
+        paths: String,
+    },
+    ImportAndOverwrite,
+    ImportedMinecraftDir {
+        paths: String,
+    },
+    ImportMinecraftDirError,
+    Welcome,
+    WizardStep {
+        current: usize,
+        total: usize,
+    },
+    WizardChooseLanguage,
+    WizardChooseInstance,
+    WizardSignIn,
+    WizardDataDir,
+    ChangeDataDir,
+    DataDirNotWritable,
+    WizardReady,
+    DownloadAndPlay,
+    Back,
+    Next,
+    SkipSetup,
+    OpenAuthLink,
+    OpenLink,
+    CopyLink,
+    CopyCode,
+    AutoOpenAuthUrls,
+    ApplyingUpdate,
+    // only used when updating the macOS app bundle
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    UnpackingUpdate,
+    ReplacingLauncher,
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    RemovingOldLauncher,
+    UpdateNotApplied {
+        expected: String,
+        current: String,
+    },
+    SpaceToFree {
+        size: u64,
+    },
+    CleanUnusedAssets,
+    FindUnusedAssets,
+    NoUnusedAssets,
+    UnusedAssetsFound {
+        count: usize,
+        size: u64,
+    },
+    DeleteUnusedAssets,
+    UnusedAssetsDeleted {
+        count: usize,
+        size: u64,
+    },
+    AssetsCleanupError,
+    InstanceAlreadyRunning,
+    JvmArgsPreset,
+    JvmArgsPresetName(JvmArgsPreset),
+    SortInstances,
+    InstanceSortName(InstanceSort),
+    DiskUsage,
+    DataDirUsage {
+        used: u64,
+        free: u64,
+    },
+    DiskUsageError,
+    Refresh,
+    IncludeSnapshots,
+    SnapshotInstance,
+    JavaUnavailableForPlatform {
+        required_version: String,
+        platform: String,
+    },
+    AdvancedSettings,
+    SkipUpdateForNow,
+    JavaVerificationFailed,
+    ClearCaches,
+    CachesCleared {
+        size: u64,
+    },
+    ClearCachesError,
+    LaunchOfflineWithCurrentFiles,
+    InstanceMayBeOutdated,
+    JavaXMXExceedsSystemMemory {
+        total_mb: u32,
+    },
+    JavaXMXHighMemory {
+        total_mb: u32,
+    },
+    JavaBuild,
+    LatestJavaBuild,
+    ListJavaBuilds,
+    ListJavaBuildsError,
+    ReportIssue,
+    IssueReportCreated {
+        path: String,
+    },
+    IssueReportError,
+    GameCrashedOnStartup(String),
+    GameDir,
+    ChangeGameDir,
+    ResetGameDir,
+    GameDirNotWritable,
+    RunningPreLaunchCommand,
+    IncompleteResolution,
+    FilesPerSecond(f64),
+    DataDirUnavailable {
+        path: String,
+        fallback: String,
+    },
+    InvalidEnvVar {
+        name: String,
+    },
+    DuplicateEnvVar {
+        name: String,
+    },
 }
 
 impl LangMessage {
     pub fn to_string(&self, lang: Lang) -> String {
-        match self {
+        self.translate(lang)
+            .or_else(|| self.translate(Lang::English))
+            .unwrap_or_else(|| format!("{:?}", self))
+    }
+
+    // None means the message is not translated to this language yet
+    fn translate(&self, lang: Lang) -> Option<String> {
+        Some(match self {
             LangMessage::AuthMessage { url: _ } => match lang {
                 Lang::English => {
                     "Authorize in the browser window.\nOr open the link manually.".to_string()
@@ -106,6 +384,7 @@ impl LangMessage {
                     "Авторизуйтесь в открывшемся окне браузера.\nИли откройте ссылку вручную."
                         .to_string()
                 }
+                _ => return None,
             },
             LangMessage::DeviceAuthMessage { url: _, code } => match lang {
                 Lang::English => {
@@ -114,352 +393,1409 @@ impl LangMessage {
                 Lang::Russian => {
                     format!("Авторизуйтесь в открывшемся окне браузера.\nИли откройте ссылку вручную и введите код: {}", code)
                 }
+                _ => return None,
             },
             LangMessage::AuthTimeout => match lang {
                 Lang::English => "Authorization timeout".to_string(),
                 Lang::Russian => "Превышено время авторизации".to_string(),
+                _ => return None,
             },
             LangMessage::UnknownAuthError => match lang {
                 Lang::English => "Authorization error".to_string(),
                 Lang::Russian => "Ошибка авторизации".to_string(),
+                _ => return None,
             },
             LangMessage::AuthorizeUsing(app_name) => match lang {
                 Lang::English => format!("Authorize using {}", app_name),
                 Lang::Russian => format!("Авторизуйтесь через {}", app_name),
+                _ => return None,
             },
             LangMessage::Authorizing => match lang {
                 Lang::English => "Authorizing...".to_string(),
                 Lang::Russian => "Авторизация...".to_string(),
+                _ => return None,
             },
             LangMessage::SelectInstance => match lang {
                 Lang::English => "Select instance:".to_string(),
                 Lang::Russian => "Выберите версию:".to_string(),
+                Lang::German => "Instanz auswählen".to_string(),
             },
             LangMessage::NotSelected => match lang {
                 Lang::English => "Not selected".to_string(),
                 Lang::Russian => "Не выбрано".to_string(),
+                Lang::German => "Nicht ausgewählt".to_string(),
             },
             LangMessage::NoInstances => match lang {
                 Lang::English => "No instances fetched".to_string(),
                 Lang::Russian => "Список версий пуст".to_string(),
+                Lang::German => "Keine Instanzen".to_string(),
             },
             LangMessage::CheckingFiles => match lang {
                 Lang::English => "Checking files...".to_string(),
                 Lang::Russian => "Проверка файлов...".to_string(),
+                Lang::German => "Dateien werden geprüft...".to_string(),
             },
             LangMessage::DownloadingFiles => match lang {
                 Lang::English => "Downloading files...".to_string(),
                 Lang::Russian => "Загрузка файлов...".to_string(),
-            },
+                Lang::German => "Dateien werden heruntergeladen...".to_string(),
+            },
+            LangMessage::Diagnostics => match lang {
+                Lang::English => "Diagnostics".to_string(),
+                Lang::Russian => "Диагностика".to_string(),
+                Lang::German => "Diagnose".to_string(),
+            },
+            LangMessage::RunDiagnostics => match lang {
+                Lang::English => "Run diagnostics".to_string(),
+                Lang::Russian => "Запустить диагностику".to_string(),
+                Lang::German => "Diagnose starten".to_string(),
+            },
+            LangMessage::DiagnosticsRunning => match lang {
+                Lang::English => "Running checks...".to_string(),
+                Lang::Russian => "Выполняются проверки...".to_string(),
+                _ => return None,
+            },
+            LangMessage::DiagnosticsSkipped => match lang {
+                Lang::English => "Skipped".to_string(),
+                Lang::Russian => "Пропущено".to_string(),
+                _ => return None,
+            },
+            LangMessage::DiagnosticsServer => match lang {
+                Lang::English => "Server".to_string(),
+                Lang::Russian => "Сервер".to_string(),
+                _ => return None,
+            },
+            LangMessage::DiagnosticsVersionManifest => match lang {
+                Lang::English => "Version manifest".to_string(),
+                Lang::Russian => "Список версий".to_string(),
+                _ => return None,
+            },
+            LangMessage::DiagnosticsAutoUpdate => match lang {
+                Lang::English => "Launcher updates".to_string(),
+                Lang::Russian => "Обновления лаунчера".to_string(),
+                _ => return None,
+            },
+            LangMessage::DiagnosticsJavaApi => match lang {
+                Lang::English => "Java download API".to_string(),
+                Lang::Russian => "API загрузки Java".to_string(),
+                _ => return None,
+            },
+            LangMessage::DiagnosticsJava => match lang {
+                Lang::English => "Java".to_string(),
+                Lang::Russian => "Java".to_string(),
+                _ => return None,
+            },
+            LangMessage::DiagnosticsDataDir => match lang {
+                Lang::English => "Data directory".to_string(),
+                Lang::Russian => "Папка данных".to_string(),
+                _ => return None,
+            },
+            LangMessage::NotEnoughDiskSpace {
+                required,
+                available,
+            } => {
+                let required_mb = *required as f64 / (1024.0 * 1024.0);
+                let available_mb = *available as f64 / (1024.0 * 1024.0);
+                match lang {
+                    Lang::English => format!(
+                        "Not enough disk space: {:.1} MB required, {:.1} MB available",
+                        required_mb, available_mb
+                    ),
+                    Lang::Russian => format!(
+                        "Недостаточно места на диске: требуется {:.1} МБ, доступно {:.1} МБ",
+                        required_mb, available_mb
+                    ),
+                    Lang::German => format!(
+                        "Nicht genügend Speicherplatz: {:.1} MB benötigt, {:.1} MB verfügbar",
+                        required_mb, available_mb
+                    ),
+                }
+            }
+            LangMessage::DownloadingFilesSummary {
+                already_have,
+                to_download,
+                size,
+            } => {
+                let size_mb = *size as f64 / (1024.0 * 1024.0);
+                match lang {
+                    Lang::English => {
+                        let mut message = format!(
+                            "Already up to date: {} files, downloading: {} files",
+                            already_have, to_download
+                        );
+                        if *size > 0 {
+                            message += &format!(" ({:.1} MB)", size_mb);
+                        }
+                        message
+                    }
+                    Lang::Russian => {
+                        let mut message = format!(
+                            "Уже актуально: {} файлов, загрузка: {} файлов",
+                            already_have, to_download
+                        );
+                        if *size > 0 {
+                            message += &format!(" ({:.1} МБ)", size_mb);
+                        }
+                        message
+                    }
+                    _ => return None,
+                }
+            }
             LangMessage::SyncInstance => match lang {
                 Lang::English => "Sync instance".to_string(),
                 Lang::Russian => "Синхронизировать версию".to_string(),
+                Lang::German => "Instanz synchronisieren".to_string(),
             },
             LangMessage::InstanceNotSynced => match lang {
                 Lang::English => "Instance not synced".to_string(),
                 Lang::Russian => "Версия не синхронизирована".to_string(),
+                Lang::German => "Instanz nicht synchronisiert".to_string(),
             },
             LangMessage::InstanceSynced => match lang {
                 Lang::English => "Instance up-to-date".to_string(),
                 Lang::Russian => "Версия синхронизирована".to_string(),
+                Lang::German => "Instanz synchronisiert".to_string(),
             },
             LangMessage::NoConnectionToSyncServer => match lang {
                 Lang::English => "No connection to instance sync server".to_string(),
                 Lang::Russian => "Нет подключения к серверу синхронизации версий".to_string(),
+                _ => return None,
             },
             LangMessage::InstanceSyncError => match lang {
                 Lang::English => "Error syncing instance".to_string(),
                 Lang::Russian => "Ошибка синхронизации версии".to_string(),
+                Lang::German => "Fehler beim Synchronisieren der Instanz".to_string(),
+            },
+            LangMessage::InstanceVersion {
+                minecraft_version,
+                build,
+            } => match lang {
+                Lang::English => format!("Minecraft {}, build {}", minecraft_version, build),
+                Lang::Russian => format!("Minecraft {}, сборка {}", minecraft_version, build),
+                _ => return None,
+            },
+            LangMessage::ApproximateSize { size } => {
+                let size_mb = *size as f64 / (1024.0 * 1024.0);
+                match lang {
+                    Lang::English if size_mb >= 1024.0 => format!("~{:.1} GB", size_mb / 1024.0),
+                    Lang::English => format!("~{:.0} MB", size_mb),
+                    Lang::Russian if size_mb >= 1024.0 => format!("~{:.1} ГБ", size_mb / 1024.0),
+                    Lang::Russian => format!("~{:.0} МБ", size_mb),
+                    Lang::German if size_mb >= 1024.0 => format!("~{:.1} GB", size_mb / 1024.0),
+                    Lang::German => format!("~{:.0} MB", size_mb),
+                }
+            }
+            LangMessage::PinInstanceVersion => match lang {
+                Lang::English => "Keep the installed version (don't update)".to_string(),
+                Lang::Russian => "Оставить установленную версию (не обновлять)".to_string(),
+                _ => return None,
+            },
+            LangMessage::InstanceUpdateAvailable {
+                local_build,
+                remote_build,
+            } => match lang {
+                Lang::English => format!("Update available: build {} → {}", local_build, remote_build),
+                Lang::Russian => format!("Доступно обновление: сборка {} → {}", local_build, remote_build),
+                _ => return None,
+            },
+            LangMessage::SyncServerError { status } => match lang {
+                Lang::English => format!("Sync server returned an error (HTTP {})", status),
+                Lang::Russian => format!("Сервер синхронизации вернул ошибку (HTTP {})", status),
+                _ => return None,
             },
             LangMessage::CheckingJava => match lang {
                 Lang::English => "Checking Java...".to_string(),
                 Lang::Russian => "Проверка Java...".to_string(),
+                Lang::German => "Java wird geprüft...".to_string(),
             },
             LangMessage::DownloadingJava => match lang {
                 Lang::English => "Downloading Java...".to_string(),
                 Lang::Russian => "Загрузка Java...".to_string(),
+                Lang::German => "Java wird heruntergeladen...".to_string(),
             },
             LangMessage::JavaInstalled { version } => match lang {
                 Lang::English => format!("Java {} installed", version),
                 Lang::Russian => format!("Java {} установлена", version),
+                _ => return None,
             },
             LangMessage::NeedJava { version } => match lang {
                 Lang::English => format!("Java {} not installed", version),
                 Lang::Russian => format!("Java {} не установлена", version),
+                _ => return None,
             },
             LangMessage::UnknownErrorDownloadingJava => match lang {
                 Lang::English => "Error downloading Java".to_string(),
                 Lang::Russian => "Ошибка загрузки Java".to_string(),
+                _ => return None,
             },
             LangMessage::NoConnectionToJavaServer => match lang {
                 Lang::English => "No connection to Java download server".to_string(),
                 Lang::Russian => "Нет подключения к серверу загрузки Java".to_string(),
+                _ => return None,
             },
             LangMessage::UnknownJavaVersion => match lang {
                 Lang::English => "Unknown Java version".to_string(),
                 Lang::Russian => "Неизвестная версия Java".to_string(),
+                _ => return None,
             },
             LangMessage::Settings => match lang {
                 Lang::English => "Settings".to_string(),
                 Lang::Russian => "Настройки".to_string(),
+                Lang::German => "Einstellungen".to_string(),
             },
             LangMessage::SelectedJavaPath => match lang {
                 Lang::English => "Selected Java path:".to_string(),
                 Lang::Russian => "Выбранный путь к Java:".to_string(),
+                _ => return None,
             },
             LangMessage::NoJavaPath => match lang {
                 Lang::English => "No Java path selected".to_string(),
                 Lang::Russian => "Путь к Java не выбран".to_string(),
+                _ => return None,
             },
             LangMessage::JavaXMX => match lang {
                 Lang::English => "Java Xmx".to_string(),
                 Lang::Russian => "Java Xmx".to_string(),
+                _ => return None,
+            },
+            LangMessage::InvalidJavaXMX { current } => match lang {
+                Lang::English => format!(
+                    "Invalid value. Use e.g. 4g, 4096m or 4096 (MB), from {} to {} MB. Current value: {}",
+                    constants::MIN_JAVA_MB,
+                    constants::MAX_JAVA_MB,
+                    current
+                ),
+                Lang::Russian => format!(
+                    "Некорректное значение. Используйте, например, 4g, 4096m или 4096 (МБ), от {} до {} МБ. Текущее значение: {}",
+                    constants::MIN_JAVA_MB,
+                    constants::MAX_JAVA_MB,
+                    current
+                ),
+                _ => return None,
+            },
+            LangMessage::WorkOffline => match lang {
+                Lang::English => "Work offline".to_string(),
+                Lang::Russian => "Работать офлайн".to_string(),
+                Lang::German => "Offline arbeiten".to_string(),
+            },
+            LangMessage::MaxDownloadRate => match lang {
+                Lang::English => "Max download speed (0 = unlimited)".to_string(),
+                Lang::Russian => "Макс. скорость загрузки (0 = без ограничений)".to_string(),
+                _ => return None,
+            },
+            LangMessage::RequestTimeout => match lang {
+                Lang::English => "Request timeout (0 = unlimited)".to_string(),
+                Lang::Russian => "Таймаут запроса (0 = без ограничений)".to_string(),
+                _ => return None,
+            },
+            LangMessage::ProxyUrl => match lang {
+                Lang::English => "Proxy URL".to_string(),
+                Lang::Russian => "Адрес прокси".to_string(),
+                _ => return None,
+            },
+            LangMessage::ProxyUsername => match lang {
+                Lang::English => "Proxy username".to_string(),
+                Lang::Russian => "Имя пользователя прокси".to_string(),
+                _ => return None,
+            },
+            LangMessage::ProxyPassword => match lang {
+                Lang::English => "Proxy password".to_string(),
+                Lang::Russian => "Пароль прокси".to_string(),
+                _ => return None,
+            },
+            LangMessage::InvalidProxyUrl => match lang {
+                Lang::English => {
+                    "Invalid proxy URL, expected http://, https:// or socks5://".to_string()
+                }
+                Lang::Russian => {
+                    "Неверный адрес прокси, ожидается http://, https:// или socks5://".to_string()
+                }
+                _ => return None,
+            },
+            LangMessage::TestConnection => match lang {
+                Lang::English => "Test connection".to_string(),
+                Lang::Russian => "Проверить соединение".to_string(),
+                Lang::German => "Verbindung testen".to_string(),
+            },
+            LangMessage::ConnectionOk => match lang {
+                Lang::English => "Connection successful".to_string(),
+                Lang::Russian => "Соединение установлено".to_string(),
+                _ => return None,
+            },
+            LangMessage::ConnectionFailed => match lang {
+                Lang::English => "Connection failed".to_string(),
+                Lang::Russian => "Не удалось подключиться".to_string(),
+                _ => return None,
+            },
+            LangMessage::ExportSettings => match lang {
+                Lang::English => "Export settings".to_string(),
+                Lang::Russian => "Экспорт настроек".to_string(),
+                Lang::German => "Einstellungen exportieren".to_string(),
+            },
+            LangMessage::ImportSettings => match lang {
+                Lang::English => "Import settings".to_string(),
+                Lang::Russian => "Импорт настроек".to_string(),
+                Lang::German => "Einstellungen importieren".to_string(),
+            },
+            LangMessage::IncludeAccountsInExport => match lang {
+                Lang::English => "Include accounts and passwords in export".to_string(),
+                Lang::Russian => "Включить аккаунты и пароли в экспорт".to_string(),
+                _ => return None,
+            },
+            LangMessage::ExportSecretsWarning => match lang {
+                Lang::English => {
+                    "The file will contain account refresh tokens and the proxy password. Do not share it."
+                        .to_string()
+                }
+                Lang::Russian => {
+                    "Файл будет содержать токены аккаунтов и пароль прокси. Не передавайте его другим."
+                        .to_string()
+                }
+                _ => return None,
+            },
+            LangMessage::ReplaceSettingsOnImport => match lang {
+                Lang::English => "Replace current settings on import instead of merging".to_string(),
+                Lang::Russian => "Заменять текущие настройки при импорте вместо объединения".to_string(),
+                _ => return None,
+            },
+            LangMessage::SettingsExported => match lang {
+                Lang::English => "Settings exported".to_string(),
+                Lang::Russian => "Настройки экспортированы".to_string(),
+                _ => return None,
+            },
+            LangMessage::SettingsImported => match lang {
+                Lang::English => "Settings imported".to_string(),
+                Lang::Russian => "Настройки импортированы".to_string(),
+                _ => return None,
+            },
+            LangMessage::ReauthorizeAfterImport => match lang {
+                Lang::English => "Accounts were not included, you will need to sign in again".to_string(),
+                Lang::Russian => "Аккаунты не были включены, потребуется войти заново".to_string(),
+                _ => return None,
+            },
+            LangMessage::UnknownSettingsIgnored { fields } => match lang {
+                Lang::English => format!("Unknown settings were ignored: {}", fields),
+                Lang::Russian => format!("Неизвестные настройки пропущены: {}", fields),
+                _ => return None,
+            },
+            LangMessage::SettingsTransferError => match lang {
+                Lang::English => "Error transferring settings".to_string(),
+                Lang::Russian => "Ошибка переноса настроек".to_string(),
+                _ => return None,
+            },
+            LangMessage::ExtraJvmArgs => match lang {
+                Lang::English => "Extra JVM arguments for this instance".to_string(),
+                Lang::Russian => "Дополнительные аргументы JVM для этой сборки".to_string(),
+                _ => return None,
+            },
+            LangMessage::InvalidArguments => match lang {
+                Lang::English => "Invalid arguments (check the quotes)".to_string(),
+                Lang::Russian => "Некорректные аргументы (проверьте кавычки)".to_string(),
+                _ => return None,
+            },
+            LangMessage::EnvironmentVariables => match lang {
+                Lang::English => {
+                    "Environment variables (override inherited ones with the same name)".to_string()
+                }
+                Lang::Russian => {
+                    "Переменные окружения (заменяют унаследованные с тем же именем)".to_string()
+                }
+                _ => return None,
+            },
+            LangMessage::PreLaunchCommand => match lang {
+                Lang::English => "Command to run before launch".to_string(),
+                Lang::Russian => "Команда перед запуском".to_string(),
+                _ => return None,
+            },
+            LangMessage::PostExitCommand => match lang {
+                Lang::English => "Command to run after the game exits".to_string(),
+                Lang::Russian => "Команда после выхода из игры".to_string(),
+                _ => return None,
+            },
+            LangMessage::WrapperCommand => match lang {
+                Lang::English => "Wrapper command (e.g. gamemoderun, mangohud)".to_string(),
+                Lang::Russian => "Команда-обёртка (например, gamemoderun, mangohud)".to_string(),
+                _ => return None,
+            },
+            LangMessage::QuickPlayServer => match lang {
+                Lang::English => "Join server on launch (Minecraft 1.20+)".to_string(),
+                Lang::Russian => "Подключиться к серверу при запуске (Minecraft 1.20+)".to_string(),
+                _ => return None,
+            },
+            LangMessage::WindowResolution => match lang {
+                Lang::English => "Window size (leave empty for the default)".to_string(),
+                Lang::Russian => "Размер окна (оставьте пустым для значения по умолчанию)".to_string(),
+                _ => return None,
+            },
+            LangMessage::InvalidResolution => match lang {
+                Lang::English => "Width and height must be positive integers".to_string(),
+                Lang::Russian => "Ширина и высота должны быть положительными целыми числами".to_string(),
+                _ => return None,
+            },
+            LangMessage::Fullscreen => match lang {
+                Lang::English => "Fullscreen".to_string(),
+                Lang::Russian => "Полноэкранный режим".to_string(),
+                _ => return None,
             },
             LangMessage::SelectJavaPath => match lang {
                 Lang::English => "Select Java path".to_string(),
                 Lang::Russian => "Выберите путь к Java".to_string(),
+                _ => return None,
             },
             LangMessage::Launch => match lang {
                 Lang::English => "Launch".to_string(),
                 Lang::Russian => "Запустить".to_string(),
+                Lang::German => "Starten".to_string(),
             },
             LangMessage::LaunchError => match lang {
                 Lang::English => "Error launching".to_string(),
                 Lang::Russian => "Ошибка запуска".to_string(),
+                _ => return None,
             },
             LangMessage::ProcessErrorCode(e) => match lang {
                 Lang::English => format!("Process exited with code: {}", e),
                 Lang::Russian => format!("Процесс завершился с кодом: {}", e),
+                _ => return None,
             },
             LangMessage::Running => match lang {
                 Lang::English => "Running...".to_string(),
                 Lang::Russian => "Запущено...".to_string(),
+                Lang::German => "Läuft...".to_string(),
             },
             LangMessage::LanguageName => match lang {
                 Lang::English => "English".to_string(),
                 Lang::Russian => "Русский".to_string(),
+                Lang::German => "Deutsch".to_string(),
             },
             LangMessage::DownloadingUpdate => match lang {
                 Lang::English => "Downloading update...".to_string(),
                 Lang::Russian => "Загрузка обновления...".to_string(),
+                Lang::German => "Update wird heruntergeladen...".to_string(),
             },
             LangMessage::CheckingForUpdates => match lang {
                 Lang::English => "Checking for updates...".to_string(),
                 Lang::Russian => "Проверка обновлений...".to_string(),
+                Lang::German => "Suche nach Updates...".to_string(),
             },
             LangMessage::Launching => match lang {
                 Lang::English => "Launching...".to_string(),
                 Lang::Russian => "Запуск...".to_string(),
+                _ => return None,
             },
             LangMessage::ErrorCheckingForUpdates => match lang {
                 Lang::English => "Error checking for updates".to_string(),
                 Lang::Russian => "Ошибка проверки обновлений".to_string(),
+                _ => return None,
             },
             LangMessage::ErrorDownloadingUpdate => match lang {
                 Lang::English => "Error downloading update".to_string(),
                 Lang::Russian => "Ошибка загрузки обновления".to_string(),
+                _ => return None,
             },
             LangMessage::NoConnectionToUpdateServer => match lang {
                 Lang::English => "No connection to update server".to_string(),
                 Lang::Russian => "Нет подключения к серверу обновлений".to_string(),
+                _ => return None,
             },
-            LangMessage::ErrorReadOnly => match lang {
+            LangMessage::ErrorReadOnly { path } => match lang {
                 Lang::English => {
                     if cfg!(target_os = "macos") {
-                        "Error: read-only mode. If running from a disk image, copy to Applications"
-                            .to_string()
+                        format!("Can't update {}: read-only location. If running from a disk image, copy to Applications", path)
                     } else {
-                        "Error: read-only mode".to_string()
+                        format!("Can't update {}: no write permission. Run the launcher with elevated permissions (as administrator) or move it to a writable folder", path)
                     }
                 }
                 Lang::Russian => {
                     if cfg!(target_os = "macos") {
-                        "Ошибка: режим только для чтения. Если лаунчер запущен из образа диска, скопируйте в Applications".to_string()
+                        format!("Не удалось обновить {}: расположение только для чтения. Если лаунчер запущен из образа диска, скопируйте в Applications", path)
                     } else {
-                        "Ошибка: режим только для чтения".to_string()
+                        format!("Не удалось обновить {}: нет прав на запись. Запустите лаунчер с повышенными правами (от имени администратора) или переместите его в папку с правами на запись", path)
                     }
                 }
+                _ => return None,
+            },
+            LangMessage::UpdateViaPackageManager { version } => match lang {
+                Lang::English => format!(
+                    "Version {} is available, please update via your package manager",
+                    version
+                ),
+                Lang::Russian => format!(
+                    "Доступна версия {}, обновите лаунчер через пакетный менеджер",
+                    version
+                ),
+                _ => return None,
             },
             LangMessage::ProceedToLauncher => match lang {
                 Lang::English => "Proceed to launcher".to_string(),
                 Lang::Russian => "Перейти к лаунчеру".to_string(),
+                Lang::German => "Weiter zum Launcher".to_string(),
             },
             LangMessage::Authorization => match lang {
                 Lang::English => "Authorization".to_string(),
                 Lang::Russian => "Авторизация".to_string(),
+                _ => return None,
             },
             LangMessage::ForceOverwrite => match lang {
                 Lang::English => "Overwrite optional files".to_string(),
                 Lang::Russian => "Перезаписать необязательные файлы".to_string(),
+                _ => return None,
             },
             LangMessage::ForceOverwriteWarning => match lang {
                 Lang::English => "Warning: this may overwrite such files as configs, server list, etc.".to_string(),
                 Lang::Russian => "Внимание: это может перезаписать такие файлы как настройки, список серверов и т.д.".to_string(),
+                _ => return None,
+            },
+            LangMessage::FilesWillBeDeleted { count } => match lang {
+                Lang::English => format!(
+                    "{} files that are not part of the instance will be deleted:",
+                    count
+                ),
+                Lang::Russian => format!(
+                    "Будут удалены файлы, не входящие в версию ({} шт.):",
+                    count
+                ),
+                _ => return None,
+            },
+            LangMessage::AndMore { count } => match lang {
+                Lang::English => format!("...and {} more", count),
+                Lang::Russian => format!("...и ещё {}", count),
+                _ => return None,
+            },
+            LangMessage::DeleteAndSync => match lang {
+                Lang::English => "Delete and sync".to_string(),
+                Lang::Russian => "Удалить и синхронизировать".to_string(),
+                _ => return None,
+            },
+            LangMessage::VerifyFiles => match lang {
+                Lang::English => "Verify files".to_string(),
+                Lang::Russian => "Проверить файлы".to_string(),
+                Lang::German => "Dateien überprüfen".to_string(),
+            },
+            LangMessage::VerifyResult { missing, corrupted } => match lang {
+                Lang::English => format!("Missing files: {}, corrupted files: {}", missing, corrupted),
+                Lang::Russian => format!("Отсутствует файлов: {}, повреждено файлов: {}", missing, corrupted),
+                _ => return None,
+            },
+            LangMessage::AllFilesOk => match lang {
+                Lang::English => "All files are intact".to_string(),
+                Lang::Russian => "Все файлы в порядке".to_string(),
+                _ => return None,
+            },
+            LangMessage::RepairFiles => match lang {
+                Lang::English => "Repair".to_string(),
+                Lang::Russian => "Восстановить".to_string(),
+                _ => return None,
+            },
+            LangMessage::RepairInstallation => match lang {
+                Lang::English => "Repair installation".to_string(),
+                Lang::Russian => "Восстановить установку".to_string(),
+                Lang::German => "Installation reparieren".to_string(),
+            },
+            LangMessage::FilesRepaired { count } => match lang {
+                Lang::English => format!("Instance repaired, files redownloaded: {}", count),
+                Lang::Russian => format!("Версия восстановлена, файлов загружено заново: {}", count),
+                _ => return None,
+            },
+            LangMessage::VerifyError => match lang {
+                Lang::English => "Error verifying files".to_string(),
+                Lang::Russian => "Ошибка проверки файлов".to_string(),
+                _ => return None,
             },
             LangMessage::KillMinecraft => match lang {
                 Lang::English => "Kill Minecraft".to_string(),
                 Lang::Russian => "Закрыть Minecraft".to_string(),
+                _ => return None,
             },
-            LangMessage::HideLauncherAfterLaunch => match lang {
-                Lang::English => "Hide launcher after launch".to_string(),
-                Lang::Russian => "Скрыть лаунчер после запуска".to_string(),
+            LangMessage::KeepLauncherOpenAfterLaunch => match lang {
+                Lang::English => "Keep launcher open after launch".to_string(),
+                Lang::Russian => "Не скрывать лаунчер после запуска".to_string(),
+                _ => return None,
+            },
+            LangMessage::DiscordRichPresence => match lang {
+                Lang::English => "Show the game in Discord status".to_string(),
+                Lang::Russian => "Показывать игру в статусе Discord".to_string(),
+                _ => return None,
+            },
+            LangMessage::MinimizeToTray => match lang {
+                Lang::English => "Minimize to tray instead of closing".to_string(),
+                Lang::Russian => "Сворачивать в трей вместо закрытия".to_string(),
+                _ => return None,
+            },
+            LangMessage::ShowLauncher => match lang {
+                Lang::English => "Show launcher".to_string(),
+                Lang::Russian => "Показать лаунчер".to_string(),
+                _ => return None,
+            },
+            LangMessage::Quit => match lang {
+                Lang::English => "Quit".to_string(),
+                Lang::Russian => "Выйти".to_string(),
+                _ => return None,
             },
             LangMessage::DownloadAndLaunch => match lang {
                 Lang::English => "Download and launch".to_string(),
                 Lang::Russian => "Загрузить и запустить".to_string(),
+                _ => return None,
             },
             LangMessage::CancelLaunch => match lang {
                 Lang::English => "Cancel launch".to_string(),
                 Lang::Russian => "Отменить запуск".to_string(),
+                _ => return None,
             },
             LangMessage::CancelDownload => match lang {
                 Lang::English => "Cancel download".to_string(),
                 Lang::Russian => "Отменить загрузку".to_string(),
+                _ => return None,
             },
             LangMessage::Retry => match lang {
                 Lang::English => "Retry".to_string(),
                 Lang::Russian => "Попробовать снова".to_string(),
+                Lang::German => "Erneut versuchen".to_string(),
             },
             LangMessage::OpenLogs => match lang {
                 Lang::English => "Open logs folder".to_string(),
                 Lang::Russian => "Открыть папку с логами".to_string(),
+                _ => return None,
+            },
+            LangMessage::OpenInstanceFolder => match lang {
+                Lang::English => "Instance folder".to_string(),
+                Lang::Russian => "Папка версии".to_string(),
+                _ => return None,
+            },
+            LangMessage::OpenGameLogs => match lang {
+                Lang::English => "Game logs".to_string(),
+                Lang::Russian => "Логи игры".to_string(),
+                _ => return None,
+            },
+            LangMessage::OpenCrashReports => match lang {
+                Lang::English => "Crash reports".to_string(),
+                Lang::Russian => "Отчёты о сбоях".to_string(),
+                _ => return None,
+            },
+            LangMessage::OpenAssetsFolder => match lang {
+                Lang::English => "Assets folder".to_string(),
+                Lang::Russian => "Папка ресурсов".to_string(),
+                _ => return None,
+            },
+            LangMessage::OpenLauncherLogs => match lang {
+                Lang::English => "Open launcher logs folder".to_string(),
+                Lang::Russian => "Открыть папку с логами лаунчера".to_string(),
+                _ => return None,
+            },
+            LangMessage::TimeRemaining(seconds) => {
+                let minutes = seconds.div_ceil(60);
+                match lang {
+                    Lang::English => {
+                        if *seconds < 60 {
+                            format!("~{} s remaining", seconds)
+                        } else {
+                            format!("~{} min remaining", minutes)
+                        }
+                    }
+                    Lang::Russian => {
+                        if *seconds < 60 {
+                            format!("осталось ~{} с", seconds)
+                        } else {
+                            format!("осталось ~{} мин", minutes)
+                        }
+                    }
+                    _ => return None,
+                }
+            }
+            LangMessage::GameOutput => match lang {
+                Lang::English => "Game output".to_string(),
+                Lang::Russian => "Вывод игры".to_string(),
+                Lang::German => "Spielausgabe".to_string(),
+            },
+            LangMessage::CopyAll => match lang {
+                Lang::English => "Copy all".to_string(),
+                Lang::Russian => "Копировать всё".to_string(),
+                Lang::German => "Alles kopieren".to_string(),
+            },
+            LangMessage::Copy => match lang {
+                Lang::English => "Copy".to_string(),
+                Lang::Russian => "Копировать".to_string(),
+                Lang::German => "Kopieren".to_string(),
             },
             LangMessage::LoadingMetadata => match lang {
                 Lang::English => "Loading metadata...".to_string(),
                 Lang::Russian => "Загрузка метаданных...".to_string(),
+                _ => return None,
             },
             LangMessage::MetadataErrorOffline => match lang {
                 Lang::English => "No connection to metadata server".to_string(),
                 Lang::Russian => "Нет подключения к серверу метаданных".to_string(),
+                _ => return None,
             },
             LangMessage::MetadataFetchError => match lang {
                 Lang::English => "Error fetching metadata".to_string(),
                 Lang::Russian => "Ошибка получения метаданных".to_string(),
+                _ => return None,
             },
             LangMessage::NewInstance => match lang {
                 Lang::English => "New instance".to_string(),
                 Lang::Russian => "Новая версия".to_string(),
+                Lang::German => "Neue Instanz".to_string(),
             },
             LangMessage::NewInstanceName => match lang {
                 Lang::English => "New instance name".to_string(),
                 Lang::Russian => "Название новой версии".to_string(),
+                _ => return None,
             },
             LangMessage::GameVersion => match lang {
                 Lang::English => "Game version".to_string(),
                 Lang::Russian => "Версия игры".to_string(),
+                _ => return None,
             },
             LangMessage::Loader => match lang {
                 Lang::English => "Loader".to_string(),
                 Lang::Russian => "Лоадер".to_string(),
+                _ => return None,
             },
             LangMessage::LoaderVersion => match lang {
                 Lang::English => "Loader version".to_string(),
                 Lang::Russian => "Версия лоадера".to_string(),
+                _ => return None,
+            },
+            LangMessage::InvalidInstanceName => match lang {
+                Lang::English => "Instance name can't contain path separators, \"..\" or \":\"".to_string(),
+                Lang::Russian => "Имя версии не может содержать разделители пути, \"..\" или \":\"".to_string(),
+                _ => return None,
             },
             LangMessage::InstanceNameExists => match lang {
                 Lang::English => "Instance name already exists".to_string(),
                 Lang::Russian => "Версия с таким именем уже существует".to_string(),
+                _ => return None,
             },
             LangMessage::CreateInstance => match lang {
                 Lang::English => "Create instance".to_string(),
                 Lang::Russian => "Создать версию".to_string(),
+                Lang::German => "Instanz erstellen".to_string(),
             },
             LangMessage::CreatingInstance => match lang {
                 Lang::English => "Creating instance...".to_string(),
                 Lang::Russian => "Создание версии...".to_string(),
+                _ => return None,
             },
             LangMessage::Cancel => match lang {
                 Lang::English => "Cancel".to_string(),
                 Lang::Russian => "Отмена".to_string(),
+                Lang::German => "Abbrechen".to_string(),
             },
             LangMessage::InstanceGenerateErrorOffline => match lang {
                 Lang::English => "Error generating instance: no connection".to_string(),
                 Lang::Russian => "Ошибка создания версии: нет подключения".to_string(),
+                _ => return None,
             },
             LangMessage::InstanceGenerateError => match lang {
                 Lang::English => "Error generating instance".to_string(),
                 Lang::Russian => "Ошибка создания версии".to_string(),
+                _ => return None,
             },
             LangMessage::LongTimeWarning => match lang {
                 Lang::English => "This may take a couple of minutes".to_string(),
                 Lang::Russian => "Это может занять несколько минут".to_string(),
+                _ => return None,
             },
             LangMessage::DeleteInstance => match lang {
                 Lang::English => "Delete instance".to_string(),
                 Lang::Russian => "Удалить версию".to_string(),
+                _ => return None,
             },
             LangMessage::SelectInstanceToDelete => match lang {
                 Lang::English => "Select instance to delete".to_string(),
                 Lang::Russian => "Выберите версию для удаления".to_string(),
+                _ => return None,
             },
             LangMessage::ConfirmDelete => match lang {
                 Lang::English => "I understand that this action is irreversible".to_string(),
                 Lang::Russian => "Я понимаю, что назад пути нет".to_string(),
+                _ => return None,
             },
             LangMessage::Delete => match lang {
                 Lang::English => "Delete".to_string(),
                 Lang::Russian => "Удалить".to_string(),
+                Lang::German => "Löschen".to_string(),
             },
             LangMessage::AddAccount => match lang {
                 Lang::English => "Add account".to_string(),
                 Lang::Russian => "Добавить аккаунт".to_string(),
+                Lang::German => "Konto hinzufügen".to_string(),
             },
             LangMessage::SelectAccount => match lang {
                 Lang::English => "Select account".to_string(),
                 Lang::Russian => "Выберите аккаунт".to_string(),
+                Lang::German => "Konto auswählen".to_string(),
             },
             LangMessage::AddAndAuthenticate => match lang {
                 Lang::English => "Add and authenticate".to_string(),
                 Lang::Russian => "Добавить и авторизоваться".to_string(),
+                _ => return None,
             },
             LangMessage::Offline => match lang {
                 Lang::English => "Offline".to_string(),
                 Lang::Russian => "Офлайн".to_string(),
+                Lang::German => "Offline".to_string(),
             },
             LangMessage::FetchingRemote => match lang {
                 Lang::English => "Fetching...".to_string(),
                 Lang::Russian => "Загрузка...".to_string(),
+                _ => return None,
             },
             LangMessage::ErrorFetchingRemote => match lang {
                 Lang::English => "Error fetching".to_string(),
                 Lang::Russian => "Ошибка загрузки".to_string(),
+                _ => return None,
             },
             LangMessage::InstanceSyncProgress => match lang {
                 Lang::English => "Instance sync progress".to_string(),
                 Lang::Russian => "Прогресс синхронизации версии".to_string(),
+                _ => return None,
             },
             LangMessage::AddOfflineAccount => match lang {
                 Lang::English => "Add offline account".to_string(),
                 Lang::Russian => "Добавить офлайн аккаунт".to_string(),
+                _ => return None,
             },
             LangMessage::EnterNickname => match lang {
                 Lang::English => "Enter nickname".to_string(),
                 Lang::Russian => "Введите никнейм".to_string(),
+                _ => return None,
             },
             LangMessage::GettingMetadata => match lang {
                 Lang::English => "Getting metadata...".to_string(),
                 Lang::Russian => "Получение метаданных...".to_string(),
+                _ => return None,
             },
             LangMessage::NoMetadata => match lang {
                 Lang::English => "No metadata".to_string(),
                 Lang::Russian => "Метаданные отсутствуют".to_string(),
+                _ => return None,
             },
             LangMessage::MetadataUpToDate => match lang {
                 Lang::English => "Metadata up-to-date".to_string(),
                 Lang::Russian => "Метаданные актуальны".to_string(),
+                _ => return None,
             },
             LangMessage::ReadLocalRemoteError => match lang {
                 Lang::English => "Local metadata (fetch error)".to_string(),
                 Lang::Russian => "Локальные метаданные (ошибка загрузки)".to_string(),
+                _ => return None,
             },
             LangMessage::ReadLocalOffline => match lang {
                 Lang::English => "Local metadata (offline)".to_string(),
                 Lang::Russian => "Локальные метаданные (офлайн)".to_string(),
+                _ => return None,
             },
             LangMessage::ErrorGettingMetadata => match lang {
                 Lang::English => "Error getting metadata".to_string(),
                 Lang::Russian => "Ошибка получения метаданных".to_string(),
-            },
-        }
+                _ => return None,
+            },
+            LangMessage::ChangeSkin => match lang {
+                Lang::English => "Change skin".to_string(),
+                Lang::Russian => "Сменить скин".to_string(),
+                _ => return None,
+            },
+            LangMessage::OpenSkinPage => match lang {
+                Lang::English => "Open skin page on minecraft.net".to_string(),
+                Lang::Russian => "Открыть страницу скина на minecraft.net".to_string(),
+                _ => return None,
+            },
+            LangMessage::SlimSkinModel => match lang {
+                Lang::English => "Slim model (Alex)".to_string(),
+                Lang::Russian => "Тонкая модель (Алекс)".to_string(),
+                _ => return None,
+            },
+            LangMessage::UploadSkin => match lang {
+                Lang::English => "Upload skin file...".to_string(),
+                Lang::Russian => "Загрузить файл скина...".to_string(),
+                _ => return None,
+            },
+            LangMessage::SkinUploaded => match lang {
+                Lang::English => "Skin uploaded".to_string(),
+                Lang::Russian => "Скин загружен".to_string(),
+                _ => return None,
+            },
+            LangMessage::SkinUploadError => match lang {
+                Lang::English => "Error uploading skin".to_string(),
+                Lang::Russian => "Ошибка загрузки скина".to_string(),
+                _ => return None,
+            },
+            LangMessage::DownloadJava => match lang {
+                Lang::English => "Download Java".to_string(),
+                Lang::Russian => "Скачать Java".to_string(),
+                _ => return None,
+            },
+            LangMessage::DownloadFullJdk => match lang {
+                Lang::English => "Download full JDK instead of JRE".to_string(),
+                Lang::Russian => "Скачивать полный JDK вместо JRE".to_string(),
+                _ => return None,
+            },
+            LangMessage::OfflineCachedList => match lang {
+                Lang::English => "offline, using cached list".to_string(),
+                Lang::Russian => "офлайн, сохранённый список".to_string(),
+                _ => return None,
+            },
+            LangMessage::LauncherCrashed => match lang {
+                Lang::English => "The launcher crashed".to_string(),
+                Lang::Russian => "Лаунчер аварийно завершился".to_string(),
+                _ => return None,
+            },
+            LangMessage::LauncherCrashedDescription => match lang {
+                Lang::English => "The launcher crashed during the last run. The report below can be copied and sent to the developers.".to_string(),
+                Lang::Russian => "Лаунчер аварийно завершился при прошлом запуске. Отчёт ниже можно скопировать и отправить разработчикам.".to_string(),
+                _ => return None,
+            },
+            LangMessage::Close => match lang {
+                Lang::English => "Close".to_string(),
+                Lang::Russian => "Закрыть".to_string(),
+                _ => return None,
+            },
+            LangMessage::LaunchWithCachedVersion => match lang {
+                Lang::English => "Launch with last-known version".to_string(),
+                Lang::Russian => "Запустить последнюю известную версию".to_string(),
+                _ => return None,
+            },
+            LangMessage::MetadataTimeout => match lang {
+                Lang::English => "Offer the cached version after".to_string(),
+                Lang::Russian => "Предлагать сохранённую версию через".to_string(),
+                _ => return None,
+            },
+            LangMessage::InstanceMemory => match lang {
+                Lang::English => "Instance memory (empty uses the default)".to_string(),
+                Lang::Russian => "Память для версии (пусто — по умолчанию)".to_string(),
+                _ => return None,
+            },
+            LangMessage::ImportMinecraftDir => match lang {
+                Lang::English => "Import saves and settings from .minecraft...".to_string(),
+                Lang::Russian => "Импортировать миры и настройки из .minecraft...".to_string(),
+                _ => return None,
+            },
+            LangMessage::ImportingMinecraftDir => match lang {
+                Lang::English => "Importing...".to_string(),
+                Lang::Russian => "Импорт...".to_string(),
+                _ => return None,
+            },
+            LangMessage::ImportOverwriteWarning { paths } => match lang {
+                Lang::English => format!("The instance already has {}. Importing will overwrite them.", paths),
+                Lang::Russian => format!("В сборке уже есть {}. Импорт перезапишет их.", paths),
+                _ => return None,
+            },
+            LangMessage::ImportAndOverwrite => match lang {
+                Lang::English => "Import and overwrite".to_string(),
+                Lang::Russian => "Импортировать и перезаписать".to_string(),
+                _ => return None,
+            },
+            LangMessage::ImportedMinecraftDir { paths } => match lang {
+                Lang::English => format!("Imported: {}. Sync will not overwrite them.", paths),
+                Lang::Russian => format!("Импортировано: {}. Синхронизация не будет их перезаписывать.", paths),
+                _ => return None,
+            },
+            LangMessage::ImportMinecraftDirError => match lang {
+                Lang::English => "Error importing from .minecraft".to_string(),
+                Lang::Russian => "Ошибка импорта из .minecraft".to_string(),
+                _ => return None,
+            },
+            LangMessage::Welcome => match lang {
+                Lang::English => "Welcome".to_string(),
+                Lang::Russian => "Добро пожаловать".to_string(),
+                _ => return None,
+            },
+            LangMessage::WizardStep { current, total } => match lang {
+                Lang::English => format!("Step {} of {}", current, total),
+                Lang::Russian => format!("Шаг {} из {}", current, total),
+                _ => return None,
+            },
+            LangMessage::WizardChooseLanguage => match lang {
+                Lang::English => "Choose your language".to_string(),
+                Lang::Russian => "Выберите язык".to_string(),
+                _ => return None,
+            },
+            LangMessage::WizardChooseInstance => match lang {
+                Lang::English => "Choose a modpack".to_string(),
+                Lang::Russian => "Выберите сборку".to_string(),
+                _ => return None,
+            },
+            LangMessage::WizardSignIn => match lang {
+                Lang::English => "Sign in".to_string(),
+                Lang::Russian => "Войдите в аккаунт".to_string(),
+                _ => return None,
+            },
+            LangMessage::WizardDataDir => match lang {
+                Lang::English => "Game files will be stored here".to_string(),
+                Lang::Russian => "Файлы игры будут храниться здесь".to_string(),
+                _ => return None,
+            },
+            LangMessage::ChangeDataDir => match lang {
+                Lang::English => "Change...".to_string(),
+                Lang::Russian => "Изменить...".to_string(),
+                _ => return None,
+            },
+            LangMessage::DataDirNotWritable => match lang {
+                Lang::English => "The selected folder is not writable".to_string(),
+                Lang::Russian => "Нет прав на запись в выбранную папку".to_string(),
+                _ => return None,
+            },
+            LangMessage::WizardReady => match lang {
+                Lang::English => "All set! The modpack will be downloaded and launched".to_string(),
+                Lang::Russian => "Всё готово! Сборка будет скачана и запущена".to_string(),
+                _ => return None,
+            },
+            LangMessage::DownloadAndPlay => match lang {
+                Lang::English => "Download and play".to_string(),
+                Lang::Russian => "Скачать и играть".to_string(),
+                _ => return None,
+            },
+            LangMessage::Back => match lang {
+                Lang::English => "Back".to_string(),
+                Lang::Russian => "Назад".to_string(),
+                _ => return None,
+            },
+            LangMessage::Next => match lang {
+                Lang::English => "Next".to_string(),
+                Lang::Russian => "Далее".to_string(),
+                _ => return None,
+            },
+            LangMessage::SkipSetup => match lang {
+                Lang::English => "Skip setup".to_string(),
+                Lang::Russian => "Пропустить".to_string(),
+                _ => return None,
+            },
+            LangMessage::OpenAuthLink => match lang {
+                Lang::English => "Open the link below to authorize, or scan the QR code.".to_string(),
+                Lang::Russian => "Откройте ссылку ниже для авторизации или отсканируйте QR-код.".to_string(),
+                _ => return None,
+            },
+            LangMessage::OpenLink => match lang {
+                Lang::English => "Open".to_string(),
+                Lang::Russian => "Открыть".to_string(),
+                _ => return None,
+            },
+            LangMessage::CopyLink => match lang {
+                Lang::English => "Copy link".to_string(),
+                Lang::Russian => "Копировать ссылку".to_string(),
+                _ => return None,
+            },
+            LangMessage::CopyCode => match lang {
+                Lang::English => "Copy code".to_string(),
+                Lang::Russian => "Копировать код".to_string(),
+                _ => return None,
+            },
+            LangMessage::AutoOpenAuthUrls => match lang {
+                Lang::English => "Open authorization links automatically".to_string(),
+                Lang::Russian => "Автоматически открывать ссылки для авторизации".to_string(),
+                _ => return None,
+            },
+            LangMessage::ApplyingUpdate => match lang {
+                Lang::English => "Applying update...".to_string(),
+                Lang::Russian => "Установка обновления...".to_string(),
+                _ => return None,
+            },
+            LangMessage::UnpackingUpdate => match lang {
+                Lang::English => "Unpacking the update".to_string(),
+                Lang::Russian => "Распаковка обновления".to_string(),
+                _ => return None,
+            },
+            LangMessage::ReplacingLauncher => match lang {
+                Lang::English => "Replacing the launcher".to_string(),
+                Lang::Russian => "Замена лаунчера".to_string(),
+                _ => return None,
+            },
+            LangMessage::RemovingOldLauncher => match lang {
+                Lang::English => "Removing the old version".to_string(),
+                Lang::Russian => "Удаление старой версии".to_string(),
+                _ => return None,
+            },
+            LangMessage::UpdateNotApplied { expected, current } => match lang {
+                Lang::English => format!(
+                    "The update to version {} did not take effect, the launcher is still at version {}. Try again later or reinstall the launcher.",
+                    expected, current
+                ),
+                Lang::Russian => format!(
+                    "Обновление до версии {} не применилось, версия лаунчера по-прежнему {}. Попробуйте позже или переустановите лаунчер.",
+                    expected, current
+                ),
+                _ => return None,
+            },
+            LangMessage::SpaceToFree { size } => {
+                let size_mb = *size as f64 / (1024.0 * 1024.0);
+                match lang {
+                    Lang::English => format!("About {:.1} MB will be freed", size_mb),
+                    Lang::Russian => format!("Будет освобождено около {:.1} МБ", size_mb),
+                    _ => return None,
+                }
+            }
+            LangMessage::CleanUnusedAssets => match lang {
+                Lang::English => "Also delete assets not used by other instances".to_string(),
+                Lang::Russian => "Также удалить ресурсы, не используемые другими сборками".to_string(),
+                _ => return None,
+            },
+            LangMessage::FindUnusedAssets => match lang {
+                Lang::English => "Find unused assets".to_string(),
+                Lang::Russian => "Найти неиспользуемые ресурсы".to_string(),
+                _ => return None,
+            },
+            LangMessage::NoUnusedAssets => match lang {
+                Lang::English => "No unused assets found".to_string(),
+                Lang::Russian => "Неиспользуемых ресурсов не найдено".to_string(),
+                _ => return None,
+            },
+            LangMessage::UnusedAssetsFound { count, size } => {
+                let size_mb = *size as f64 / (1024.0 * 1024.0);
+                match lang {
+                    Lang::English => format!("{} unused files, {:.1} MB", count, size_mb),
+                    Lang::Russian => format!("Неиспользуемых файлов: {}, {:.1} МБ", count, size_mb),
+                    _ => return None,
+                }
+            }
+            LangMessage::DeleteUnusedAssets => match lang {
+                Lang::English => "Delete them".to_string(),
+                Lang::Russian => "Удалить их".to_string(),
+                _ => return None,
+            },
+            LangMessage::UnusedAssetsDeleted { count, size } => {
+                let size_mb = *size as f64 / (1024.0 * 1024.0);
+                match lang {
+                    Lang::English => format!("Deleted {} files, freed {:.1} MB", count, size_mb),
+                    Lang::Russian => format!("Удалено файлов: {}, освобождено {:.1} МБ", count, size_mb),
+                    _ => return None,
+                }
+            }
+            LangMessage::AssetsCleanupError => match lang {
+                Lang::English => "Error cleaning up assets".to_string(),
+                Lang::Russian => "Ошибка очистки ресурсов".to_string(),
+                _ => return None,
+            },
+            LangMessage::InstanceAlreadyRunning => match lang {
+                Lang::English => "This instance is already running".to_string(),
+                Lang::Russian => "Эта сборка уже запущена".to_string(),
+                _ => return None,
+            },
+            LangMessage::JvmArgsPreset => match lang {
+                Lang::English => "JVM arguments preset".to_string(),
+                Lang::Russian => "Набор аргументов JVM".to_string(),
+                _ => return None,
+            },
+            LangMessage::JvmArgsPresetName(preset) => match (preset, lang) {
+                (JvmArgsPreset::Default, Lang::English) => "Launcher default".to_string(),
+                (JvmArgsPreset::Default, Lang::Russian) => "По умолчанию".to_string(),
+                (JvmArgsPreset::Aikar, Lang::English) => "Aikar's flags".to_string(),
+                (JvmArgsPreset::Aikar, Lang::Russian) => "Флаги Aikar".to_string(),
+                (JvmArgsPreset::Custom, Lang::English) => "Custom (extra arguments only)".to_string(),
+                (JvmArgsPreset::Custom, Lang::Russian) => "Свои (только дополнительные аргументы)".to_string(),
+                _ => return None,
+            },
+            LangMessage::SortInstances => match lang {
+                Lang::English => "Sort instances by".to_string(),
+                Lang::Russian => "Сортировать сборки".to_string(),
+                _ => return None,
+            },
+            LangMessage::InstanceSortName(sort) => match (sort, lang) {
+                (InstanceSort::LastPlayed, Lang::English) => "Last played".to_string(),
+                (InstanceSort::LastPlayed, Lang::Russian) => "По последнему запуску".to_string(),
+                (InstanceSort::RecentlySynced, Lang::English) => "Recently synced".to_string(),
+                (InstanceSort::RecentlySynced, Lang::Russian) => "По последней синхронизации".to_string(),
+                (InstanceSort::Alphabetical, Lang::English) => "Name".to_string(),
+                (InstanceSort::Alphabetical, Lang::Russian) => "По названию".to_string(),
+                _ => return None,
+            },
+            LangMessage::DiskUsage => match lang {
+                Lang::English => "Disk usage".to_string(),
+                Lang::Russian => "Использование диска".to_string(),
+                _ => return None,
+            },
+            LangMessage::DataDirUsage { used, free } => {
+                let used_gb = *used as f64 / (1024.0 * 1024.0 * 1024.0);
+                let free_gb = *free as f64 / (1024.0 * 1024.0 * 1024.0);
+                match lang {
+                    Lang::English => format!("Data directory: {:.1} GB used, {:.1} GB free", used_gb, free_gb),
+                    Lang::Russian => format!("Папка данных: занято {:.1} ГБ, свободно {:.1} ГБ", used_gb, free_gb),
+                    _ => return None,
+                }
+            }
+            LangMessage::DiskUsageError => match lang {
+                Lang::English => "Error calculating disk usage".to_string(),
+                Lang::Russian => "Ошибка подсчёта занятого места".to_string(),
+                _ => return None,
+            },
+            LangMessage::Refresh => match lang {
+                Lang::English => "Refresh".to_string(),
+                Lang::Russian => "Обновить".to_string(),
+                _ => return None,
+            },
+            LangMessage::IncludeSnapshots => match lang {
+                Lang::English => "Show snapshots".to_string(),
+                Lang::Russian => "Показывать снапшоты".to_string(),
+                _ => return None,
+            },
+            LangMessage::SnapshotInstance => match lang {
+                Lang::English => "Snapshot version, may be unstable".to_string(),
+                Lang::Russian => "Снапшот, может работать нестабильно".to_string(),
+                _ => return None,
+            },
+            LangMessage::JavaUnavailableForPlatform {
+                required_version,
+                platform,
+            } => match lang {
+                Lang::English => format!("Java {} can't be downloaded for {}. Install it manually and select it in the settings", required_version, platform),
+                Lang::Russian => format!("Java {} недоступна для загрузки на {}. Установите её вручную и выберите в настройках", required_version, platform),
+                _ => return None,
+            },
+            LangMessage::AdvancedSettings => match lang {
+                Lang::English => "Advanced".to_string(),
+                Lang::Russian => "Дополнительно".to_string(),
+                _ => return None,
+            },
+            LangMessage::SkipUpdateForNow => match lang {
+                Lang::English => "Skip update for now".to_string(),
+                Lang::Russian => "Пропустить обновление".to_string(),
+                _ => return None,
+            },
+            LangMessage::JavaVerificationFailed => match lang {
+                Lang::English => "Java verification failed, downloading a fresh copy".to_string(),
+                Lang::Russian => "Java не прошла проверку, скачиваем заново".to_string(),
+                _ => return None,
+            },
+            LangMessage::ClearCaches => match lang {
+                Lang::English => "Clear caches".to_string(),
+                Lang::Russian => "Очистить кэш".to_string(),
+                _ => return None,
+            },
+            LangMessage::CachesCleared { size } => {
+                let size_mb = *size as f64 / (1024.0 * 1024.0);
+                match lang {
+                    Lang::English => format!("Caches cleared, freed {:.1} MB", size_mb),
+                    Lang::Russian => format!("Кэш очищен, освобождено {:.1} МБ", size_mb),
+                    _ => return None,
+                }
+            }
+            LangMessage::ClearCachesError => match lang {
+                Lang::English => "Error clearing caches".to_string(),
+                Lang::Russian => "Ошибка очистки кэша".to_string(),
+                _ => return None,
+            },
+            LangMessage::LaunchOfflineWithCurrentFiles => match lang {
+                Lang::English => "Launch offline with current files".to_string(),
+                Lang::Russian => "Запустить с текущими файлами".to_string(),
+                _ => return None,
+            },
+            LangMessage::InstanceMayBeOutdated => match lang {
+                Lang::English => "The instance could not be synced and may be out of date".to_string(),
+                Lang::Russian => "Не удалось синхронизировать сборку, она может быть устаревшей".to_string(),
+                _ => return None,
+            },
+            LangMessage::JavaXMXExceedsSystemMemory { total_mb } => match lang {
+                Lang::English => format!(
+                    "More than the system memory ({} MB), the value is not saved",
+                    total_mb
+                ),
+                Lang::Russian => format!(
+                    "Больше, чем объём памяти системы ({} МБ), значение не сохранено",
+                    total_mb
+                ),
+                _ => return None,
+            },
+            LangMessage::JavaXMXHighMemory { total_mb } => match lang {
+                Lang::English => format!(
+                    "Close to the system memory ({} MB), the game or the system may slow down or crash",
+                    total_mb
+                ),
+                Lang::Russian => format!(
+                    "Близко к объёму памяти системы ({} МБ), игра или система могут тормозить или падать",
+                    total_mb
+                ),
+                _ => return None,
+            },
+            LangMessage::JavaBuild => match lang {
+                Lang::English => "Java build:".to_string(),
+                Lang::Russian => "Сборка Java:".to_string(),
+                _ => return None,
+            },
+            LangMessage::LatestJavaBuild => match lang {
+                Lang::English => "Latest (default)".to_string(),
+                Lang::Russian => "Последняя (по умолчанию)".to_string(),
+                _ => return None,
+            },
+            LangMessage::ListJavaBuilds => match lang {
+                Lang::English => "Show available builds".to_string(),
+                Lang::Russian => "Показать доступные сборки".to_string(),
+                _ => return None,
+            },
+            LangMessage::ListJavaBuildsError => match lang {
+                Lang::English => "Error getting the list of Java builds".to_string(),
+                Lang::Russian => "Ошибка получения списка сборок Java".to_string(),
+                _ => return None,
+            },
+            LangMessage::ReportIssue => match lang {
+                Lang::English => "Report an issue".to_string(),
+                Lang::Russian => "Сообщить о проблеме".to_string(),
+                _ => return None,
+            },
+            LangMessage::IssueReportCreated { path } => match lang {
+                Lang::English => format!("Report saved to {}, attach it to your issue", path),
+                Lang::Russian => format!("Отчёт сохранён в {}, приложите его к сообщению о проблеме", path),
+                _ => return None,
+            },
+            LangMessage::IssueReportError => match lang {
+                Lang::English => "Error creating the report".to_string(),
+                Lang::Russian => "Ошибка создания отчёта".to_string(),
+                _ => return None,
+            },
+            LangMessage::GameCrashedOnStartup(code) => match lang {
+                Lang::English => format!("Game crashed on startup (exit code {})", code),
+                Lang::Russian => format!("Игра аварийно завершилась при запуске (код {})", code),
+                _ => return None,
+            },
+            LangMessage::GameDir => match lang {
+                Lang::English => "Game folder".to_string(),
+                Lang::Russian => "Папка игры".to_string(),
+                _ => return None,
+            },
+            LangMessage::ChangeGameDir => match lang {
+                Lang::English => "Change".to_string(),
+                Lang::Russian => "Изменить".to_string(),
+                _ => return None,
+            },
+            LangMessage::ResetGameDir => match lang {
+                Lang::English => "Reset to default".to_string(),
+                Lang::Russian => "Сбросить".to_string(),
+                _ => return None,
+            },
+            LangMessage::GameDirNotWritable => match lang {
+                Lang::English => "The selected folder is not writable".to_string(),
+                Lang::Russian => "Нет прав на запись в выбранную папку".to_string(),
+                _ => return None,
+            },
+            LangMessage::RunningPreLaunchCommand => match lang {
+                Lang::English => "Running the pre-launch command...".to_string(),
+                Lang::Russian => "Выполнение команды перед запуском...".to_string(),
+                _ => return None,
+            },
+            LangMessage::IncompleteResolution => match lang {
+                Lang::English => "Fill in both width and height or leave both empty".to_string(),
+                Lang::Russian => "Заполните и ширину, и высоту или оставьте оба поля пустыми".to_string(),
+                _ => return None,
+            },
+            LangMessage::FilesPerSecond(rate) => match lang {
+                Lang::English => format!("{:.1} files/s", rate),
+                Lang::Russian => format!("{:.1} файлов/с", rate),
+                _ => return None,
+            },
+            LangMessage::DataDirUnavailable { path, fallback } => match lang {
+                Lang::English => format!("Data folder {} is missing or not writable, using {}", path, fallback),
+                Lang::Russian => format!("Папка данных {} недоступна для записи или отсутствует, используется {}", path, fallback),
+                _ => return None,
+            },
+            LangMessage::InvalidEnvVar { name } => match lang {
+                Lang::English => format!("Invalid environment variable {}: names can't contain '=' and values can't contain null characters", name),
+                Lang::Russian => format!("Недопустимая переменная окружения {}: имя не может содержать '=', а значение — нулевые символы", name),
+                _ => return None,
+            },
+            LangMessage::DuplicateEnvVar { name } => match lang {
+                Lang::English => format!("Environment variable {} is set more than once", name),
+                Lang::Russian => format!("Переменная окружения {} задана несколько раз", name),
+                _ => return None,
+            },
+        })
     }
 }
@@ -0,0 +1,41 @@
+use discord_rich_presence::activity::{Activity, Timestamps};
+use discord_rich_presence::{DiscordIpc, DiscordIpcClient};
+
+use crate::config::build_config;
+
+#[derive(thiserror::Error, Debug)]
+pub enum DiscordPresenceError {
+    #[error("Discord client id is not set")]
+    ClientIdNotSet,
+}
+
+pub struct DiscordPresence {
+    client: DiscordIpcClient,
+}
+
+impl DiscordPresence {
+    pub fn start(instance_name: &str, minecraft_version: &str) -> anyhow::Result<Self> {
+        let client_id =
+            build_config::get_discord_client_id().ok_or(DiscordPresenceError::ClientIdNotSet)?;
+        let mut client = DiscordIpcClient::new(client_id);
+        client.connect()?;
+
+        let start = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_millis() as i64;
+        let activity = Activity::new()
+            .details(instance_name)
+            .state(format!("Minecraft {}", minecraft_version))
+            .timestamps(Timestamps::new().start(start));
+        client.set_activity(activity)?;
+
+        Ok(DiscordPresence { client })
+    }
+}
+
+impl Drop for DiscordPresence {
+    fn drop(&mut self) {
+        let _ = self.client.clear_activity();
+        let _ = self.client.close();
+    }
+}
@@ -0,0 +1,80 @@
+use std::fs::{File, TryLockError};
+use std::io::{Read as _, Seek as _, SeekFrom, Write as _};
+use std::path::Path;
+
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+const LOCK_FILE_NAME: &str = ".launcher.lock";
+
+#[derive(thiserror::Error, Debug)]
+pub enum InstanceLockError {
+    #[error("Instance {0} is already running")]
+    AlreadyRunning(String),
+}
+
+fn get_process_start_time(pid: u32) -> Option<u64> {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+    system.process(pid).map(|process| process.start_time())
+}
+
+// the lock file holds "<pid> <start time>" of the last game started in the instance,
+// the start time tells a live game apart from an unrelated process that reused the pid
+fn is_recorded_game_running(content: &str) -> bool {
+    let mut parts = content.split_whitespace();
+    let (Some(Ok(pid)), Some(Ok(start_time))) = (
+        parts.next().map(str::parse::<u32>),
+        parts.next().map(str::parse::<u64>),
+    ) else {
+        return false;
+    };
+    get_process_start_time(pid) == Some(start_time)
+}
+
+/// Held for as long as the game is running in the instance directory.
+/// The OS releases the file lock if the launcher exits, the recorded game process keeps
+/// the instance locked after that until it exits too.
+pub struct InstanceLock {
+    file: File,
+}
+
+impl InstanceLock {
+    pub fn acquire(instance_dir: &Path, instance_name: &str) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(instance_dir)?;
+        let mut file = File::options()
+            .create(true)
+            .truncate(false)
+            .read(true)
+            .write(true)
+            .open(instance_dir.join(LOCK_FILE_NAME))?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                return Err(InstanceLockError::AlreadyRunning(instance_name.to_string()).into())
+            }
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
+        if is_recorded_game_running(&content) {
+            return Err(InstanceLockError::AlreadyRunning(instance_name.to_string()).into());
+        }
+        Ok(InstanceLock { file })
+    }
+
+    /// Records the game process so that a restarted launcher still sees the instance as running.
+    pub fn record_process(&mut self, pid: u32) -> anyhow::Result<()> {
+        let start_time = get_process_start_time(pid).unwrap_or_default();
+        self.file.set_len(0)?;
+        self.file.seek(SeekFrom::Start(0))?;
+        write!(self.file, "{} {}", pid, start_time)?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
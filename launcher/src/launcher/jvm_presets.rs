@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_GC_OPTIONS: &[&str] = &[
+    "-XX:+UnlockExperimentalVMOptions",
+    "-XX:+UseG1GC",
+    "-XX:G1NewSizePercent=20",
+    "-XX:G1ReservePercent=20",
+    "-XX:MaxGCPauseMillis=50",
+    "-XX:G1HeapRegionSize=32M",
+    "-XX:+DisableExplicitGC",
+    "-XX:+AlwaysPreTouch",
+    "-XX:+ParallelRefProcEnabled",
+];
+
+// https://docs.papermc.io/paper/aikars-flags
+const AIKAR_GC_OPTIONS: &[&str] = &[
+    "-XX:+UseG1GC",
+    "-XX:+ParallelRefProcEnabled",
+    "-XX:MaxGCPauseMillis=200",
+    "-XX:+UnlockExperimentalVMOptions",
+    "-XX:+DisableExplicitGC",
+    "-XX:+AlwaysPreTouch",
+    "-XX:G1HeapWastePercent=5",
+    "-XX:G1MixedGCCountTarget=4",
+    "-XX:G1MixedGCLiveThresholdPercent=90",
+    "-XX:SurvivorRatio=32",
+    "-XX:+PerfDisableSharedMem",
+    "-XX:MaxTenuringThreshold=1",
+];
+
+const AIKAR_LARGE_HEAP_MB: u32 = 12 * 1024;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum JvmArgsPreset {
+    #[default]
+    Default,
+    Aikar,
+    /// Only the instance's extra JVM arguments are passed.
+    Custom,
+}
+
+impl JvmArgsPreset {
+    pub fn get_gc_options(self, xmx_mb: u32) -> Vec<String> {
+        match self {
+            JvmArgsPreset::Default => DEFAULT_GC_OPTIONS.iter().map(|&s| s.to_string()).collect(),
+            JvmArgsPreset::Aikar => {
+                // young generation sizing differs for heaps above 12 GB
+                let (new_size, max_new_size, region_size, reserve, ihop) =
+                    if xmx_mb >= AIKAR_LARGE_HEAP_MB {
+                        (40, 50, "16M", 15, 20)
+                    } else {
+                        (30, 40, "8M", 20, 15)
+                    };
+                let mut options: Vec<String> =
+                    AIKAR_GC_OPTIONS.iter().map(|&s| s.to_string()).collect();
+                options.extend([
+                    format!("-XX:G1NewSizePercent={}", new_size),
+                    format!("-XX:G1MaxNewSizePercent={}", max_new_size),
+                    format!("-XX:G1HeapRegionSize={}", region_size),
+                    format!("-XX:G1ReservePercent={}", reserve),
+                    format!("-XX:InitiatingHeapOccupancyPercent={}", ihop),
+                ]);
+                options
+            }
+            JvmArgsPreset::Custom => vec![],
+        }
+    }
+}
@@ -1,12 +1,13 @@
 use log::debug;
 use maplit::hashmap;
 use shared::paths::{
-    get_authlib_injector_path, get_client_jar_path, get_instance_dir, get_libraries_dir,
-    get_logs_dir, get_natives_dir,
+    get_authlib_injector_path, get_client_jar_path, get_libraries_dir, get_logs_dir,
+    get_natives_dir,
 };
 use shared::version::extra_version_metadata::AuthBackend;
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use tokio::process::{Child, Command as TokioCommand};
 
 use crate::auth::base::get_auth_provider;
@@ -19,45 +20,51 @@ use shared::version::version_metadata;
 
 use super::compat;
 
-const GC_OPTIONS: &[&str] = &[
-    "-XX:+UnlockExperimentalVMOptions",
-    "-XX:+UseG1GC",
-    "-XX:G1NewSizePercent=20",
-    "-XX:G1ReservePercent=20",
-    "-XX:MaxGCPauseMillis=50",
-    "-XX:G1HeapRegionSize=32M",
-    "-XX:+DisableExplicitGC",
-    "-XX:+AlwaysPreTouch",
-    "-XX:+ParallelRefProcEnabled",
-];
-
 #[cfg(target_os = "windows")]
 const PATHSEP: &str = ";";
 #[cfg(not(target_os = "windows"))]
 const PATHSEP: &str = ":";
 
+// single pass, so values containing "${...}" (e.g. paths) are never substituted again
 fn replace_launch_config_variables(
-    argument: String,
+    argument: &str,
     variables: &HashMap<String, String>,
-) -> String {
-    variables.iter().fold(argument, |acc, (k, v)| {
-        acc.replace(&format!("${{{}}}", k), v)
-    })
+) -> Result<String, LaunchError> {
+    let mut result = String::with_capacity(argument.len());
+    let mut rest = argument;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 2..start + len];
+        let value = variables
+            .get(name)
+            .ok_or_else(|| LaunchError::UnresolvedPlaceholder {
+                name: name.to_string(),
+                argument: argument.to_string(),
+            })?;
+        result.push_str(&rest[..start]);
+        result.push_str(value);
+        rest = &rest[start + len + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
 }
 
 fn process_args(
     args: &Vec<version_metadata::VariableArgument>,
     variables: &HashMap<String, String>,
-) -> Vec<String> {
+    enabled_features: &[&str],
+) -> Result<Vec<String>, LaunchError> {
     let mut options = vec![];
     for arg in args {
-        options.extend(
-            arg.get_matching_values(&os::get_os_name(), &os::get_system_arch())
-                .iter()
-                .map(|v| replace_launch_config_variables(v.to_string(), variables)),
-        );
+        for value in
+            arg.get_matching_values(&os::get_os_name(), &os::get_system_arch(), enabled_features)
+        {
+            options.push(replace_launch_config_variables(value, variables)?);
+        }
     }
-    options
+    Ok(options)
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -68,6 +75,19 @@ pub enum LaunchError {
     MissingLibrary(PathBuf),
     #[error("Java path for version {0} not found")]
     JavaPathNotFound(String),
+    #[error("Invalid extra JVM arguments: {0}")]
+    InvalidExtraJvmArgs(shell_words::ParseError),
+    #[error("Invalid wrapper command: {0}")]
+    InvalidWrapperCommand(shell_words::ParseError),
+    #[error("Pre-launch command failed: {0}")]
+    PreLaunchCommandFailed(anyhow::Error),
+    #[error("Unresolved placeholder ${{{name}}} in argument {argument:?}")]
+    UnresolvedPlaceholder { name: String, argument: String },
+}
+
+// one file per instance, several instances can run at the same time
+pub fn get_minecraft_log_path(launcher_dir: &Path, instance_name: &str) -> PathBuf {
+    get_logs_dir(launcher_dir).join(format!("latest_minecraft_launch_{}.log", instance_name))
 }
 
 pub async fn launch(
@@ -82,7 +102,7 @@ pub async fn launch(
     let auth_provider = auth_backend.as_ref().map(|x| get_auth_provider(x));
 
     let launcher_dir = config.get_launcher_dir();
-    let mut minecraft_dir = get_instance_dir(&launcher_dir, version_metadata.get_name());
+    let mut minecraft_dir = config.get_game_dir(version_metadata.get_name());
     let libraries_dir = get_libraries_dir(&launcher_dir);
     let natives_dir = get_natives_dir(&launcher_dir, version_metadata.get_parent_id());
 
@@ -122,7 +142,9 @@ pub async fn launch(
         classpath_str = classpath_str.replace("/", "\\");
     }
 
-    let variables: HashMap<String, String> = hashmap! {
+    let instance_settings = config.get_instance_settings(version_metadata.get_name());
+
+    let mut variables: HashMap<String, String> = hashmap! {
         "natives_directory".to_string() => natives_dir.to_str().unwrap().to_string(),
         "launcher_name".to_string() => "java-minecraft-launcher".to_string(),
         "launcher_version".to_string() => "1.6.84-j".to_string(),
@@ -134,25 +156,43 @@ pub async fn launch(
         "game_directory".to_string() => minecraft_dir.to_str().unwrap().to_string(),
         "assets_root".to_string() => config.get_assets_dir().to_str().unwrap().to_string(),
         "assets_index_name".to_string() => version_metadata.get_asset_index()?.id.to_string(),
+        // legacy versions only, virtual asset directories are not created
+        "game_assets".to_string() => config.get_assets_dir().to_str().unwrap().to_string(),
         "auth_uuid".to_string() => auth_data.user_info.uuid.replace("-", ""),
         "auth_access_token".to_string() => auth_data.access_token.clone(),
+        "auth_session".to_string() => format!(
+            "token:{}:{}",
+            auth_data.access_token,
+            auth_data.user_info.uuid.replace("-", "")
+        ),
         "clientid".to_string() => "".to_string(),
         "auth_xuid".to_string() => "".to_string(),
         "user_type".to_string() => if online { "mojang" } else { "offline" }.to_string(),
         "version_type".to_string() => "release".to_string(),
-        "resolution_width".to_string() => "925".to_string(),
-        "resolution_height".to_string() => "530".to_string(),
         "user_properties".to_string() => "{}".to_string(),
     };
 
+    let mut enabled_features = vec![];
+    if let (Some(width), Some(height)) = (instance_settings.width, instance_settings.height) {
+        enabled_features.push("has_custom_resolution");
+        variables.insert("resolution_width".to_string(), width.to_string());
+        variables.insert("resolution_height".to_string(), height.to_string());
+    }
+    if let Some(server) = &instance_settings.quick_play_server {
+        enabled_features.push("is_quick_play_multiplayer");
+        variables.insert("quickPlayMultiplayer".to_string(), server.clone());
+    }
+
+    let xmx_mb = config.get_xmx_mb(
+        version_metadata.get_name(),
+        version_metadata.get_recommended_xmx(),
+    );
+    let xms_mb = config.get_xms_mb(version_metadata.get_name(), xmx_mb);
     let mut java_options = [
-        GC_OPTIONS
-            .iter()
-            .map(|&s| s.to_string())
-            .collect::<Vec<_>>(),
+        instance_settings.jvm_args_preset.get_gc_options(xmx_mb),
         vec![
-            "-Xms512M".to_string(),
-            format!("-Xmx{}", config.xmx),
+            format!("-Xms{}m", xms_mb),
+            format!("-Xmx{}m", xmx_mb),
             "-Duser.language=en".to_string(),
             "-Dfile.encoding=UTF-8".to_string(),
         ],
@@ -178,8 +218,27 @@ pub async fn launch(
 
     let arguments = version_metadata.get_arguments()?;
 
-    java_options.extend(process_args(&arguments.jvm, &variables));
-    let minecraft_options = process_args(&arguments.game, &variables);
+    java_options.extend(process_args(&arguments.jvm, &variables, &enabled_features)?);
+
+    java_options.extend(
+        shell_words::split(&instance_settings.extra_jvm_args)
+            .map_err(LaunchError::InvalidExtraJvmArgs)?,
+    );
+    let mut minecraft_options = process_args(&arguments.game, &variables, &enabled_features)?;
+    if let (Some(width), Some(height)) = (instance_settings.width, instance_settings.height) {
+        if !minecraft_options.iter().any(|option| option == "--width") {
+            // legacy versions don't have the has_custom_resolution rule
+            minecraft_options.extend([
+                "--width".to_string(),
+                width.to_string(),
+                "--height".to_string(),
+                height.to_string(),
+            ]);
+        }
+    }
+    if instance_settings.fullscreen {
+        minecraft_options.push("--fullscreen".to_string());
+    }
 
     let java_path = config
         .java_paths
@@ -193,20 +252,36 @@ pub async fn launch(
     debug!("Main class: {}", version_metadata.get_main_class());
     debug!("Game arguments: {:?}", minecraft_options);
 
-    let mut cmd = TokioCommand::new(java_path);
+    let wrapper = match instance_settings
+        .wrapper_command
+        .as_deref()
+        .filter(|wrapper| !wrapper.trim().is_empty())
+    {
+        Some(wrapper) => shell_words::split(wrapper).map_err(LaunchError::InvalidWrapperCommand)?,
+        None => vec![],
+    };
+
+    let mut cmd = match wrapper.split_first() {
+        Some((program, args)) => {
+            debug!("Using wrapper {} with arguments {:?}", program, args);
+            let mut cmd = TokioCommand::new(program);
+            cmd.args(args).arg(java_path);
+            cmd
+        }
+        None => TokioCommand::new(java_path),
+    };
     cmd.args(&java_options)
         .arg(version_metadata.get_main_class())
         .args(&minecraft_options)
+        .envs(&instance_settings.env_vars)
         .current_dir(minecraft_dir_short);
 
     // for some reason this is needed on macOS for minecraft process not to crash with
     // "Assertion failed: (count <= len && "snprintf() output has been truncated"), function LOAD_ERROR, file dispatch.c, line 74."
     std::env::remove_var("DYLD_FALLBACK_LIBRARY_PATH");
 
-    let file =
-        std::fs::File::create(get_logs_dir(&launcher_dir).join("latest_minecraft_launch.log"))?;
-    cmd.stdout(file.try_clone()?);
-    cmd.stderr(file);
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
 
     #[cfg(target_os = "windows")]
     {
@@ -216,3 +291,40 @@ pub async fn launch(
 
     Ok(cmd.spawn()?)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_launch_config_variables() {
+        let variables = hashmap! {
+            "game_directory".to_string() => "/games/${version_name}".to_string(),
+            "version_name".to_string() => "1.20.1".to_string(),
+        };
+
+        // substituted values are not scanned for placeholders again
+        assert_eq!(
+            replace_launch_config_variables("--gameDir=${game_directory}", &variables).unwrap(),
+            "--gameDir=/games/${version_name}"
+        );
+        assert_eq!(
+            replace_launch_config_variables("${version_name}-${version_name}", &variables).unwrap(),
+            "1.20.1-1.20.1"
+        );
+
+        match replace_launch_config_variables("--user=${auth_player_name}", &variables) {
+            Err(LaunchError::UnresolvedPlaceholder { name, argument }) => {
+                assert_eq!(name, "auth_player_name");
+                assert_eq!(argument, "--user=${auth_player_name}");
+            }
+            result => panic!("expected an unresolved placeholder, got {:?}", result),
+        }
+
+        // an unterminated placeholder is kept as is
+        assert_eq!(
+            replace_launch_config_variables("${version_name}/${game", &variables).unwrap(),
+            "1.20.1/${game"
+        );
+    }
+}
@@ -1,3 +1,6 @@
 pub mod compat;
+pub mod discord;
+pub mod instance_lock;
+pub mod jvm_presets;
 pub mod launch;
 pub mod update;
@@ -1,12 +1,15 @@
 use futures::StreamExt as _;
-use reqwest::Client;
 use std::process::Command;
 use std::sync::Arc;
+use std::time::Duration;
 use std::{env, fs};
+use tokio_util::sync::CancellationToken;
 
 use crate::config::build_config;
 use crate::lang::LangMessage;
 use crate::utils;
+use shared::adaptive_download::RateLimiter;
+use shared::http;
 use shared::progress::ProgressBar;
 
 #[cfg(target_os = "windows")]
@@ -43,52 +46,149 @@ lazy_static::lazy_static! {
 pub enum UpdateError {
     #[error("Auto update URL not set")]
     AutoUpdateUrlNotSet,
+    #[error("Timed out checking for updates")]
+    VersionCheckTimeout,
+    #[error("Update download cancelled")]
+    DownloadCancelled,
 }
 
+const VERSION_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
 async fn fetch_new_version() -> anyhow::Result<String> {
     if let Some(version_url) = &*VERSION_URL {
-        let client = Client::new();
-        let response = client.get(version_url).send().await?.error_for_status()?;
-        let text = response.text().await?;
-        Ok(text.trim().to_string())
+        let fetch = async {
+            let client = http::client();
+            let response = http::send(http::with_server_headers(
+                client.get(version_url),
+                version_url,
+            ))
+            .await?
+            .error_for_status()?;
+            let text = response.text().await?;
+            anyhow::Ok(text.trim().to_string())
+        };
+        tokio::time::timeout(VERSION_CHECK_TIMEOUT, fetch)
+            .await
+            .map_err(|_| UpdateError::VersionCheckTimeout)?
     } else {
         Err(UpdateError::AutoUpdateUrlNotSet.into())
     }
 }
 
-pub async fn need_update() -> anyhow::Result<bool> {
+// returns the new version if it differs from the current one
+pub async fn need_update() -> anyhow::Result<Option<String>> {
+    if VERSION_URL.is_none() {
+        // self-built launchers have no update server, there is nothing to check
+        return Ok(None);
+    }
     let new_version = fetch_new_version().await?;
     let current_version = build_config::get_version().expect("Version not set");
-    Ok(new_version != current_version)
+    Ok(Some(new_version).filter(|new_version| new_version != &current_version))
+}
+
+const EXPECTED_VERSION_FILENAME: &str = "expected_update_version.txt";
+
+fn get_expected_version_path() -> std::path::PathBuf {
+    utils::get_data_dir().join(EXPECTED_VERSION_FILENAME)
+}
+
+// written before replacing the launcher, so the relaunched process can tell whether the update took effect
+pub fn record_expected_version(version: &str) -> anyhow::Result<()> {
+    fs::write(get_expected_version_path(), version)?;
+    Ok(())
+}
+
+/// Returns the version recorded before the last update, removing it so it is only checked once.
+pub fn take_expected_version() -> Option<String> {
+    let path = get_expected_version_path();
+    let version = fs::read_to_string(&path).ok()?;
+    let _ = fs::remove_file(&path);
+    Some(version.trim().to_string())
+}
+
+// installs under these are owned by a package manager, /usr/local is left to the user
+const PACKAGE_MANAGER_PREFIXES: &[&str] = &["/usr", "/opt", "/nix", "/snap"];
+
+// self_replace can't work for installs owned by a package manager,
+// other read-only locations fail the replacement and are reported separately
+pub fn is_package_managed() -> bool {
+    if env::var_os("FLATPAK_ID").is_some() || env::var_os("SNAP").is_some() {
+        return true;
+    }
+    if cfg!(any(windows, target_os = "macos")) {
+        return false;
+    }
+    let Ok(current_exe) = env::current_exe() else {
+        return false;
+    };
+    !current_exe.starts_with("/usr/local")
+        && PACKAGE_MANAGER_PREFIXES
+            .iter()
+            .any(|prefix| current_exe.starts_with(prefix))
 }
 
 pub async fn download_new_launcher(
     progress_bar: Arc<dyn ProgressBar<LangMessage> + Send + Sync>,
+    max_rate_kbps: Option<u64>,
+    cancellation_token: CancellationToken,
+) -> anyhow::Result<Vec<u8>> {
+    tokio::select! {
+        res = download_new_launcher_inner(progress_bar, max_rate_kbps) => res,
+        _ = cancellation_token.cancelled() => Err(UpdateError::DownloadCancelled.into()),
+    }
+}
+
+async fn download_new_launcher_inner(
+    progress_bar: Arc<dyn ProgressBar<LangMessage> + Send + Sync>,
+    max_rate_kbps: Option<u64>,
 ) -> anyhow::Result<Vec<u8>> {
     if UPDATE_URL.is_none() {
         return Err(UpdateError::AutoUpdateUrlNotSet.into());
     }
     let update_url = UPDATE_URL.as_ref().unwrap();
 
-    let client = Client::new();
-    let response = client.get(update_url).send().await?.error_for_status()?;
+    let client = http::streaming_client_builder()?.build()?;
+    let response = http::send(http::with_server_headers(
+        client.get(update_url),
+        update_url,
+    ))
+    .await?
+    .error_for_status()?;
 
     let total_size = response.content_length().unwrap_or(0);
     progress_bar.set_length(total_size);
     progress_bar.set_message(LangMessage::DownloadingUpdate);
 
+    let rate_limiter = RateLimiter::new(max_rate_kbps);
     let mut bytes = Vec::with_capacity(total_size as usize);
     let mut stream = response.bytes_stream();
     while let Some(chunk) = stream.next().await {
         let chunk = chunk?;
         bytes.extend_from_slice(&chunk);
         progress_bar.inc(chunk.len() as u64);
+        if let Some(rate_limiter) = &rate_limiter {
+            rate_limiter.consume(chunk.len()).await;
+        }
     }
     progress_bar.finish();
 
     Ok(bytes)
 }
 
+// the path that has to be writable for the update to be applied
+pub fn get_launcher_location() -> String {
+    let Ok(current_exe) = env::current_exe() else {
+        return build_config::get_launcher_name();
+    };
+    let location = if cfg!(target_os = "macos") {
+        // Contents/MacOS/<exe> inside the app bundle
+        current_exe.ancestors().nth(3).unwrap_or(&current_exe)
+    } else {
+        &current_exe
+    };
+    location.display().to_string()
+}
+
 #[cfg(target_os = "macos")]
 fn unarchive_tar_gz(archive_data: &[u8], dest_dir: &std::path::Path) -> std::io::Result<()> {
     use flate2::read::GzDecoder;
@@ -106,22 +206,34 @@ fn unarchive_tar_gz(archive_data: &[u8], dest_dir: &std::path::Path) -> std::io:
     Ok(())
 }
 
+// blocking, reports each step through the progress bar
 #[cfg(not(target_os = "macos"))]
-pub fn replace_launcher_and_start(new_binary: &[u8]) -> anyhow::Result<()> {
+pub fn replace_launcher_and_start(
+    new_binary: &[u8],
+    progress_bar: Arc<dyn ProgressBar<LangMessage> + Send + Sync>,
+) -> anyhow::Result<()> {
     let current_exe = env::current_exe()?;
+    progress_bar.set_length(2);
 
+    progress_bar.set_message(LangMessage::ReplacingLauncher);
     let new_exe = utils::get_temp_dir().join("new_launcher");
     fs::write(&new_exe, new_binary)?;
     self_replace::self_replace(&new_exe)?;
     fs::remove_file(&new_exe)?;
+    progress_bar.inc(1);
 
+    progress_bar.set_message(LangMessage::Launching);
     let args: Vec<String> = env::args().collect();
     Command::new(&current_exe).args(&args[1..]).spawn()?;
     std::process::exit(0);
 }
 
+// blocking, reports each step through the progress bar
 #[cfg(target_os = "macos")]
-pub fn replace_launcher_and_start(new_archive: &[u8]) -> anyhow::Result<()> {
+pub fn replace_launcher_and_start(
+    new_archive: &[u8],
+    progress_bar: Arc<dyn ProgressBar<LangMessage> + Send + Sync>,
+) -> anyhow::Result<()> {
     let current_exe = env::current_exe()?;
     let current_dir = current_exe
         .parent()
@@ -144,11 +256,14 @@ pub fn replace_launcher_and_start(new_archive: &[u8]) -> anyhow::Result<()> {
 
     let temp_dir = utils::get_temp_dir().join("launcher_update");
     let backup_dir = utils::get_temp_dir().join("launcher_backup");
+    progress_bar.set_length(4);
 
+    progress_bar.set_message(LangMessage::UnpackingUpdate);
     fs::create_dir_all(&temp_dir)?;
     fs::create_dir_all(&backup_dir)?;
 
     unarchive_tar_gz(new_archive, &temp_dir)?;
+    progress_bar.inc(1);
 
     if backup_dir.exists() {
         fs::remove_dir_all(&backup_dir)?;
@@ -157,10 +272,16 @@ pub fn replace_launcher_and_start(new_archive: &[u8]) -> anyhow::Result<()> {
     // update.app is the name of the app bundle in the tar.gz created in ci
     const UPDATE_APP_NAME: &str = "update.app";
 
+    progress_bar.set_message(LangMessage::ReplacingLauncher);
     fs::rename(bundle_dir, &backup_dir)?;
     fs::rename(temp_dir.join(UPDATE_APP_NAME), bundle_dir)?;
+    progress_bar.inc(1);
+
+    progress_bar.set_message(LangMessage::RemovingOldLauncher);
     fs::remove_dir_all(&backup_dir)?;
+    progress_bar.inc(1);
 
+    progress_bar.set_message(LangMessage::Launching);
     let args: Vec<String> = env::args().collect();
     Command::new(&current_exe).args(&args[1..]).spawn()?;
     std::process::exit(0);
@@ -4,6 +4,7 @@ mod app;
 mod auth;
 mod config;
 mod constants;
+mod crash_report;
 mod lang;
 mod launcher;
 mod update_app;
@@ -12,16 +13,19 @@ mod vendor;
 mod version;
 
 use clap::{Arg, ArgAction, Command};
-use config::runtime_config::{get_logs_path, Config};
-use utils::set_sigint_handler;
+use config::runtime_config::Config;
+use std::path::PathBuf;
+use utils::{get_data_dir, set_sigint_handler};
 
-use shared::logs::setup_logger;
+use shared::logs::setup_rotating_logger;
+use shared::paths::get_logs_dir;
 
 fn main() {
     std::env::set_var("RUST_LIB_BACKTRACE", "1");
 
     set_sigint_handler();
-    setup_logger(&get_logs_path());
+    setup_rotating_logger(&get_logs_dir(&get_data_dir()), "launcher");
+    crash_report::install_panic_hook();
 
     let matches = Command::new("generate-instance")
         .about("Generates instances based on a specification file")
@@ -32,9 +36,18 @@ fn main() {
                 .short('l')
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("data-dir")
+                .help("Store instances and assets in this directory")
+                .long("data-dir")
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
         .get_matches();
 
-    let config = Config::load();
+    let mut config = Config::load();
+    config.apply_data_dir_override(matches.get_one::<PathBuf>("data-dir").cloned());
+    shared::http::set_client_config(config.network.get_client_config());
+    shared::endpoints::set_endpoints(config.network.get_endpoints());
     update_app::app::run_gui(&config);
     app::launcher_app::run_gui(config, matches.get_flag("launch"));
 }
@@ -5,16 +5,24 @@ use eframe::egui;
 use eframe::run_native;
 use log::error;
 use log::info;
+use log::warn;
 use tokio::runtime::Runtime;
+use tokio_util::sync::CancellationToken;
 
+use crate::app::error_label;
 use crate::app::progress_bar::GuiProgressBar;
 use crate::config::build_config;
 use crate::config::runtime_config;
 use crate::lang::Lang;
 use crate::lang::LangMessage;
 use crate::launcher::update::download_new_launcher;
+use crate::launcher::update::get_launcher_location;
+use crate::launcher::update::is_package_managed;
 use crate::launcher::update::need_update;
+use crate::launcher::update::record_expected_version;
 use crate::launcher::update::replace_launcher_and_start;
+use crate::launcher::update::take_expected_version;
+use crate::launcher::update::UpdateError;
 use crate::utils;
 
 use shared::progress::ProgressBar;
@@ -22,26 +30,33 @@ use shared::progress::Unit;
 
 enum UpdateStatus {
     Checking,
-    NeedUpdate,
+    NeedUpdate(String),
+    UpdateNotApplied { expected: String, current: String },
+    PackageManaged(String),
     UpToDate,
-    UpdateError,
+    UpdateError(String),
     UpdateErrorOffline,
 }
 
 enum DownloadStatus {
     NeedDownloading,
     Downloaded(Vec<u8>),
-    DownloadError,
+    Applying,
+    DownloadError(String),
     DownloadErrorOffline,
-    ErrorReadOnly,
+    ErrorReadOnly(String),
 }
 
 pub struct UpdateApp {
     runtime: Runtime,
     lang: Lang,
+    max_rate_kbps: Option<u64>,
     need_update_receiver: mpsc::Receiver<UpdateStatus>,
     new_binary_receiver: Option<mpsc::Receiver<DownloadStatus>>,
+    apply_receiver: Option<mpsc::Receiver<DownloadStatus>>,
     update_progress_bar: Arc<GuiProgressBar>,
+    apply_progress_bar: Arc<GuiProgressBar>,
+    download_cancellation_token: CancellationToken,
     update_status: UpdateStatus,
     download_status: DownloadStatus,
     exit_on_close: bool,
@@ -53,6 +68,11 @@ pub fn run_gui(config: &runtime_config::Config) {
         return;
     }
 
+    if config.work_offline {
+        info!("Working offline, skipping auto-update");
+        return;
+    }
+
     if build_config::get_version().is_none() {
         info!("Version not set, skipping auto-update");
         return;
@@ -65,16 +85,17 @@ pub fn run_gui(config: &runtime_config::Config) {
 
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_inner_size((300.0, 150.0))
+            .with_inner_size((400.0, 150.0))
             .with_icon(utils::get_icon_data()),
         ..Default::default()
     };
 
     let lang = config.lang;
+    let max_rate_kbps = config.max_download_rate_kbps;
     run_native(
         &format!("{} Updater", build_config::get_launcher_name()),
         native_options,
-        Box::new(move |cc| Ok(Box::new(UpdateApp::new(lang, &cc.egui_ctx)))),
+        Box::new(move |cc| Ok(Box::new(UpdateApp::new(lang, max_rate_kbps, &cc.egui_ctx)))),
     )
     .unwrap();
 }
@@ -92,23 +113,27 @@ impl eframe::App for UpdateApp {
 }
 
 impl UpdateApp {
-    fn new(lang: Lang, ctx: &egui::Context) -> Self {
+    fn new(lang: Lang, max_rate_kbps: Option<u64>, ctx: &egui::Context) -> Self {
         let runtime = Runtime::new().unwrap();
 
         let (need_update_sender, need_update_receiver) = mpsc::channel();
-        let ctx_clone = ctx.clone();
-        runtime.spawn(async move {
-            let _ = need_update_sender.send(match need_update().await {
-                Ok(true) => UpdateStatus::NeedUpdate,
-                Ok(false) => UpdateStatus::UpToDate,
-                Err(e) if utils::is_connect_error(&e) => UpdateStatus::UpdateErrorOffline,
-                Err(e) => {
-                    error!("Error checking for updates:\n{:?}", e);
-                    UpdateStatus::UpdateError
-                }
+
+        // a stale or mismatched binary would otherwise prompt for the same update on every start
+        let current_version = build_config::get_version().expect("Version not set");
+        if let Some(expected) =
+            take_expected_version().filter(|expected| expected != &current_version)
+        {
+            warn!(
+                "Update to {} did not take effect, still running {}",
+                expected, current_version
+            );
+            let _ = need_update_sender.send(UpdateStatus::UpdateNotApplied {
+                expected,
+                current: current_version,
             });
-            ctx_clone.request_repaint();
-        });
+        } else {
+            Self::spawn_update_check(&runtime, need_update_sender, ctx);
+        }
 
         let update_progress_bar = Arc::new(GuiProgressBar::new(ctx));
         update_progress_bar.set_unit(Unit {
@@ -119,15 +144,47 @@ impl UpdateApp {
         UpdateApp {
             runtime,
             lang,
+            max_rate_kbps,
             need_update_receiver,
             new_binary_receiver: None,
+            apply_receiver: None,
             update_progress_bar,
+            apply_progress_bar: Arc::new(GuiProgressBar::new(ctx)),
+            download_cancellation_token: CancellationToken::new(),
             update_status: UpdateStatus::Checking,
             download_status: DownloadStatus::NeedDownloading,
             exit_on_close: true,
         }
     }
 
+    fn spawn_update_check(
+        runtime: &Runtime,
+        need_update_sender: mpsc::Sender<UpdateStatus>,
+        ctx: &egui::Context,
+    ) {
+        let ctx_clone = ctx.clone();
+        runtime.spawn(async move {
+            let _ = need_update_sender.send(match need_update().await {
+                Ok(Some(new_version)) if is_package_managed() => {
+                    info!("Installed via a package manager, not replacing the launcher");
+                    UpdateStatus::PackageManaged(new_version)
+                }
+                Ok(Some(new_version)) => UpdateStatus::NeedUpdate(new_version),
+                Ok(None) => UpdateStatus::UpToDate,
+                Err(e) if matches!(e.downcast_ref(), Some(UpdateError::VersionCheckTimeout)) => {
+                    warn!("Update check timed out, proceeding to the launcher");
+                    UpdateStatus::UpToDate
+                }
+                Err(e) if utils::is_connect_error(&e) => UpdateStatus::UpdateErrorOffline,
+                Err(e) => {
+                    error!("Error checking for updates:\n{:?}", e);
+                    UpdateStatus::UpdateError(format!("{:?}", e))
+                }
+            });
+            ctx_clone.request_repaint();
+        });
+    }
+
     fn render_close_button(&mut self, ui: &mut egui::Ui) {
         if ui
             .button(LangMessage::ProceedToLauncher.to_string(self.lang))
@@ -141,16 +198,49 @@ impl UpdateApp {
     fn ui(&mut self, ctx: &egui::Context) {
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.vertical_centered(|ui| {
-                if let DownloadStatus::Downloaded(new_binary) = &self.download_status {
-                    if let Some(e) = replace_launcher_and_start(new_binary).err() {
-                        self.download_status = if utils::is_read_only_error(&e) {
-                            DownloadStatus::ErrorReadOnly
+                if matches!(self.download_status, DownloadStatus::Downloaded(_)) {
+                    let DownloadStatus::Downloaded(new_binary) =
+                        std::mem::replace(&mut self.download_status, DownloadStatus::Applying)
+                    else {
+                        unreachable!();
+                    };
+                    let expected_version = match &self.update_status {
+                        UpdateStatus::NeedUpdate(new_version) => Some(new_version.clone()),
+                        _ => None,
+                    };
+                    let (apply_sender, apply_receiver) = mpsc::channel();
+                    self.apply_receiver = Some(apply_receiver);
+                    let apply_progress_bar = self.apply_progress_bar.clone();
+                    let ctx = ctx.clone();
+                    // unpacking and moving the bundle can take a while, keep the window responsive
+                    self.runtime.spawn_blocking(move || {
+                        if let Some(expected_version) = expected_version {
+                            if let Err(e) = record_expected_version(&expected_version) {
+                                warn!("Failed to record the expected version:\n{:?}", e);
+                            }
+                        }
+                        if let Some(e) =
+                            replace_launcher_and_start(&new_binary, apply_progress_bar).err()
+                        {
+                            // the old launcher is still in place, there is nothing to check on restart
+                            let _ = take_expected_version();
+                            let _ = apply_sender.send(if utils::is_read_only_error(&e) {
+                                warn!("No permission to replace launcher:\n{:?}", e);
+                                DownloadStatus::ErrorReadOnly(get_launcher_location())
+                            } else {
+                                error!("Error replacing launcher:\n{:?}", e);
+                                DownloadStatus::DownloadError(format!("{:?}", e))
+                            });
+                            ctx.request_repaint();
                         } else {
-                            error!("Error replacing launcher:\n{:?}", e);
-                            DownloadStatus::DownloadError
-                        };
-                    } else {
-                        panic!("Launcher should have been replaced and launched");
+                            panic!("Launcher should have been replaced and launched");
+                        }
+                    });
+                }
+
+                if let Some(apply_receiver) = &self.apply_receiver {
+                    if let Ok(download_status) = apply_receiver.try_recv() {
+                        self.download_status = download_status;
                     }
                 }
 
@@ -160,35 +250,55 @@ impl UpdateApp {
                             DownloadStatus::Downloaded(_) => {
                                 ui.label(LangMessage::Launching.to_string(self.lang));
                             }
-                            DownloadStatus::DownloadError => {}
+                            DownloadStatus::Applying => {
+                                panic!("Should not receive Applying");
+                            }
+                            DownloadStatus::DownloadError(_) => {}
                             DownloadStatus::DownloadErrorOffline => {}
                             DownloadStatus::NeedDownloading => {
                                 panic!("Should not receive NeedDownloading");
                             }
-                            DownloadStatus::ErrorReadOnly => {}
+                            DownloadStatus::ErrorReadOnly(_) => {}
                         }
                         self.download_status = download_status;
                     }
                 } else if let Ok(update_status) = self.need_update_receiver.try_recv() {
                     match &update_status {
-                        UpdateStatus::NeedUpdate => {
+                        UpdateStatus::NeedUpdate(_) => {
                             let (new_binary_sender, new_binary_receiver) = mpsc::channel();
                             self.new_binary_receiver = Some(new_binary_receiver);
                             let update_progress_bar = self.update_progress_bar.clone();
+                            let cancellation_token = self.download_cancellation_token.clone();
+                            let max_rate_kbps = self.max_rate_kbps;
                             let ctx = ctx.clone();
                             self.runtime.spawn(async move {
                                 let _ = new_binary_sender.send(
-                                    match download_new_launcher(update_progress_bar).await {
+                                    match download_new_launcher(
+                                        update_progress_bar,
+                                        max_rate_kbps,
+                                        cancellation_token,
+                                    )
+                                    .await
+                                    {
                                         Ok(new_binary) => DownloadStatus::Downloaded(new_binary),
+                                        Err(e)
+                                            if matches!(
+                                                e.downcast_ref(),
+                                                Some(UpdateError::DownloadCancelled)
+                                            ) =>
+                                        {
+                                            info!("Update download skipped");
+                                            return;
+                                        }
                                         Err(e) if utils::is_read_only_error(&e) => {
-                                            DownloadStatus::ErrorReadOnly
+                                            DownloadStatus::ErrorReadOnly(get_launcher_location())
                                         }
                                         Err(e) if utils::is_connect_error(&e) => {
                                             DownloadStatus::DownloadErrorOffline
                                         }
                                         Err(e) => {
                                             error!("Unknown error downloading update:\n{:?}", e);
-                                            DownloadStatus::DownloadError
+                                            DownloadStatus::DownloadError(format!("{:?}", e))
                                         }
                                     },
                                 );
@@ -199,7 +309,9 @@ impl UpdateApp {
                             self.exit_on_close = false;
                             ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
                         }
-                        UpdateStatus::UpdateError => {}
+                        UpdateStatus::PackageManaged(_) => {}
+                        UpdateStatus::UpdateNotApplied { .. } => {}
+                        UpdateStatus::UpdateError(_) => {}
                         UpdateStatus::UpdateErrorOffline => {}
                         UpdateStatus::Checking => {
                             panic!("Should not receive Checking");
@@ -211,13 +323,24 @@ impl UpdateApp {
                 match &self.update_status {
                     UpdateStatus::Checking => {
                         ui.label(LangMessage::CheckingForUpdates.to_string(self.lang));
+                        self.render_close_button(ui);
                     }
-                    UpdateStatus::NeedUpdate => match &self.download_status {
+                    UpdateStatus::NeedUpdate(_) => match &self.download_status {
                         DownloadStatus::NeedDownloading => {
                             self.update_progress_bar.render(ui, self.lang);
+                            // only skips this session, the update is offered again on next start
+                            if ui
+                                .button(LangMessage::SkipUpdateForNow.to_string(self.lang))
+                                .clicked()
+                            {
+                                self.download_cancellation_token.cancel();
+                                self.exit_on_close = false;
+                                ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
+                            }
                         }
-                        DownloadStatus::DownloadError => {
+                        DownloadStatus::DownloadError(e) => {
                             ui.label(LangMessage::ErrorDownloadingUpdate.to_string(self.lang));
+                            error_label::render_copy_button(ui, e, self.lang);
                             self.render_close_button(ui);
                         }
                         DownloadStatus::DownloadErrorOffline => {
@@ -225,14 +348,41 @@ impl UpdateApp {
                             self.render_close_button(ui);
                         }
                         DownloadStatus::Downloaded(_) => {}
-                        DownloadStatus::ErrorReadOnly => {
-                            ui.label(LangMessage::ErrorReadOnly.to_string(self.lang));
+                        DownloadStatus::Applying => {
+                            ui.label(LangMessage::ApplyingUpdate.to_string(self.lang));
+                            self.apply_progress_bar.render(ui, self.lang);
+                        }
+                        DownloadStatus::ErrorReadOnly(path) => {
+                            ui.label(
+                                LangMessage::ErrorReadOnly { path: path.clone() }
+                                    .to_string(self.lang),
+                            );
                             self.render_close_button(ui);
                         }
                     },
+                    UpdateStatus::PackageManaged(new_version) => {
+                        ui.label(
+                            LangMessage::UpdateViaPackageManager {
+                                version: new_version.clone(),
+                            }
+                            .to_string(self.lang),
+                        );
+                        self.render_close_button(ui);
+                    }
                     UpdateStatus::UpToDate => {}
-                    UpdateStatus::UpdateError => {
+                    UpdateStatus::UpdateNotApplied { expected, current } => {
+                        ui.label(
+                            LangMessage::UpdateNotApplied {
+                                expected: expected.clone(),
+                                current: current.clone(),
+                            }
+                            .to_string(self.lang),
+                        );
+                        self.render_close_button(ui);
+                    }
+                    UpdateStatus::UpdateError(e) => {
                         ui.label(LangMessage::ErrorCheckingForUpdates.to_string(self.lang));
+                        error_label::render_copy_button(ui, e, self.lang);
                         self.render_close_button(ui);
                     }
                     UpdateStatus::UpdateErrorOffline => {
@@ -1,10 +1,13 @@
 use log::info;
 use serde::Deserialize;
+use shared::adaptive_download::AdaptiveDownloadError;
+use shared::files::DiskSpaceError;
 
 use crate::config::build_config;
 use crate::constants;
+use std::error::Error as _;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub fn set_sigint_handler() {
     ctrlc::set_handler(move || {
@@ -24,38 +27,113 @@ pub fn get_temp_dir() -> PathBuf {
 }
 
 pub fn is_read_only_error(e: &anyhow::Error) -> bool {
-    if let Some(e) = e.downcast_ref::<std::io::Error>() {
-        return e.kind() == std::io::ErrorKind::PermissionDenied || e.raw_os_error() == Some(18);
+    e.chain()
+        .filter_map(|e| e.downcast_ref::<std::io::Error>())
+        .any(|e| {
+            matches!(
+                e.kind(),
+                std::io::ErrorKind::PermissionDenied | std::io::ErrorKind::ReadOnlyFilesystem
+            ) || e.raw_os_error() == Some(18)
+        })
+}
+
+fn find_reqwest_error(e: &anyhow::Error) -> Option<&reqwest::Error> {
+    e.chain().find_map(|e| e.downcast_ref::<reqwest::Error>())
+}
+
+// rustls handshake failures surface as InvalidData io errors inside the connect error
+fn is_tls_error(e: &reqwest::Error) -> bool {
+    let mut source = e.source();
+    while let Some(err) = source {
+        if let Some(io_err) = err.downcast_ref::<std::io::Error>() {
+            if io_err.kind() == std::io::ErrorKind::InvalidData {
+                return true;
+            }
+        }
+        source = err.source();
     }
     false
 }
 
+pub fn get_disk_space_error(e: &anyhow::Error) -> Option<(u64, u64)> {
+    e.chain().find_map(|e| {
+        e.downcast_ref::<DiskSpaceError>().map(|e| match e {
+            DiskSpaceError::NotEnoughSpace {
+                required,
+                available,
+                ..
+            } => (*required, *available),
+        })
+    })
+}
+
 pub fn is_connect_error(e: &anyhow::Error) -> bool {
-    if let Some(e) = e.downcast_ref::<reqwest::Error>() {
-        return e.is_connect() || e.status().is_some_and(|s| s.as_u16() == 523);
-        // 523 = Cloudflare Origin is Unreachable
+    if e.chain().any(|e| {
+        matches!(
+            e.downcast_ref::<AdaptiveDownloadError>(),
+            Some(AdaptiveDownloadError::ConnectionTimeout)
+        )
+    }) {
+        return true;
     }
-    false
+    find_reqwest_error(e).is_some_and(|e| {
+        e.status().is_none() && (e.is_timeout() || (e.is_connect() && !is_tls_error(e)))
+    })
 }
 
-pub fn validate_xmx(xmx: &str) -> bool {
-    let xmx = xmx.trim();
-    if xmx.is_empty() {
-        return false;
+pub fn get_http_status(e: &anyhow::Error) -> Option<reqwest::StatusCode> {
+    find_reqwest_error(e).and_then(|e| e.status())
+}
+
+pub fn parse_xmx_mb(xmx: &str) -> Option<u32> {
+    let xmx = xmx.trim().to_lowercase();
+    let mb = if let Some(gb) = xmx.strip_suffix('g') {
+        gb.trim().parse::<u32>().ok()?.checked_mul(1024)?
+    } else if let Some(mb) = xmx.strip_suffix('m') {
+        mb.trim().parse::<u32>().ok()?
+    } else {
+        xmx.parse::<u32>().ok()?
+    };
+
+    if (constants::MIN_JAVA_MB..=constants::MAX_JAVA_MB).contains(&mb) {
+        Some(mb)
+    } else {
+        None
     }
+}
 
-    let xmx = xmx.to_uppercase();
-    if xmx.ends_with("M") {
-        if let Ok(mb) = xmx[..xmx.len() - 1].parse::<u32>() {
-            return (constants::MIN_JAVA_MB..=constants::MAX_JAVA_MB).contains(&mb);
-        }
-    } else if xmx.ends_with("G") {
-        if let Ok(gb) = xmx[..xmx.len() - 1].parse::<u32>() {
-            return (constants::MIN_JAVA_MB..=constants::MAX_JAVA_MB).contains(&(gb * 1024));
-        }
+pub fn get_total_memory_mb() -> Option<u32> {
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+    match system.total_memory() / (1024 * 1024) {
+        0 => None,
+        mb => u32::try_from(mb).ok(),
     }
+}
 
-    false
+pub fn get_unix_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn hide_window(ctx: &egui::Context) {
+    if cfg!(windows) {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Decorations(false));
+        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize([0.0, 0.0].into()));
+    } else {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+    }
+}
+
+pub fn show_window(ctx: &egui::Context) {
+    if cfg!(windows) {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Decorations(true));
+        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize([670.0, 450.0].into()));
+    } else {
+        ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+    }
 }
 
 pub fn get_icon_data() -> egui::IconData {
@@ -97,6 +175,17 @@ pub fn get_data_dir() -> PathBuf {
     data_dir
 }
 
+// never creates the directory, it may be a mount point of a drive that isn't connected
+pub fn is_dir_writable(dir: &Path) -> bool {
+    if !dir.is_dir() {
+        return false;
+    }
+    let test_path = dir.join(".write_test");
+    let writable = fs::write(&test_path, b"").is_ok();
+    let _ = fs::remove_file(&test_path);
+    writable
+}
+
 pub fn is_valid_minecraft_username(username: &str) -> bool {
     if username.len() < 3 || username.len() > 16 {
         return false;
@@ -0,0 +1,106 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use log::info;
+use shared::files;
+use shared::version::asset_metadata::AssetsMetadata;
+use shared::version::version_manifest::VersionInfo;
+
+use super::complete_version_metadata::CompleteVersionMetadata;
+
+#[derive(thiserror::Error, Debug)]
+pub enum AssetsGcError {
+    #[error("A custom assets directory may be shared with other launchers, not cleaning it")]
+    CustomAssetsDir,
+}
+
+pub struct UnreferencedAssets {
+    pub paths: Vec<PathBuf>,
+    pub size: u64,
+}
+
+async fn get_referenced_hashes(
+    version_infos: &[VersionInfo],
+    launcher_dir: &Path,
+    assets_dir: &Path,
+) -> anyhow::Result<HashSet<String>> {
+    let mut hashes = HashSet::new();
+    for version_info in version_infos {
+        // a skipped instance would have its assets deleted, so any failure aborts the scan
+        let version_metadata = CompleteVersionMetadata::read_local(version_info, launcher_dir)
+            .await
+            .with_context(|| format!("Failed to read metadata of {}", version_info.get_name()))?;
+        let asset_index = version_metadata.get_asset_index()?;
+        let asset_metadata = AssetsMetadata::read_local(&asset_index.id, assets_dir).await?;
+        hashes.extend(
+            asset_metadata
+                .objects
+                .into_values()
+                .map(|object| object.hash),
+        );
+    }
+    Ok(hashes)
+}
+
+// asset objects not used by any of the given instances
+pub async fn find_unreferenced_assets(
+    version_infos: &[VersionInfo],
+    launcher_dir: &Path,
+    assets_dir: &Path,
+    custom_assets_dir: bool,
+) -> anyhow::Result<UnreferencedAssets> {
+    if custom_assets_dir {
+        return Err(AssetsGcError::CustomAssetsDir.into());
+    }
+
+    let referenced = get_referenced_hashes(version_infos, launcher_dir, assets_dir).await?;
+    let objects_dir = assets_dir.join("objects");
+    let mut paths = vec![];
+    let mut size = 0;
+    for path in files::get_files_in_dir(&objects_dir)? {
+        let Some(hash) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !referenced.contains(hash) {
+            size += tokio::fs::metadata(&path)
+                .await
+                .map(|m| m.len())
+                .unwrap_or(0);
+            paths.push(path);
+        }
+    }
+    Ok(UnreferencedAssets { paths, size })
+}
+
+/// Deletes the found assets that are still unreferenced, instances may have changed since they were found.
+pub async fn delete_assets(
+    assets: &UnreferencedAssets,
+    version_infos: &[VersionInfo],
+    launcher_dir: &Path,
+    assets_dir: &Path,
+) -> anyhow::Result<UnreferencedAssets> {
+    let referenced = get_referenced_hashes(version_infos, launcher_dir, assets_dir).await?;
+    let mut paths = vec![];
+    let mut size = 0;
+    for path in &assets.paths {
+        let Some(hash) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if referenced.contains(hash) {
+            continue;
+        }
+        size += tokio::fs::metadata(path)
+            .await
+            .map(|m| m.len())
+            .unwrap_or(0);
+        tokio::fs::remove_file(path).await?;
+        paths.push(path.clone());
+    }
+    info!(
+        "Deleted {} unreferenced assets, freed {} bytes",
+        paths.len(),
+        size
+    );
+    Ok(UnreferencedAssets { paths, size })
+}
@@ -0,0 +1,49 @@
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use shared::files;
+use shared::java;
+use shared::paths::{
+    get_hash_cache_path, get_manifest_cache_path, get_manifest_cache_validators_path,
+};
+
+// everything here is rebuilt or refetched on demand, instances, accounts and settings are never touched
+fn get_cache_paths(launcher_dir: &Path) -> Vec<PathBuf> {
+    vec![
+        get_hash_cache_path(launcher_dir),
+        get_manifest_cache_path(launcher_dir),
+        get_manifest_cache_validators_path(launcher_dir),
+        java::get_temp_dir(),
+    ]
+}
+
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+/// Deletes the launcher caches, returns the number of freed bytes.
+pub fn clear_caches(launcher_dir: &Path) -> u64 {
+    let mut freed = 0;
+    for path in get_cache_paths(launcher_dir) {
+        let size = if path.is_dir() {
+            files::get_dir_size(&path)
+        } else {
+            path.metadata().map(|x| x.len()).unwrap_or(0)
+        };
+        match remove_path(&path) {
+            Ok(()) => {
+                info!("Cleared cache {:?} ({} bytes)", path, size);
+                freed += size;
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to clear cache {:?}:\n{:?}", path, e),
+        }
+    }
+    info!("Cleared caches, freed {} bytes", freed);
+    freed
+}
@@ -1,7 +1,7 @@
 use shared::{
-    adaptive_download::download_files,
+    adaptive_download::{download_files_with_options, DownloadOptions},
     files::{self, CheckEntry},
-    paths::{get_client_jar_path, get_versions_dir, get_versions_extra_dir},
+    paths::{check_safe_name, get_client_jar_path, get_versions_dir, get_versions_extra_dir},
     progress,
     version::{
         extra_version_metadata::{AuthBackend, ExtraVersionMetadata},
@@ -13,6 +13,7 @@ use std::collections::HashMap;
 use std::path::Path;
 
 use super::{os, overrides::with_overrides};
+use crate::config::build_config;
 
 pub struct CompleteVersionMetadata {
     version_name: String,
@@ -32,7 +33,17 @@ pub enum VersionMetadataError {
 }
 
 impl CompleteVersionMetadata {
+    // names and ids from the manifest become file and directory names
+    fn check_names(version_info: &VersionInfo) -> anyhow::Result<()> {
+        check_safe_name(&version_info.get_name())?;
+        for metadata_info in version_info.get_metadata_info() {
+            check_safe_name(&metadata_info.id)?;
+        }
+        Ok(())
+    }
+
     pub async fn read_local(version_info: &VersionInfo, data_dir: &Path) -> anyhow::Result<Self> {
+        Self::check_names(version_info)?;
         let versions_dir = get_versions_dir(data_dir);
 
         let mut base = vec![];
@@ -63,6 +74,7 @@ impl CompleteVersionMetadata {
         version_info: &VersionInfo,
         data_dir: &Path,
     ) -> anyhow::Result<Self> {
+        Self::check_names(version_info)?;
         let versions_dir = get_versions_dir(data_dir);
         let versions_extra_dir = get_versions_extra_dir(data_dir);
 
@@ -80,8 +92,18 @@ impl CompleteVersionMetadata {
         }
 
         let download_entries =
-            files::get_download_entries(check_entries, progress::no_progress_bar()).await?;
-        download_files(download_entries, progress::no_progress_bar()).await?;
+            files::get_download_entries(check_entries.clone(), progress::no_progress_bar()).await?;
+        let download_options = DownloadOptions {
+            mirrors: build_config::get_download_mirrors(),
+            ..Default::default()
+        };
+        download_files_with_options(
+            download_entries,
+            progress::no_progress_bar(),
+            &download_options,
+        )
+        .await?;
+        files::verify_check_entries(&check_entries).await?;
 
         Self::read_local(version_info, data_dir).await
     }
@@ -121,10 +143,41 @@ impl CompleteVersionMetadata {
         Err(VersionMetadataError::MissingClientDownload.into())
     }
 
+    // only counts the sizes listed in the metadata, instance files have no size information
+    pub fn get_size_estimate(&self) -> u64 {
+        let libraries_size: u64 = self
+            .get_libraries_with_overrides()
+            .iter()
+            .flat_map(|library| {
+                library.get_check_entries(
+                    Path::new(""),
+                    Some((&os::get_os_name(), &os::get_system_arch())),
+                )
+            })
+            .filter_map(|entry| entry.size)
+            .sum();
+        let client_size = self.base[0]
+            .downloads
+            .as_ref()
+            .and_then(|downloads| downloads.client.as_ref())
+            .and_then(|client| client.size)
+            .unwrap_or(0);
+        let assets_size = self
+            .get_asset_index()
+            .ok()
+            .and_then(|asset_index| asset_index.total_size)
+            .unwrap_or(0);
+        libraries_size + client_size + assets_size
+    }
+
     pub fn get_auth_backend(&self) -> Option<&AuthBackend> {
         self.extra.as_ref()?.auth_backend.as_ref()
     }
 
+    pub fn get_recommended_xmx(&self) -> Option<&str> {
+        self.extra.as_ref()?.recommended_xmx.as_deref()
+    }
+
     pub fn get_libraries_with_overrides(&self) -> Vec<Library> {
         let all_libraries = self
             .base
@@ -0,0 +1,87 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::warn;
+
+const IMPORTED_PATHS: &[&str] = &["saves", "resourcepacks", "options.txt"];
+
+#[derive(thiserror::Error, Debug)]
+pub enum ImportError {
+    #[error("No saves, resource packs or options found in {0}")]
+    NothingToImport(PathBuf),
+    #[error("{0} is the instance's game directory or overlaps with it")]
+    OverlappingDirs(PathBuf),
+}
+
+pub fn get_default_minecraft_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        dirs::data_dir().map(|dir| dir.join(".minecraft"))
+    } else if cfg!(target_os = "macos") {
+        dirs::data_dir().map(|dir| dir.join("minecraft"))
+    } else {
+        dirs::home_dir().map(|dir| dir.join(".minecraft"))
+    }
+}
+
+fn get_importable_paths(source_dir: &Path) -> Vec<String> {
+    IMPORTED_PATHS
+        .iter()
+        .filter(|path| source_dir.join(path).exists())
+        .map(|path| path.to_string())
+        .collect()
+}
+
+// importable paths that already exist in the instance and would be overwritten
+pub fn get_conflicting_paths(source_dir: &Path, instance_dir: &Path) -> Vec<String> {
+    get_importable_paths(source_dir)
+        .into_iter()
+        .filter(|path| instance_dir.join(path).exists())
+        .collect()
+}
+
+// copying a directory into itself would truncate every file it overwrites
+pub fn dirs_overlap(source_dir: &Path, instance_dir: &Path) -> bool {
+    let (Ok(source_dir), Ok(instance_dir)) =
+        (fs::canonicalize(source_dir), fs::canonicalize(instance_dir))
+    else {
+        return false;
+    };
+    source_dir.starts_with(&instance_dir) || instance_dir.starts_with(&source_dir)
+}
+
+fn copy_recursive(src: &Path, dest: &Path) -> anyhow::Result<()> {
+    let file_type = fs::symlink_metadata(src)?.file_type();
+    if file_type.is_symlink() {
+        // following links could leave the source tree or loop forever
+        warn!("Skipping symlink {:?} during import", src);
+    } else if file_type.is_dir() {
+        fs::create_dir_all(dest)?;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+    } else {
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(src, dest)?;
+    }
+    Ok(())
+}
+
+/// Copies saves, resource packs and options from an existing game directory into the instance.
+/// Returns the imported paths relative to the instance directory.
+pub fn import_minecraft_dir(source_dir: &Path, instance_dir: &Path) -> anyhow::Result<Vec<String>> {
+    fs::create_dir_all(instance_dir)?;
+    if dirs_overlap(source_dir, instance_dir) {
+        return Err(ImportError::OverlappingDirs(source_dir.to_path_buf()).into());
+    }
+    let paths = get_importable_paths(source_dir);
+    if paths.is_empty() {
+        return Err(ImportError::NothingToImport(source_dir.to_path_buf()).into());
+    }
+    for path in &paths {
+        copy_recursive(&source_dir.join(path), &instance_dir.join(path))?;
+    }
+    Ok(paths)
+}
@@ -3,7 +3,7 @@ use std::{collections::HashSet, path::Path};
 use log::{error, warn};
 use serde::{Deserialize, Serialize};
 use shared::{
-    paths::{get_instance_dir, get_local_instances_path},
+    paths::{get_instance_dir, get_local_instances_path, is_safe_name},
     version::version_manifest::{VersionInfo, VersionManifest},
 };
 use tokio::task;
@@ -53,7 +53,17 @@ impl InstanceStorage {
     }
 
     pub fn set_remote_manifest(&mut self, manifest: Option<VersionManifest>) {
-        self.remote_manifest = manifest;
+        self.remote_manifest = manifest.map(|mut manifest| {
+            manifest.versions.retain(|version_info| {
+                let name = version_info.get_name();
+                let safe = is_safe_name(&name);
+                if !safe {
+                    error!("Ignoring version with unsafe name: {:?}", name);
+                }
+                safe
+            });
+            manifest
+        });
     }
 
     fn get_remote_versions(&self) -> Vec<&VersionInfo> {
@@ -91,12 +101,33 @@ impl InstanceStorage {
         self.safe_save(config).await;
     }
 
-    pub fn get_instance(&self, version_name: &str) -> Option<LocalInstance> {
+    pub fn get_local_version_infos(&self) -> Vec<VersionInfo> {
+        self.instances
+            .iter()
+            .map(|instance| instance.version_info.clone())
+            .collect()
+    }
+
+    pub fn get_local_version_info(&self, version_name: &str) -> Option<&VersionInfo> {
+        self.instances
+            .iter()
+            .find(|instance| instance.version_info.get_name() == version_name)
+            .map(|instance| &instance.version_info)
+    }
+
+    pub fn get_instance(&self, config: &Config, version_name: &str) -> Option<LocalInstance> {
         let local_instance = self
             .instances
             .iter()
             .find(|instance| instance.version_info.get_name() == version_name)
             .cloned();
+        if let Some(local_instance) = &local_instance {
+            if local_instance.status == InstanceStatus::UpToDate
+                && config.get_instance_settings(version_name).pin_version
+            {
+                return Some(local_instance.clone());
+            }
+        }
         let remote_instance = self
             .get_remote_versions()
             .into_iter()
@@ -121,10 +152,13 @@ impl InstanceStorage {
     }
 
     pub async fn mark_downloaded(&mut self, config: &Config, version_name: &str) {
-        let remote_versions = self.get_remote_versions();
-        let remote_version = remote_versions
+        let pinned = config.get_instance_settings(version_name).pin_version
+            && self.get_local_version_info(version_name).is_some();
+        let remote_version = self
+            .get_remote_versions()
             .into_iter()
             .find(|v| v.get_name() == version_name)
+            .filter(|_| !pinned)
             .cloned();
 
         if let Some(remote_version) = remote_version {
@@ -147,7 +181,6 @@ impl InstanceStorage {
                 "Tried to mark non-existent version as downloaded: {}",
                 version_name
             );
-            return;
         }
     }
 
@@ -184,11 +217,10 @@ impl InstanceStorage {
             .instances
             .iter()
             .find(|instance| instance.version_info.get_name() == version_name);
-        if let Some(instance) = instance {
-            if instance.status != InstanceStatus::Outdated {
-                self.remove_instance_files(&launcher_dir, version_name)
-                    .await;
-            }
+        if instance.is_some() {
+            // partially synced instances have files to remove too
+            self.remove_instance_files(&launcher_dir, version_name)
+                .await;
             self.instances
                 .retain(|instance| instance.version_info.get_name() != version_name);
             self.safe_save(config).await;
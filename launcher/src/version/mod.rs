@@ -1,4 +1,7 @@
+pub mod assets_gc;
+pub mod caches;
 pub mod complete_version_metadata;
+pub mod import;
 pub mod instance_storage;
 pub mod os;
 pub mod overrides;
@@ -4,15 +4,16 @@ use std::sync::Arc;
 
 use log::{debug, info, warn};
 use rand::seq::SliceRandom as _;
-use shared::adaptive_download::download_files;
+use shared::adaptive_download::{download_files_with_options, fetch_with_mirrors, DownloadOptions};
 use shared::paths::{
-    get_authlib_injector_path, get_instance_dir, get_libraries_dir, get_natives_dir,
+    get_authlib_injector_path, get_hash_cache_path, get_libraries_dir, get_natives_dir,
 };
 use shared::version::asset_metadata::AssetsMetadata;
 use std::fs;
 use zip::ZipArchive;
 
-use shared::files::{self, CheckEntry};
+use shared::files::{self, CheckEntry, DownloadEntry, HashCache};
+use shared::http;
 use shared::progress::ProgressBar;
 use shared::version::extra_version_metadata::{AuthBackend, ExtraVersionMetadata};
 use shared::version::version_metadata;
@@ -22,12 +23,18 @@ use crate::lang::LangMessage;
 use super::complete_version_metadata::CompleteVersionMetadata;
 use super::os;
 
-fn get_objects_entries(
+fn is_preserved(path: &Path, preserved_paths: &[String], instance_dir: &Path) -> bool {
+    preserved_paths
+        .iter()
+        .any(|preserved| path.starts_with(instance_dir.join(preserved)))
+}
+
+fn get_overwrite_sets(
     extra_version_metadata: &ExtraVersionMetadata,
     force_overwrite: bool,
+    preserved_paths: &[String],
     instance_dir: &Path,
-) -> anyhow::Result<Vec<CheckEntry>> {
-    let objects = &extra_version_metadata.objects;
+) -> (HashSet<PathBuf>, HashSet<PathBuf>) {
     let include = &extra_version_metadata.include;
     let include_no_overwrite = &extra_version_metadata.include_no_overwrite;
 
@@ -48,33 +55,74 @@ fn get_objects_entries(
         to_overwrite.extend(no_overwrite_iter);
     }
 
+    // files behind symlinks may be shared with other instances, never delete or overwrite them
+    to_overwrite.retain(|x| !files::is_under_symlink(x, instance_dir));
+    // same for files the user imported into the instance
+    to_overwrite.retain(|x| !is_preserved(x, preserved_paths, instance_dir));
+
     // Remove files that are in both no_overwrite and overwrite
     // e.g. config folder is in no_overwrite but config/<filename>.json is in overwrite
     no_overwrite.retain(|x| !to_overwrite.contains(x));
 
-    // delete extra to_overwrite files
-    let objects_hashset: HashSet<PathBuf> =
-        objects.iter().map(|x| instance_dir.join(&x.path)).collect();
-    let _ = to_overwrite
+    (to_overwrite, no_overwrite)
+}
+
+fn get_extra_files(
+    extra_version_metadata: &ExtraVersionMetadata,
+    to_overwrite: &HashSet<PathBuf>,
+    instance_dir: &Path,
+) -> Vec<PathBuf> {
+    let objects_hashset: HashSet<PathBuf> = extra_version_metadata
+        .objects
         .iter()
-        .map(|x| {
-            if !objects_hashset.contains(x) {
-                fs::remove_file(x).unwrap();
-            }
-        })
-        .collect::<Vec<()>>();
+        .map(|x| instance_dir.join(&x.path))
+        .collect();
+    let mut extra_files: Vec<PathBuf> = to_overwrite
+        .iter()
+        .filter(|x| !objects_hashset.contains(*x))
+        .cloned()
+        .collect();
+    extra_files.sort();
+    extra_files
+}
+
+fn get_objects_entries(
+    extra_version_metadata: &ExtraVersionMetadata,
+    force_overwrite: bool,
+    delete_extra: bool,
+    preserved_paths: &[String],
+    instance_dir: &Path,
+) -> anyhow::Result<Vec<CheckEntry>> {
+    let objects = &extra_version_metadata.objects;
+    let (to_overwrite, no_overwrite) = get_overwrite_sets(
+        extra_version_metadata,
+        force_overwrite,
+        preserved_paths,
+        instance_dir,
+    );
+
+    if delete_extra {
+        for path in get_extra_files(extra_version_metadata, &to_overwrite, instance_dir) {
+            fs::remove_file(path)?;
+        }
+    }
 
     let mut download_entries = vec![];
     for object in objects.iter() {
         let object_path = instance_dir.join(&object.path);
 
-        if no_overwrite.contains(&object_path) {
+        // a missing file under a symlinked directory would still be written through the link
+        if no_overwrite.contains(&object_path)
+            || is_preserved(&object_path, preserved_paths, instance_dir)
+            || files::is_under_symlink(&object_path, instance_dir)
+        {
             continue;
         }
         download_entries.push(CheckEntry {
             url: object.url.clone(),
             remote_sha1: Some(object.sha1.clone()),
             path: object_path,
+            size: None,
         });
     }
 
@@ -83,16 +131,25 @@ fn get_objects_entries(
 
 async fn fetch_hashes(
     sha1_urls: HashMap<PathBuf, String>,
+    mirrors: &[String],
 ) -> anyhow::Result<HashMap<PathBuf, String>> {
-    let client = reqwest::Client::new();
+    let client = http::client();
 
     let mut futures = vec![];
     for (path, url) in sha1_urls {
         let client = client.clone();
         let future = async move {
-            let response = client.get(&url).send().await?.error_for_status()?;
-            let bytes = response.bytes().await?;
-            let sha1 = String::from_utf8(bytes.to_vec())?;
+            let sha1 = fetch_with_mirrors(&url, mirrors, |url| {
+                let client = client.clone();
+                async move {
+                    let response = http::send(http::with_server_headers(client.get(&url), &url))
+                        .await?
+                        .error_for_status()?;
+                    let bytes = response.bytes().await?;
+                    Ok(String::from_utf8(bytes.to_vec())?)
+                }
+            })
+            .await?;
             Ok((path, sha1))
         };
         futures.push(future);
@@ -126,6 +183,7 @@ async fn fetch_hashes(
 async fn get_libraries_entries(
     libraries: &Vec<version_metadata::Library>,
     libraries_dir: &Path,
+    mirrors: &[String],
 ) -> anyhow::Result<Vec<CheckEntry>> {
     let mut sha1_urls = HashMap::<PathBuf, String>::new();
     let mut check_download_entries: Vec<CheckEntry> = Vec::new();
@@ -151,7 +209,7 @@ async fn get_libraries_entries(
         }
     }
 
-    let missing_hashes = fetch_hashes(sha1_urls).await?;
+    let missing_hashes = fetch_hashes(sha1_urls, mirrors).await?;
 
     let check_download_entries: Vec<_> = check_download_entries
         .into_iter()
@@ -180,20 +238,27 @@ fn extract_natives(
         if let Some(natives_path) =
             library.get_os_native_path(libraries_dir, &os::get_os_name(), &os::get_system_arch())
         {
-            extract_files(&natives_path, natives_dir)?;
+            extract_files(&natives_path, natives_dir, library.extract.as_ref())?;
         }
     }
 
     Ok(())
 }
 
-fn extract_files(src: &Path, dest: &Path) -> anyhow::Result<()> {
+fn extract_files(
+    src: &Path,
+    dest: &Path,
+    extract_rules: Option<&version_metadata::ExtractRules>,
+) -> anyhow::Result<()> {
     let file = fs::File::open(src)?;
     let mut zip = ZipArchive::new(file)?;
 
     for i in 0..zip.len() {
         let mut entry = zip.by_index(i)?;
         if let Some(file_path) = entry.enclosed_name() {
+            if extract_rules.is_some_and(|rules| rules.is_excluded(entry.name())) {
+                continue;
+            }
             let output_path = dest.join(file_path);
             if entry.is_file() {
                 if let Some(parent) = output_path.parent() {
@@ -227,21 +292,57 @@ fn get_authlib_injector_entry(
         url: AUTHLIB_INJECTOR_URL.to_string(),
         remote_sha1: Some(AUTHLIB_INJECTOR_SHA1.to_string()),
         path: get_authlib_injector_path(launcher_dir),
+        size: None,
     })
 }
 
+/// The directory the game runs in and the paths in it that syncing never overwrites or deletes.
+pub struct GameDir<'a> {
+    pub path: &'a Path,
+    pub preserved_paths: &'a [String],
+}
+
+// files under include that are not part of the pack and would be removed by a sync
+pub fn get_files_to_delete(
+    version_metadata: &CompleteVersionMetadata,
+    force_overwrite: bool,
+    game_dir: &GameDir,
+) -> Vec<PathBuf> {
+    let Some(extra) = version_metadata.get_extra() else {
+        return vec![];
+    };
+    let (to_overwrite, _) = get_overwrite_sets(
+        extra,
+        force_overwrite,
+        game_dir.preserved_paths,
+        game_dir.path,
+    );
+    get_extra_files(extra, &to_overwrite, game_dir.path)
+}
+
+// none of the pack files are present yet, so anything under include was put there by the user
+pub fn is_first_sync(version_metadata: &CompleteVersionMetadata, instance_dir: &Path) -> bool {
+    let Some(extra) = version_metadata.get_extra() else {
+        return false;
+    };
+    !extra
+        .objects
+        .iter()
+        .any(|object| instance_dir.join(&object.path).exists())
+}
+
 pub async fn sync_instance(
     version_metadata: &CompleteVersionMetadata,
     force_overwrite: bool,
+    game_dir: &GameDir<'_>,
     launcher_dir: &Path,
     assets_dir: &Path,
+    download_options: &DownloadOptions,
     progress_bar: Arc<dyn ProgressBar<LangMessage> + Send + Sync>,
 ) -> anyhow::Result<()> {
-    let version_name = version_metadata.get_name();
-
     let libraries_dir = get_libraries_dir(launcher_dir);
     let natives_dir = get_natives_dir(launcher_dir, version_metadata.get_parent_id());
-    let instance_dir = get_instance_dir(launcher_dir, version_name);
+    let instance_dir = game_dir.path;
 
     let mut check_entries = vec![];
 
@@ -249,10 +350,18 @@ pub async fn sync_instance(
 
     let mut libraries = version_metadata.get_libraries_with_overrides();
     libraries.extend(version_metadata.get_extra_forge_libs());
-    check_entries.extend(get_libraries_entries(&libraries, &libraries_dir).await?);
+    check_entries.extend(
+        get_libraries_entries(&libraries, &libraries_dir, &download_options.mirrors).await?,
+    );
 
     if let Some(extra) = version_metadata.get_extra() {
-        check_entries.extend(get_objects_entries(extra, force_overwrite, &instance_dir)?);
+        check_entries.extend(get_objects_entries(
+            extra,
+            force_overwrite,
+            true,
+            game_dir.preserved_paths,
+            instance_dir,
+        )?);
     }
 
     if let Some(authlib_injector) = get_authlib_injector_entry(version_metadata, launcher_dir) {
@@ -260,21 +369,60 @@ pub async fn sync_instance(
     }
 
     let asset_index = version_metadata.get_asset_index()?;
-    let asset_metadata = AssetsMetadata::read_or_download(asset_index, assets_dir).await?;
+    let asset_metadata =
+        AssetsMetadata::read_or_download(asset_index, assets_dir, download_options).await?;
 
     check_entries.extend(
         asset_metadata.get_check_entries(assets_dir, version_metadata.get_resources_url_base())?,
     );
 
     info!("Got {} check download entries", check_entries.len());
+    let total_entries = check_entries.len();
     progress_bar.set_message(LangMessage::CheckingFiles);
-    let mut download_entries =
-        files::get_download_entries(check_entries, progress_bar.clone()).await?;
+    // force overwrite rehashes everything in case the cache itself is stale
+    let hash_cache_path = get_hash_cache_path(launcher_dir);
+    let mut hash_cache = if force_overwrite {
+        HashCache::default()
+    } else {
+        HashCache::load(&hash_cache_path).await
+    };
+    let mut download_entries = files::get_download_entries_cached(
+        check_entries,
+        Some(&mut hash_cache),
+        progress_bar.clone(),
+    )
+    .await?;
+    if let Err(e) = hash_cache.save(&hash_cache_path).await {
+        warn!("Failed to save hash cache:\n{:?}", e);
+    }
 
     let rng = &mut rand::rngs::OsRng;
     download_entries.shuffle(rng);
 
-    info!("Got {} download entries", download_entries.len());
+    let download_size: u64 = download_entries.iter().filter_map(|x| x.size).sum();
+    info!(
+        "Got {} download entries ({} already present, {} bytes known)",
+        download_entries.len(),
+        total_entries - download_entries.len(),
+        download_size
+    );
+    // the instance and the assets can be on other drives than the launcher data
+    let mut instance_size = 0;
+    let mut assets_size = 0;
+    let mut launcher_size = 0;
+    for entry in &download_entries {
+        let size = entry.size.unwrap_or(0);
+        if entry.path.starts_with(instance_dir) {
+            instance_size += size;
+        } else if entry.path.starts_with(assets_dir) {
+            assets_size += size;
+        } else {
+            launcher_size += size;
+        }
+    }
+    files::check_free_space(instance_dir, instance_size)?;
+    files::check_free_space(assets_dir, assets_size)?;
+    files::check_free_space(launcher_dir, launcher_size)?;
 
     let paths = download_entries
         .iter()
@@ -282,10 +430,206 @@ pub async fn sync_instance(
         .collect::<Vec<_>>();
     debug!("Paths to download: {:?}", paths);
 
+    progress_bar.set_message(LangMessage::DownloadingFilesSummary {
+        already_have: total_entries - download_entries.len(),
+        to_download: download_entries.len(),
+        size: download_size,
+    });
+    download_files_with_options(download_entries, progress_bar, download_options).await?;
+
+    extract_natives(&libraries, &libraries_dir, &natives_dir)?;
+
+    Ok(())
+}
+
+pub struct VerifyReport {
+    pub missing: Vec<PathBuf>,
+    pub corrupted: Vec<PathBuf>,
+    to_repair: Vec<DownloadEntry>,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.to_repair.is_empty()
+    }
+}
+
+// checks the already synced files against the known hashes without touching the network,
+// always hashes every file, ignoring the hash cache
+pub async fn verify_instance(
+    version_metadata: &CompleteVersionMetadata,
+    game_dir: &GameDir<'_>,
+    launcher_dir: &Path,
+    assets_dir: &Path,
+    progress_bar: Arc<dyn ProgressBar<LangMessage> + Send + Sync>,
+) -> anyhow::Result<VerifyReport> {
+    let libraries_dir = get_libraries_dir(launcher_dir);
+    let instance_dir = game_dir.path;
+
+    let mut check_entries = vec![];
+
+    check_entries.push(version_metadata.get_client_check_entry(launcher_dir)?);
+
+    let mut libraries = version_metadata.get_libraries_with_overrides();
+    libraries.extend(version_metadata.get_extra_forge_libs());
+    for library in &libraries {
+        check_entries.extend(
+            library
+                .get_check_entries(
+                    &libraries_dir,
+                    Some((&os::get_os_name(), &os::get_system_arch())),
+                )
+                .into_iter()
+                .filter(|entry| !entry.url.is_empty()),
+        );
+    }
+
+    if let Some(extra) = version_metadata.get_extra() {
+        check_entries.extend(get_objects_entries(
+            extra,
+            false,
+            false,
+            game_dir.preserved_paths,
+            instance_dir,
+        )?);
+    }
+
+    if let Some(authlib_injector) = get_authlib_injector_entry(version_metadata, launcher_dir) {
+        check_entries.push(authlib_injector);
+    }
+
+    let asset_index = version_metadata.get_asset_index()?;
+    let asset_metadata = AssetsMetadata::read_local(&asset_index.id, assets_dir).await?;
+    check_entries.extend(
+        asset_metadata.get_check_entries(assets_dir, version_metadata.get_resources_url_base())?,
+    );
+
+    info!("Verifying {} entries", check_entries.len());
+    progress_bar.set_message(LangMessage::CheckingFiles);
+    let to_repair = files::get_download_entries(check_entries, progress_bar).await?;
+
+    let (missing, corrupted) = to_repair
+        .iter()
+        .map(|entry| entry.path.clone())
+        .partition(|path| !path.exists());
+
+    Ok(VerifyReport {
+        missing,
+        corrupted,
+        to_repair,
+    })
+}
+
+pub async fn repair_instance(
+    version_metadata: &CompleteVersionMetadata,
+    report: VerifyReport,
+    launcher_dir: &Path,
+    download_options: &DownloadOptions,
+    progress_bar: Arc<dyn ProgressBar<LangMessage> + Send + Sync>,
+) -> anyhow::Result<()> {
+    let libraries_dir = get_libraries_dir(launcher_dir);
+    let natives_dir = get_natives_dir(launcher_dir, version_metadata.get_parent_id());
+
+    info!("Repairing {} files", report.to_repair.len());
     progress_bar.set_message(LangMessage::DownloadingFiles);
-    download_files(download_entries, progress_bar).await?;
+    download_files_with_options(report.to_repair, progress_bar, download_options).await?;
 
+    let mut libraries = version_metadata.get_libraries_with_overrides();
+    libraries.extend(version_metadata.get_extra_forge_libs());
     extract_natives(&libraries, &libraries_dir, &natives_dir)?;
 
     Ok(())
 }
+
+// returns the number of files that had to be redownloaded
+pub async fn verify_and_repair_instance(
+    version_metadata: &CompleteVersionMetadata,
+    game_dir: &GameDir<'_>,
+    launcher_dir: &Path,
+    assets_dir: &Path,
+    download_options: &DownloadOptions,
+    progress_bar: Arc<dyn ProgressBar<LangMessage> + Send + Sync>,
+) -> anyhow::Result<usize> {
+    let report = verify_instance(
+        version_metadata,
+        game_dir,
+        launcher_dir,
+        assets_dir,
+        progress_bar.clone(),
+    )
+    .await?;
+    let repaired = report.to_repair.len();
+    if !report.is_ok() {
+        repair_instance(
+            version_metadata,
+            report,
+            launcher_dir,
+            download_options,
+            progress_bar,
+        )
+        .await?;
+    }
+
+    Ok(repaired)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    fn get_entry_paths(
+        extra: &ExtraVersionMetadata,
+        force_overwrite: bool,
+        instance_dir: &Path,
+    ) -> HashSet<PathBuf> {
+        get_objects_entries(extra, force_overwrite, false, &[], instance_dir)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect()
+    }
+
+    #[test]
+    fn test_include_no_overwrite() {
+        let instance_dir = env::temp_dir().join("launcher_sync_test");
+        let _ = fs::remove_dir_all(&instance_dir);
+        fs::create_dir_all(&instance_dir).unwrap();
+
+        let extra: ExtraVersionMetadata = serde_json::from_value(serde_json::json!({
+            "include": ["mods"],
+            "include_no_overwrite": ["config"],
+            "objects": [
+                {"path": "mods/mod.jar", "sha1": "1", "url": "https://example.com/mod.jar"},
+                {"path": "config/mod.json", "sha1": "2", "url": "https://example.com/mod.json"},
+            ],
+        }))
+        .unwrap();
+        let mod_path = instance_dir.join("mods/mod.jar");
+        let config_path = instance_dir.join("config/mod.json");
+        let all_paths = HashSet::from([mod_path.clone(), config_path.clone()]);
+
+        // fresh install, nothing is present so both are downloaded
+        assert_eq!(get_entry_paths(&extra, false, &instance_dir), all_paths);
+
+        // the user edited the config, only the overwritten files are checked
+        fs::create_dir_all(instance_dir.join("mods")).unwrap();
+        fs::create_dir_all(instance_dir.join("config")).unwrap();
+        fs::write(&mod_path, "mod").unwrap();
+        fs::write(&config_path, "edited").unwrap();
+        assert_eq!(
+            get_entry_paths(&extra, false, &instance_dir),
+            HashSet::from([mod_path.clone()])
+        );
+
+        // a forced sync restores the config as well
+        assert_eq!(get_entry_paths(&extra, true, &instance_dir), all_paths);
+
+        // deleted no-overwrite files are downloaded again
+        fs::remove_file(&config_path).unwrap();
+        assert_eq!(get_entry_paths(&extra, false, &instance_dir), all_paths);
+
+        fs::remove_dir_all(&instance_dir).unwrap();
+    }
+}
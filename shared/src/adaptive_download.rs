@@ -1,7 +1,9 @@
 use futures::stream::{FuturesUnordered, StreamExt};
-use log::debug;
+use log::{debug, warn};
 use reqwest::Client;
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::path::Path;
 use std::sync::{
     atomic::{AtomicUsize, Ordering},
     Arc,
@@ -11,25 +13,31 @@ use tokio::io::AsyncWriteExt;
 use tokio::sync::Mutex;
 
 use crate::files::DownloadEntry;
+use crate::http;
 use crate::progress::ProgressBar;
+use crate::utils::urls_from_rel_path;
 
-const MAX_CONCURRENCY: usize = 75;
+pub const DEFAULT_INITIAL_CONCURRENCY: usize = 4;
+pub const DEFAULT_MAX_CONCURRENCY: usize = 75;
 const MIN_CONCURRENCY: usize = 1;
 const WINDOW_DURATION: Duration = Duration::from_secs(2);
 const UPDATE_CONCURRENCY_EVERY: usize = 5;
 const REQUEST_TIMEOUT: Duration = Duration::from_secs(4);
+const NO_PROGRESS_TIMEOUT: Duration = Duration::from_secs(60);
+// throughput has to grow by at least this factor to keep adding downloads
+const THROUGHPUT_GROWTH_THRESHOLD: f64 = 1.05;
 
 struct DownloadRecord {
     timestamp: Instant,
     success: bool,
-    latency_ms: u128,
+    bytes: u64,
 }
 
 struct SlidingWindow {
     window: VecDeque<DownloadRecord>,
     total: usize,
     successes: usize,
-    sum_latency_successes: u128,
+    sum_bytes: u64,
 }
 
 impl SlidingWindow {
@@ -38,22 +46,22 @@ impl SlidingWindow {
             window: VecDeque::new(),
             total: 0,
             successes: 0,
-            sum_latency_successes: 0,
+            sum_bytes: 0,
         }
     }
 
-    fn push(&mut self, success: bool, latency_ms: u128) {
+    fn push(&mut self, success: bool, bytes: u64) {
         self.window.push_back(DownloadRecord {
             timestamp: Instant::now(),
             success,
-            latency_ms,
+            bytes,
         });
 
         self.total += 1;
         if success {
             self.successes += 1;
-            self.sum_latency_successes += latency_ms;
         }
+        self.sum_bytes += bytes;
     }
 
     fn pop_expired(&mut self) {
@@ -64,19 +72,19 @@ impl SlidingWindow {
                 self.total -= 1;
                 if rec.success {
                     self.successes -= 1;
-                    self.sum_latency_successes -= rec.latency_ms;
                 }
+                self.sum_bytes -= rec.bytes;
             } else {
                 break;
             }
         }
     }
 
-    /// Insert the latest result, remove old ones, then compute success rate & average success latency.
+    /// Insert the latest result, remove old ones, then compute success rate & throughput.
     /// Success rate = successes / total
-    /// Average latency = sum of successful latencies / successes (only for success).
-    fn add_and_calculate(&mut self, success: bool, latency_ms: u128) -> (f64, f64) {
-        self.push(success, latency_ms);
+    /// Throughput = bytes of downloads finished within the window / window duration (bytes per second).
+    fn add_and_calculate(&mut self, success: bool, bytes: u64) -> (f64, f64) {
+        self.push(success, bytes);
         self.pop_expired();
 
         if self.total == 0 {
@@ -84,36 +92,159 @@ impl SlidingWindow {
         }
 
         let success_rate = self.successes as f64 / self.total as f64;
-        let avg_latency = if self.successes > 0 {
-            self.sum_latency_successes as f64 / self.successes as f64
-        } else {
-            0.0
+        let throughput = self.sum_bytes as f64 / WINDOW_DURATION.as_secs_f64();
+
+        (success_rate, throughput)
+    }
+}
+
+/// Token bucket shared by all concurrent downloads, so the limit applies to the total rate.
+pub struct RateLimiter {
+    bytes_per_second: f64,
+    state: Mutex<RateLimiterState>,
+}
+
+struct RateLimiterState {
+    available: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// `None` or `Some(0)` means unlimited.
+    pub fn new(max_rate_kbps: Option<u64>) -> Option<Self> {
+        let max_rate_kbps = max_rate_kbps.filter(|&max_rate_kbps| max_rate_kbps > 0)?;
+        let bytes_per_second = (max_rate_kbps * 1024) as f64;
+        Some(Self {
+            bytes_per_second,
+            state: Mutex::new(RateLimiterState {
+                available: bytes_per_second,
+                last_refill: Instant::now(),
+            }),
+        })
+    }
+
+    pub async fn consume(&self, bytes: usize) {
+        let mut state = self.state.lock().await;
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.available =
+            (state.available + elapsed * self.bytes_per_second).min(self.bytes_per_second);
+        state.last_refill = now;
+
+        state.available -= bytes as f64;
+        if state.available < 0.0 {
+            // keep the lock while sleeping so other downloads wait for their turn
+            let wait = Duration::from_secs_f64(-state.available / self.bytes_per_second);
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct DownloadOptions {
+    /// Limits the total download rate, `None` or `Some(0)` means unlimited.
+    pub max_rate_kbps: Option<u64>,
+    /// Interchangeable base URLs. A URL under one of them is retried against the others in order.
+    /// Once a base refuses a connection, the others are tried first for the remaining files.
+    pub mirrors: Vec<String>,
+    /// Number of parallel downloads to start with, adjusted by the measured throughput.
+    pub initial_concurrency: usize,
+    pub max_concurrency: usize,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            max_rate_kbps: None,
+            mirrors: Vec::new(),
+            initial_concurrency: DEFAULT_INITIAL_CONCURRENCY,
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+        }
+    }
+}
+
+// mirror bases that refused connections earlier in this download, shared by all files
+type UnreachableMirrors = std::sync::Mutex<HashSet<String>>;
+
+// when any download last received data, a rate limited file may take minutes while still progressing
+type LastProgress = std::sync::Mutex<Instant>;
+
+// returns (mirror base, url) pairs, the base is None for urls outside of all mirrors
+fn get_candidate_urls(
+    url: &str,
+    mirrors: &[String],
+    unreachable: &HashSet<String>,
+) -> Vec<(Option<String>, String)> {
+    for (i, base) in mirrors.iter().enumerate() {
+        let Some(rel_path) = url.strip_prefix(base.trim_end_matches('/')) else {
+            continue;
+        };
+        let Some(rel_path) = rel_path.strip_prefix('/') else {
+            continue;
         };
 
-        (success_rate, avg_latency)
+        let mut bases = vec![base.trim_end_matches('/').to_string()];
+        bases.extend(
+            mirrors
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, base)| base.trim_end_matches('/').to_string()),
+        );
+        // a dead primary would otherwise cost a connection attempt for every file
+        bases.sort_by_key(|base| unreachable.contains(base));
+        if let Ok(urls) = urls_from_rel_path(Path::new(rel_path), &bases) {
+            return bases.into_iter().map(Some).zip(urls).collect();
+        }
     }
+    vec![(None, url.to_string())]
 }
 
-async fn download_file(client: &Client, entry: &DownloadEntry) -> anyhow::Result<u128> {
-    let start = Instant::now();
+fn is_mirror_fallback_error(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<reqwest::Error>()
+        .is_some_and(|e| e.is_connect() || e.is_status())
+}
 
-    let response = client.get(&entry.url).send().await?.error_for_status()?;
+fn is_connect_error(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<reqwest::Error>()
+        .is_some_and(|e| e.is_connect())
+}
+
+async fn download_file(
+    client: &Client,
+    url: &str,
+    entry: &DownloadEntry,
+    rate_limiter: Option<&RateLimiter>,
+    last_progress: &LastProgress,
+) -> anyhow::Result<u64> {
+    let response = http::send(http::with_server_headers(client.get(url), url))
+        .await?
+        .error_for_status()?;
     let mut stream = response.bytes_stream();
 
     if let Some(parent_dir) = entry.path.parent() {
         tokio::fs::create_dir_all(parent_dir).await?;
     }
+    if entry.path.is_symlink() {
+        // replace the link itself instead of writing through it
+        warn!("Replacing symlink {:?} with a downloaded file", entry.path);
+        tokio::fs::remove_file(&entry.path).await?;
+    }
     let mut file = tokio::fs::File::create(&entry.path).await?;
 
     let per_chunk_timeout = REQUEST_TIMEOUT;
+    let mut bytes = 0;
     while let Some(chunk_result) = tokio::time::timeout(per_chunk_timeout, stream.next()).await? {
         let chunk = chunk_result?;
         file.write_all(&chunk).await?;
+        bytes += chunk.len() as u64;
+        *last_progress.lock().unwrap() = Instant::now();
+        if let Some(rate_limiter) = rate_limiter {
+            rate_limiter.consume(chunk.len()).await;
+        }
     }
 
-    let latency_ms = start.elapsed().as_millis();
-
-    Ok(latency_ms)
+    Ok(bytes)
 }
 
 fn is_timeout_error(e: &anyhow::Error) -> bool {
@@ -124,24 +255,73 @@ fn is_timeout_error(e: &anyhow::Error) -> bool {
     // reqwest doesn't let us check for this error directly
 }
 
-/// Download a single file, returning (success, latency_ms).
-/// On success, we return Ok(Some(latency_ms)).
+/// Requests `url` and, when its server can't be reached or refuses it, the same path on the other mirrors.
+pub async fn fetch_with_mirrors<T, F, Fut>(
+    url: &str,
+    mirrors: &[String],
+    fetch: F,
+) -> anyhow::Result<T>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let urls = get_candidate_urls(url, mirrors, &HashSet::new());
+    let mut urls = urls.into_iter().map(|(_, url)| url).peekable();
+    while let Some(url) = urls.next() {
+        match fetch(url.clone()).await {
+            Err(e) if urls.peek().is_some() && is_mirror_fallback_error(&e) => {
+                warn!(
+                    "Error fetching {}, trying next mirror: {}",
+                    http::redact(&url),
+                    http::redact(&e.to_string())
+                );
+            }
+            result => return result,
+        }
+    }
+    unreachable!("candidate urls are never empty")
+}
+
+/// Download a single file.
+/// On success, we return Ok(Some(bytes_downloaded)).
 /// If it's a timeout, we return Ok(None). If it's another error, we return Err(e).
-async fn do_download(client: &Client, entry: &DownloadEntry) -> anyhow::Result<Option<u128>> {
-    let latency_ms = match download_file(client, entry).await {
-        Ok(r) => r,
-        Err(e) => {
-            // If it's a timeout, we return Ok(None), else Err
-            if is_timeout_error(&e) {
-                debug!("Timeout downloading {}", entry.url);
-                return Ok(None);
-            } else {
-                return Err(e);
+async fn do_download(
+    client: &Client,
+    entry: &DownloadEntry,
+    rate_limiter: Option<&RateLimiter>,
+    mirrors: &[String],
+    unreachable_mirrors: &UnreachableMirrors,
+    last_progress: &LastProgress,
+) -> anyhow::Result<Option<u64>> {
+    let urls = get_candidate_urls(&entry.url, mirrors, &unreachable_mirrors.lock().unwrap());
+    for (i, (base, url)) in urls.iter().enumerate() {
+        match download_file(client, url, entry, rate_limiter, last_progress).await {
+            Ok(bytes) => return Ok(Some(bytes)),
+            Err(e) if i + 1 < urls.len() && is_mirror_fallback_error(&e) => {
+                warn!(
+                    "Error downloading {}, trying next mirror: {}",
+                    http::redact(url),
+                    http::redact(&e.to_string())
+                );
+                if let Some(base) = base.as_ref().filter(|_| is_connect_error(&e)) {
+                    if unreachable_mirrors.lock().unwrap().insert(base.clone()) {
+                        warn!("{} is unreachable, preferring other mirrors", base);
+                    }
+                }
+            }
+            Err(e) => {
+                // If it's a timeout, we return Ok(None), else Err
+                if is_timeout_error(&e) {
+                    debug!("Timeout downloading {}", http::redact(url));
+                    return Ok(None);
+                } else {
+                    return Err(e);
+                }
             }
         }
-    };
+    }
 
-    Ok(Some(latency_ms))
+    unreachable!("candidate urls are never empty")
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -153,14 +333,34 @@ pub enum AdaptiveDownloadError {
 pub async fn download_files<M>(
     download_entries: Vec<DownloadEntry>,
     progress_bar: Arc<dyn ProgressBar<M> + Send + Sync>,
+) -> anyhow::Result<()> {
+    download_files_with_options(download_entries, progress_bar, &DownloadOptions::default()).await
+}
+
+pub async fn download_files_with_options<M>(
+    download_entries: Vec<DownloadEntry>,
+    progress_bar: Arc<dyn ProgressBar<M> + Send + Sync>,
+    options: &DownloadOptions,
 ) -> anyhow::Result<()> {
     progress_bar.set_length(download_entries.len() as u64);
 
-    let client = Client::builder().connect_timeout(REQUEST_TIMEOUT).build()?;
+    let rate_limiter = RateLimiter::new(options.max_rate_kbps);
 
-    let desired_concurrency = Arc::new(AtomicUsize::new(4));
+    // each file has its own chunk and progress timeouts
+    let client = http::streaming_client_builder()?
+        .connect_timeout(REQUEST_TIMEOUT)
+        .build()?;
+
+    let max_concurrency = options.max_concurrency.max(MIN_CONCURRENCY);
+    let desired_concurrency = Arc::new(AtomicUsize::new(
+        options
+            .initial_concurrency
+            .clamp(MIN_CONCURRENCY, max_concurrency),
+    ));
 
     let sliding_window = Arc::new(Mutex::new(SlidingWindow::new()));
+    let unreachable_mirrors = UnreachableMirrors::default();
+    let last_progress = LastProgress::new(Instant::now());
 
     let mut cur_entries = download_entries;
     let mut active = FuturesUnordered::new();
@@ -173,7 +373,15 @@ pub async fn download_files<M>(
         while can_spawn_more(active.len(), &desired_concurrency) {
             if let Some(entry) = cur_entries.pop() {
                 let fut = async {
-                    let result = do_download(&client, &entry).await;
+                    let result = do_download(
+                        &client,
+                        &entry,
+                        rate_limiter.as_ref(),
+                        &options.mirrors,
+                        &unreachable_mirrors,
+                        &last_progress,
+                    )
+                    .await;
                     (result, entry)
                 };
                 active.push(fut);
@@ -185,15 +393,17 @@ pub async fn download_files<M>(
 
     spawn_if_possible(&mut active, &mut cur_entries);
 
-    let mut previous_success_time = Instant::now();
-
     let mut next_concurrency_update = UPDATE_CONCURRENCY_EVERY;
+    let mut last_throughput = 0.0;
     loop {
-        let sleep_until = previous_success_time + Duration::from_secs(60);
+        let sleep_until = *last_progress.lock().unwrap() + NO_PROGRESS_TIMEOUT;
 
         let maybe_item = tokio::select! {
             item = active.next() => item,
             _ = tokio::time::sleep_until(sleep_until.into()) => {
+                if last_progress.lock().unwrap().elapsed() < NO_PROGRESS_TIMEOUT {
+                    continue;
+                }
                 return Err(AdaptiveDownloadError::ConnectionTimeout.into());
             }
         };
@@ -201,11 +411,11 @@ pub async fn download_files<M>(
             break;
         };
 
-        let (success, latency_ms) = match result {
-            Ok(Some(latency_ms)) => {
+        let (success, bytes) = match result {
+            Ok(Some(bytes)) => {
                 progress_bar.inc(1);
-                previous_success_time = Instant::now();
-                (true, latency_ms)
+                *last_progress.lock().unwrap() = Instant::now();
+                (true, bytes)
             }
             Ok(None) => {
                 cur_entries.push(entry);
@@ -216,9 +426,9 @@ pub async fn download_files<M>(
             }
         };
 
-        let (success_rate, avg_latency) = {
+        let (success_rate, throughput) = {
             let mut guard = sliding_window.lock().await;
-            guard.add_and_calculate(success, latency_ms)
+            guard.add_and_calculate(success, bytes)
         };
 
         let current = desired_concurrency.load(Ordering::SeqCst);
@@ -227,16 +437,22 @@ pub async fn download_files<M>(
             next_concurrency_update = UPDATE_CONCURRENCY_EVERY;
             let mut new_value = current;
             if success {
-                if success_rate > 0.9 && avg_latency < 2000.0 {
-                    new_value = (current + 1).min(MAX_CONCURRENCY);
+                if success_rate > 0.9 && throughput > last_throughput * THROUGHPUT_GROWTH_THRESHOLD
+                {
+                    new_value = (current + 1).min(max_concurrency);
                 }
+                last_throughput = throughput;
             } else {
-                new_value = (current - (current + 3) / 4).max(MIN_CONCURRENCY);
+                new_value = (current - current.div_ceil(4)).max(MIN_CONCURRENCY);
             }
 
             if new_value != current {
                 desired_concurrency.store(new_value, Ordering::SeqCst);
-                debug!("New concurrency: {}", new_value);
+                debug!(
+                    "New concurrency: {} ({:.1} KB/s)",
+                    new_value,
+                    throughput / 1024.0
+                );
             }
         }
 
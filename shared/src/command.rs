@@ -0,0 +1,106 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Mutex;
+
+use log::{error, info};
+use tokio::io::{AsyncBufReadExt as _, AsyncRead, BufReader};
+use tokio::process::Child;
+
+const COMMAND_OUTPUT_TAIL_LINES: usize = 20;
+
+#[derive(thiserror::Error, Debug)]
+pub enum CommandError {
+    #[error("Empty command")]
+    EmptyCommand,
+    #[error("Command exited with code {0}, output tail:\n{1}")]
+    NonZeroExit(i32, String),
+}
+
+// logs the output line by line as it arrives so long-running commands are visible,
+// invalid UTF-8 (e.g. a legacy console code page) must not stop the reading and break the pipe
+async fn read_command_output<R: AsyncRead + Unpin>(
+    reader: Option<R>,
+    tail: &Mutex<VecDeque<String>>,
+) {
+    let Some(reader) = reader else {
+        return;
+    };
+    let mut reader = BufReader::new(reader);
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf).await {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => {
+                error!("Error reading command output:\n{:?}", e);
+                break;
+            }
+        }
+
+        let line = String::from_utf8_lossy(&buf).trim_end().to_string();
+        info!("[command] {}", line);
+        let mut tail = tail.lock().unwrap();
+        if tail.len() == COMMAND_OUTPUT_TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(line);
+    }
+}
+
+// A non-zero exit fails with the last lines of both streams in the order they were printed.
+async fn wait_for_command(mut child: Child) -> anyhow::Result<()> {
+    let tail = Mutex::new(VecDeque::new());
+    let (_, _, status) = tokio::join!(
+        read_command_output(child.stdout.take(), &tail),
+        read_command_output(child.stderr.take(), &tail),
+        child.wait(),
+    );
+    let status = status?;
+    if !status.success() {
+        let tail: Vec<String> = tail.into_inner().unwrap().into();
+        return Err(CommandError::NonZeroExit(status.code().unwrap_or(-1), tail.join("\n")).into());
+    }
+    Ok(())
+}
+
+// converts Windows verbatim paths ("\\?\C:\..." and "\\?\UNC\server\...") to their regular form,
+// leaves everything else untouched
+fn strip_verbatim_prefix(arg: &str) -> String {
+    if let Some(rest) = arg.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", rest)
+    } else if let Some(rest) = arg.strip_prefix(r"\\?\") {
+        rest.to_string()
+    } else {
+        arg.to_string()
+    }
+}
+
+/// Runs a shell-style command line, logging its output.
+/// `dir` and `env_vars` only apply to the spawned process.
+pub async fn exec_command(
+    command: &str,
+    dir: Option<&Path>,
+    env_vars: &HashMap<String, String>,
+) -> anyhow::Result<()> {
+    let mut parts = shell_words::split(command)?;
+    let mut dir = dir.map(Path::to_path_buf);
+    if cfg!(windows) {
+        parts = parts
+            .iter()
+            .map(|part| strip_verbatim_prefix(part))
+            .collect();
+        dir = dir.map(|dir| PathBuf::from(strip_verbatim_prefix(&dir.to_string_lossy())));
+    }
+    let (program, args) = parts.split_first().ok_or(CommandError::EmptyCommand)?;
+    let mut cmd = tokio::process::Command::new(program);
+    cmd.args(args)
+        .envs(env_vars)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(dir) = dir {
+        cmd.current_dir(dir);
+    }
+    wait_for_command(cmd.spawn()?).await
+}
@@ -0,0 +1,34 @@
+use std::sync::RwLock;
+
+use crate::utils::VANILLA_MANIFEST_URL;
+
+pub const JAVA_METADATA_URL: &str = "https://api.azul.com/metadata/v1/zulu/packages/";
+
+/// Replacements for the public Azul and Mojang endpoints, e.g. a regional mirror.
+#[derive(Clone, Default)]
+pub struct Endpoints {
+    pub java_metadata_url: Option<String>,
+    pub vanilla_manifest_url: Option<String>,
+}
+
+static ENDPOINTS: RwLock<Option<Endpoints>> = RwLock::new(None);
+
+pub fn set_endpoints(endpoints: Endpoints) {
+    *ENDPOINTS.write().unwrap() = Some(endpoints);
+}
+
+fn get_endpoints() -> Endpoints {
+    ENDPOINTS.read().unwrap().clone().unwrap_or_default()
+}
+
+pub fn get_java_metadata_url() -> String {
+    get_endpoints()
+        .java_metadata_url
+        .unwrap_or_else(|| JAVA_METADATA_URL.to_string())
+}
+
+pub fn get_vanilla_manifest_url() -> String {
+    get_endpoints()
+        .vanilla_manifest_url
+        .unwrap_or_else(|| VANILLA_MANIFEST_URL.to_string())
+}
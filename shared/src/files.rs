@@ -1,13 +1,18 @@
-use log::error;
+use log::warn;
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
 use sha1::{Digest, Sha1};
 use std::collections::HashMap;
+use std::io::Read as _;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::UNIX_EPOCH;
 use tokio::fs;
-use tokio::io::{AsyncReadExt as _, AsyncWriteExt};
+use tokio::io::AsyncWriteExt;
+use tokio_util::sync::CancellationToken;
 use walkdir::WalkDir;
 
+use crate::http;
 use crate::progress::{run_tasks_with_progress, ProgressBar};
 
 pub fn get_files_in_dir(path: &Path) -> anyhow::Result<Vec<PathBuf>> {
@@ -23,39 +28,247 @@ pub fn get_files_in_dir(path: &Path) -> anyhow::Result<Vec<PathBuf>> {
     Ok(files)
 }
 
-pub async fn hash_file(path: &Path) -> anyhow::Result<String> {
-    let mut file = fs::File::open(path).await?;
-    let mut hasher = Sha1::new();
-    let mut buffer = [0; 1024];
+// total size of the files under path, symlinks are not followed
+pub fn get_dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
 
+// checks whether the path or any of its parents up to base is a symlink
+pub fn is_under_symlink(path: &Path, base: &Path) -> bool {
+    path.ancestors()
+        .take_while(|p| *p != base)
+        .any(|p| p.is_symlink())
+}
+
+const HASH_CHUNK_SIZE: usize = 1024 * 1024;
+
+#[derive(thiserror::Error, Debug)]
+#[error("Hashing cancelled")]
+struct HashCancelledError;
+
+// blocking tasks can't be aborted, so the token is checked between chunks
+fn hash_file_blocking(
+    path: &Path,
+    cancellation_token: &CancellationToken,
+) -> anyhow::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha1::new();
+    let mut buf = vec![0; HASH_CHUNK_SIZE];
     loop {
-        let n = file.read(&mut buffer).await?;
-        if n == 0 {
+        if cancellation_token.is_cancelled() {
+            return Err(HashCancelledError.into());
+        }
+        let read = file.read(&mut buf)?;
+        if read == 0 {
             break;
         }
-        hasher.update(&buffer[..n]);
+        hasher.update(&buf[..read]);
     }
-
     Ok(format!("{:x}", hasher.finalize()))
 }
 
+#[cfg(target_os = "windows")]
+pub fn get_free_space(path: &Path) -> anyhow::Result<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use winapi::shared::ntdef::ULARGE_INTEGER;
+    use winapi::um::fileapi::GetDiskFreeSpaceExW;
+
+    let path_wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut free: ULARGE_INTEGER = unsafe { std::mem::zeroed() };
+    let res = unsafe {
+        GetDiskFreeSpaceExW(
+            path_wide.as_ptr(),
+            &mut free,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    if res == 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(unsafe { *free.QuadPart() })
+}
+
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+pub fn get_free_space(path: &Path) -> anyhow::Result<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path_c = CString::new(path.as_os_str().as_bytes())?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    let res = unsafe { libc::statvfs(path_c.as_ptr(), &mut stat) };
+    if res != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum DiskSpaceError {
+    #[error(
+        "Not enough disk space in {path:?}: {required} bytes required, {available} bytes available"
+    )]
+    NotEnoughSpace {
+        path: PathBuf,
+        required: u64,
+        available: u64,
+    },
+}
+
+// the target directory may not exist yet, so the closest existing parent is checked;
+// failing to query the free space is not treated as an error
+pub fn check_free_space(path: &Path, required: u64) -> anyhow::Result<()> {
+    let Some(existing) = path.ancestors().find(|p| p.exists()) else {
+        return Ok(());
+    };
+    match get_free_space(existing) {
+        Ok(available) if available < required => Err(DiskSpaceError::NotEnoughSpace {
+            path: path.to_path_buf(),
+            required,
+            available,
+        }
+        .into()),
+        Ok(_) => Ok(()),
+        Err(e) => {
+            warn!("Failed to get free disk space for {:?}:\n{:?}", existing, e);
+            Ok(())
+        }
+    }
+}
+
+async fn hash_file_cancellable(
+    path: &Path,
+    cancellation_token: CancellationToken,
+) -> anyhow::Result<String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || hash_file_blocking(&path, &cancellation_token)).await?
+}
+
+// hashing is cpu bound, so it runs on the blocking pool instead of stalling the async workers
+pub async fn hash_file(path: &Path) -> anyhow::Result<String> {
+    hash_file_cancellable(path, CancellationToken::new()).await
+}
+
 pub async fn hash_files<M>(
     files: Vec<PathBuf>,
     progress_bar: Arc<dyn ProgressBar<M> + Send + Sync>,
 ) -> anyhow::Result<Vec<String>> {
     let tasks_count = files.len() as u64;
 
-    let tasks = files
-        .into_iter()
-        .map(|path| async move { hash_file(&path).await });
+    // stops the hashing still running on the blocking pool once this future is dropped
+    let cancellation_token = CancellationToken::new();
+    let _guard = cancellation_token.clone().drop_guard();
+
+    let tasks = files.into_iter().map(|path| {
+        let cancellation_token = cancellation_token.clone();
+        async move { hash_file_cancellable(&path, cancellation_token).await }
+    });
 
     run_tasks_with_progress(tasks, progress_bar, tasks_count, num_cpus::get()).await
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+struct FileStamp {
+    size: u64,
+    mtime_nanos: u64,
+}
+
+impl FileStamp {
+    fn read(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        let mtime = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(FileStamp {
+            size: metadata.len(),
+            mtime_nanos: mtime.as_nanos() as u64,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct HashCacheEntry {
+    stamp: FileStamp,
+    sha1: String,
+}
+
+// hashes of previously checked files, trusted while their size and mtime stay the same
+#[derive(Serialize, Deserialize, Default)]
+pub struct HashCache {
+    entries: HashMap<PathBuf, HashCacheEntry>,
+}
+
+impl HashCache {
+    pub async fn load(path: &Path) -> Self {
+        match fs::read(path).await {
+            Ok(data) => serde_json::from_slice(&data).unwrap_or_default(),
+            Err(_) => HashCache::default(),
+        }
+    }
+
+    pub async fn save(&self, path: &Path) -> anyhow::Result<()> {
+        fs::write(path, serde_json::to_vec(self)?).await?;
+        Ok(())
+    }
+
+    fn get(&self, path: &Path, stamp: &FileStamp) -> Option<&String> {
+        self.entries
+            .get(path)
+            .filter(|entry| &entry.stamp == stamp)
+            .map(|entry| &entry.sha1)
+    }
+}
+
+pub async fn hash_files_cached<M>(
+    files: Vec<PathBuf>,
+    hash_cache: &mut HashCache,
+    progress_bar: Arc<dyn ProgressBar<M> + Send + Sync>,
+) -> anyhow::Result<Vec<String>> {
+    let stamps: Vec<_> = files.iter().map(|path| FileStamp::read(path)).collect();
+    let to_hash: Vec<_> = files
+        .iter()
+        .zip(&stamps)
+        .filter(|(path, stamp)| {
+            stamp
+                .as_ref()
+                .and_then(|stamp| hash_cache.get(path, stamp))
+                .is_none()
+        })
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    let new_hashes = hash_files(to_hash.clone(), progress_bar).await?;
+    let new_hashes: HashMap<_, _> = to_hash.into_iter().zip(new_hashes).collect();
+
+    let mut hashes = Vec::with_capacity(files.len());
+    for (path, stamp) in files.into_iter().zip(stamps) {
+        let sha1 = new_hashes
+            .get(&path)
+            .or_else(|| hash_cache.get(&path, stamp.as_ref()?))
+            .ok_or_else(|| CheckDownloadError::HashMissing(path.clone()))?
+            .clone();
+        if let Some(stamp) = stamp {
+            hash_cache.entries.insert(
+                path,
+                HashCacheEntry {
+                    stamp,
+                    sha1: sha1.clone(),
+                },
+            );
+        }
+        hashes.push(sha1);
+    }
+
+    Ok(hashes)
+}
+
 pub async fn download_file(client: &Client, url: &str, path: &Path) -> anyhow::Result<()> {
-    let response = client
-        .get(url)
-        .send()
+    let response = http::send(http::with_server_headers(client.get(url), url))
         .await?
         .error_for_status()?
         .bytes()
@@ -64,6 +277,10 @@ pub async fn download_file(client: &Client, url: &str, path: &Path) -> anyhow::R
     if let Some(parent_dir) = path.parent() {
         tokio::fs::create_dir_all(parent_dir).await?;
     }
+    if path.is_symlink() {
+        // replace the link itself instead of writing through it
+        tokio::fs::remove_file(path).await?;
+    }
     let mut file = tokio::fs::File::create(path).await?;
 
     file.write_all(&response).await?;
@@ -74,24 +291,40 @@ pub async fn download_file(client: &Client, url: &str, path: &Path) -> anyhow::R
 pub struct DownloadEntry {
     pub url: String,
     pub path: PathBuf,
+    pub size: Option<u64>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CheckEntry {
     pub url: String,
     pub remote_sha1: Option<String>,
     pub path: PathBuf,
+    pub size: Option<u64>,
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum CheckDownloadError {
     #[error("Hash of file {0} is missing")]
     HashMissing(PathBuf),
+    #[error("Hash mismatch for file {path}: expected {expected}, got {actual}")]
+    HashMismatch {
+        path: PathBuf,
+        expected: String,
+        actual: String,
+    },
 }
 
 pub async fn get_download_entries<M>(
     check_entries: Vec<CheckEntry>,
     progress_bar: Arc<dyn ProgressBar<M> + Send + Sync>,
+) -> anyhow::Result<Vec<DownloadEntry>> {
+    get_download_entries_cached(check_entries, None, progress_bar).await
+}
+
+pub async fn get_download_entries_cached<M>(
+    check_entries: Vec<CheckEntry>,
+    hash_cache: Option<&mut HashCache>,
+    progress_bar: Arc<dyn ProgressBar<M> + Send + Sync>,
 ) -> anyhow::Result<Vec<DownloadEntry>> {
     let to_hash: Vec<_> = check_entries
         .iter()
@@ -104,11 +337,13 @@ pub async fn get_download_entries<M>(
         })
         .collect();
 
-    let hashes = hash_files(to_hash.clone(), progress_bar.clone()).await?;
-    let hashes = to_hash
-        .into_iter()
-        .zip(hashes.into_iter())
-        .collect::<HashMap<_, _>>();
+    let hashes = match hash_cache {
+        Some(hash_cache) => {
+            hash_files_cached(to_hash.clone(), hash_cache, progress_bar.clone()).await?
+        }
+        None => hash_files(to_hash.clone(), progress_bar.clone()).await?,
+    };
+    let hashes = to_hash.into_iter().zip(hashes).collect::<HashMap<_, _>>();
 
     let mut download_entries = HashMap::new();
     for entry in check_entries {
@@ -131,6 +366,7 @@ pub async fn get_download_entries<M>(
                 DownloadEntry {
                     url: entry.url.clone(),
                     path: entry.path.clone(),
+                    size: entry.size,
                 },
             );
         }
@@ -139,6 +375,25 @@ pub async fn get_download_entries<M>(
     Ok(download_entries.into_values().collect())
 }
 
+// removes mismatching files so that they are downloaded again next time
+pub async fn verify_check_entries(check_entries: &[CheckEntry]) -> anyhow::Result<()> {
+    for entry in check_entries {
+        if let Some(expected) = &entry.remote_sha1 {
+            let actual = hash_file(&entry.path).await?;
+            if &actual != expected {
+                let _ = fs::remove_file(&entry.path).await;
+                return Err(CheckDownloadError::HashMismatch {
+                    path: entry.path.clone(),
+                    expected: expected.clone(),
+                    actual,
+                }
+                .into());
+            }
+        }
+    }
+    Ok(())
+}
+
 async fn remove_empty_dirs(path: &Path) -> anyhow::Result<()> {
     for entry in WalkDir::new(path)
         .contents_first(true)
@@ -194,7 +449,7 @@ pub async fn sync_mapping(
 
     let paths = get_files_in_dir(target_dir)?;
     for path in paths {
-        if !mappings_files.contains_key(&path) {
+        if !mappings_files.contains_key(&path) && !is_under_symlink(&path, target_dir) {
             fs::remove_file(&path).await?;
         }
     }
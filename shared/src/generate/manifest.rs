@@ -58,5 +58,6 @@ pub async fn get_version_info(
         inherits_from: metadata_info,
         extra_metadata_url,
         extra_metadata_sha1,
+        version_type: None,
     })
 }
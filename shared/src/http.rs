@@ -0,0 +1,201 @@
+use std::sync::RwLock;
+use std::time::Duration;
+
+use log::warn;
+use reqwest::header::{HeaderName, HeaderValue, LOCATION};
+use reqwest::redirect::Policy;
+use reqwest::{Client, ClientBuilder, Proxy, RequestBuilder, Response, StatusCode, Url};
+
+const MAX_REDIRECTS: usize = 10;
+
+#[derive(Clone)]
+pub struct ProxyConfig {
+    /// `http://`, `https://`, `socks5://` or `socks5h://` URL.
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl ProxyConfig {
+    pub fn to_proxy(&self) -> anyhow::Result<Proxy> {
+        let mut url = Url::parse(&self.url)?;
+        if let Some(username) = &self.username {
+            let _ = url.set_username(username);
+            let _ = url.set_password(self.password.as_deref());
+        }
+        Ok(Proxy::all(url)?)
+    }
+}
+
+#[derive(Clone)]
+pub struct ClientConfig {
+    pub connect_timeout: Duration,
+    /// Aborts a request if no data is received for this long.
+    pub read_timeout: Duration,
+    /// Limits the total time of a request, including reading the body.
+    /// Streamed downloads are exempt, they may be rate limited and only fail on `read_timeout`.
+    pub request_timeout: Option<Duration>,
+    pub proxy: Option<ProxyConfig>,
+    /// Sent only to `server_hosts`, e.g. an `Authorization` token for a private CDN.
+    pub server_headers: Vec<(String, String)>,
+    pub server_hosts: Vec<String>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            read_timeout: Duration::from_secs(30),
+            request_timeout: None,
+            proxy: None,
+            server_headers: Vec::new(),
+            server_hosts: Vec::new(),
+        }
+    }
+}
+
+static CLIENT_CONFIG: RwLock<Option<ClientConfig>> = RwLock::new(None);
+static CLIENT: RwLock<Option<Client>> = RwLock::new(None);
+
+pub fn set_client_config(config: ClientConfig) {
+    *CLIENT_CONFIG.write().unwrap() = Some(config);
+    *CLIENT.write().unwrap() = None;
+}
+
+/// Builder for a client without the total request timeout, for downloads streamed to disk.
+pub fn streaming_client_builder() -> anyhow::Result<ClientBuilder> {
+    let config = CLIENT_CONFIG.read().unwrap().clone().unwrap_or_default();
+    let mut builder = Client::builder()
+        .connect_timeout(config.connect_timeout)
+        .read_timeout(config.read_timeout)
+        .redirect(get_redirect_policy(&config));
+    if let Some(proxy) = &config.proxy {
+        builder = builder.proxy(proxy.to_proxy()?);
+    }
+    Ok(builder)
+}
+
+pub fn client_builder() -> anyhow::Result<ClientBuilder> {
+    let request_timeout = CLIENT_CONFIG
+        .read()
+        .unwrap()
+        .as_ref()
+        .and_then(|config| config.request_timeout);
+    let mut builder = streaming_client_builder()?;
+    if let Some(request_timeout) = request_timeout {
+        builder = builder.timeout(request_timeout);
+    }
+    Ok(builder)
+}
+
+pub fn client() -> Client {
+    if let Some(client) = CLIENT.read().unwrap().as_ref() {
+        return client.clone();
+    }
+
+    let client = client_builder()
+        .and_then(|builder| Ok(builder.build()?))
+        .unwrap_or_else(|e| {
+            warn!("Failed to build HTTP client, using defaults:\n{:?}", e);
+            Client::new()
+        });
+    *CLIENT.write().unwrap() = Some(client.clone());
+    client
+}
+
+fn is_server_host(server_hosts: &[String], url: &Url) -> bool {
+    url.host_str()
+        .map(|host| host.to_lowercase())
+        .is_some_and(|host| server_hosts.iter().any(|x| x == &host))
+}
+
+fn is_server_url(config: &ClientConfig, url: &str) -> bool {
+    Url::parse(url).is_ok_and(|url| is_server_host(&config.server_hosts, &url))
+}
+
+// reqwest only strips its own list of sensitive headers on a cross-host redirect and would forward
+// the server headers to any CDN, so a redirect leaving the server hosts is left for `send` to follow
+fn get_redirect_policy(config: &ClientConfig) -> Policy {
+    if config.server_headers.is_empty() {
+        return Policy::limited(MAX_REDIRECTS);
+    }
+    let server_hosts = config.server_hosts.clone();
+    Policy::custom(move |attempt| {
+        if attempt.previous().len() > MAX_REDIRECTS {
+            return attempt.error("too many redirects");
+        }
+        let leaves_servers = attempt
+            .previous()
+            .last()
+            .is_some_and(|url| is_server_host(&server_hosts, url))
+            && !is_server_host(&server_hosts, attempt.url());
+        if leaves_servers {
+            attempt.stop()
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
+/// Sends a request built with `with_server_headers`.
+/// A redirect off the server hosts is followed here with a fresh request that has none of the headers.
+pub async fn send(request: RequestBuilder) -> reqwest::Result<Response> {
+    let (client, request) = request.build_split();
+    let response = client.execute(request?).await?;
+    if !matches!(
+        response.status(),
+        StatusCode::MOVED_PERMANENTLY
+            | StatusCode::FOUND
+            | StatusCode::SEE_OTHER
+            | StatusCode::TEMPORARY_REDIRECT
+            | StatusCode::PERMANENT_REDIRECT
+    ) {
+        return Ok(response);
+    }
+    let Some(location) = response
+        .headers()
+        .get(LOCATION)
+        .and_then(|location| location.to_str().ok())
+        .and_then(|location| response.url().join(location).ok())
+    else {
+        return Ok(response);
+    };
+    client.get(location).send().await
+}
+
+/// Attaches the configured server headers if `url` points to one of the server hosts.
+pub fn with_server_headers(mut request: RequestBuilder, url: &str) -> RequestBuilder {
+    let Some(config) = CLIENT_CONFIG.read().unwrap().clone() else {
+        return request;
+    };
+    if !is_server_url(&config, url) {
+        return request;
+    }
+    for (name, value) in &config.server_headers {
+        match (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            (Ok(name), Ok(mut value)) => {
+                value.set_sensitive(true);
+                request = request.header(name, value);
+            }
+            _ => warn!("Ignoring invalid server header {}", name),
+        }
+    }
+    request
+}
+
+/// Replaces the values of the configured server headers in `text` before it is logged.
+pub fn redact(text: &str) -> String {
+    let Some(config) = CLIENT_CONFIG.read().unwrap().clone() else {
+        return text.to_string();
+    };
+    let mut text = text.to_string();
+    for (_, value) in &config.server_headers {
+        if !value.is_empty() {
+            text = text.replace(value, "<redacted>");
+        }
+    }
+    text
+}
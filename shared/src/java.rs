@@ -1,8 +1,9 @@
 use flate2::read::GzDecoder;
 use futures::StreamExt;
+use log::warn;
 use regex::Regex;
-use reqwest::{Client, Url};
-use serde::Deserialize;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -10,6 +11,9 @@ use std::sync::Arc;
 use tar::Archive;
 use tokio::process::Command;
 
+use crate::adaptive_download::RateLimiter;
+use crate::{endpoints, files, http};
+
 use serde_json::Value;
 #[cfg(target_os = "windows")]
 use winreg::enums::*;
@@ -22,6 +26,32 @@ use crate::progress::ProgressBar;
 pub struct JavaInstallation {
     pub version: String,
     pub path: PathBuf,
+    #[serde(default)]
+    pub is_64_bit: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum JavaPackageType {
+    #[default]
+    Jre,
+    Jdk,
+}
+
+/// A single build from the Azul package list.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct JavaPackage {
+    pub name: String,
+    pub download_url: String,
+}
+
+impl JavaPackageType {
+    fn as_str(self) -> &'static str {
+        match self {
+            JavaPackageType::Jre => "jre",
+            JavaPackageType::Jdk => "jdk",
+        }
+    }
 }
 
 lazy_static::lazy_static! {
@@ -34,27 +64,94 @@ const JAVA_BINARY_NAME: &str = "java.exe";
 #[cfg(not(target_os = "windows"))]
 const JAVA_BINARY_NAME: &str = "java";
 
-async fn get_installation(path: &Path) -> Option<JavaInstallation> {
-    let path = if path.is_file() {
-        path.to_path_buf()
-    } else {
-        which::which(path).ok()?
-    };
+#[cfg(target_os = "windows")]
+const JAVAC_BINARY_NAME: &str = "javac.exe";
 
-    let mut cmd = Command::new(&path);
+#[cfg(not(target_os = "windows"))]
+const JAVAC_BINARY_NAME: &str = "javac";
+
+// a JDK ships the compiler next to the java binary
+pub fn is_jdk(java_path: &Path) -> bool {
+    let java_path = fs::canonicalize(java_path).unwrap_or(java_path.to_path_buf());
+    java_path.with_file_name(JAVAC_BINARY_NAME).is_file()
+}
+
+// macOS archives keep the actual home inside a bundle, e.g. zulu-17.jdk/Contents/Home
+fn get_java_binary_path(java_home: &Path) -> PathBuf {
+    let java_path = java_home.join("bin").join(JAVA_BINARY_NAME);
+    if java_path.is_file() {
+        return java_path;
+    }
+    if let Ok(entries) = fs::read_dir(java_home) {
+        for entry in entries.filter_map(Result::ok) {
+            let bundle_java_path = entry
+                .path()
+                .join("Contents")
+                .join("Home")
+                .join("bin")
+                .join(JAVA_BINARY_NAME);
+            if bundle_java_path.is_file() {
+                return bundle_java_path;
+            }
+        }
+    }
+    java_path
+}
+
+#[cfg_attr(not(target_os = "windows"), allow(unused_mut))]
+fn java_command(path: &Path) -> Command {
+    let mut cmd = Command::new(path);
     #[cfg(target_os = "windows")]
     {
         use winapi::um::winbase::CREATE_NO_WINDOW;
 
         cmd.creation_flags(CREATE_NO_WINDOW);
     }
-    let output = cmd.arg("-version").output().await.ok()?;
+    cmd
+}
+
+async fn get_installation(path: &Path) -> Option<JavaInstallation> {
+    let path = if path.is_file() {
+        path.to_path_buf()
+    } else {
+        which::which(path).ok()?
+    };
+
+    let output = java_command(&path).arg("-version").output().await.ok()?;
 
     let version_result = String::from_utf8_lossy(&output.stderr);
     let captures = JAVA_VERSION_RGX.captures(&version_result)?;
 
     let version = captures.get(1)?.as_str().to_string();
-    Some(JavaInstallation { version, path })
+    Some(JavaInstallation {
+        version,
+        path,
+        is_64_bit: get_bitness(&version_result),
+    })
+}
+
+// 64-bit JVMs report "64-Bit Server VM", 32-bit ones just "Client VM" or "Server VM"
+fn get_bitness(java_version_output: &str) -> Option<bool> {
+    if java_version_output.contains("64-Bit") {
+        Some(true)
+    } else if java_version_output.contains(" VM") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+async fn is_32_bit_on_64_bit_host(java: &JavaInstallation) -> bool {
+    if !cfg!(target_pointer_width = "64") {
+        return false;
+    }
+    let is_64_bit = match java.is_64_bit {
+        Some(is_64_bit) => Some(is_64_bit),
+        None => get_installation(&java.path)
+            .await
+            .and_then(|installation| installation.is_64_bit),
+    };
+    is_64_bit == Some(false)
 }
 
 #[cfg(not(target_os = "windows"))]
@@ -81,6 +178,15 @@ async fn does_match(java: &JavaInstallation, required_version: &str) -> bool {
         return false;
     }
 
+    if is_32_bit_on_64_bit_host(java).await {
+        warn!(
+            "Skipping 32-bit Java {} at {}",
+            java.version,
+            java.path.display()
+        );
+        return false;
+    }
+
     if std::env::consts::ARCH != "aarch64" {
         return true;
     }
@@ -127,6 +233,7 @@ fn find_java_in_registry(
                     res.push(JavaInstallation {
                         version,
                         path: exe_path,
+                        is_64_bit: None,
                     });
                 }
             }
@@ -227,11 +334,11 @@ async fn find_java_installations() -> Vec<JavaInstallation> {
 }
 
 #[derive(thiserror::Error, Debug)]
-enum JavaDownloadError {
-    #[error("Unsupported architecture")]
-    UnsupportedArchitecture,
-    #[error("Unsupported operating system")]
-    UnsupportedOS,
+pub enum JavaDownloadError {
+    #[error("Unsupported architecture {0}")]
+    UnsupportedArchitecture(String),
+    #[error("Unsupported operating system {0}")]
+    UnsupportedOS(String),
     #[error("No Java versions available")]
     NoJavaVersionsAvailable,
     #[error("Invalid downloaded Java")]
@@ -244,25 +351,110 @@ enum JavaDownloadError {
     NoFileNameInURL,
     #[error("No file extension in URL")]
     NoFileExtensionInURL,
+    #[error("Unsupported archive type of {0}")]
+    UnsupportedArchiveType(String),
+}
+
+// modules the game and its libraries load at startup
+const REQUIRED_JAVA_MODULES: &[&str] = &[
+    "java.base",
+    "java.desktop",
+    "java.logging",
+    "java.management",
+];
+
+#[derive(thiserror::Error, Debug)]
+pub enum JavaSmokeTestError {
+    #[error("{0} exited with {1}:\n{2}")]
+    CommandFailed(PathBuf, std::process::ExitStatus, String),
+    #[error("{0} is missing modules: {1}")]
+    MissingModules(PathBuf, String),
+}
+
+/// Checks that the Java at `path` actually starts and has the modules the game needs,
+/// as an install can report the right version and still be broken.
+pub async fn smoke_test_java(path: &Path) -> anyhow::Result<()> {
+    let output = java_command(path).arg("-version").output().await?;
+    let version_output = String::from_utf8_lossy(&output.stderr).to_string();
+    if !output.status.success() {
+        return Err(JavaSmokeTestError::CommandFailed(
+            path.to_path_buf(),
+            output.status,
+            version_output,
+        )
+        .into());
+    }
+
+    // Java 8 has no modules to list
+    let is_legacy = JAVA_VERSION_RGX
+        .captures(&version_output)
+        .and_then(|captures| captures.get(1))
+        .is_some_and(|version| version.as_str().starts_with("1."));
+    if is_legacy {
+        return Ok(());
+    }
+
+    let output = java_command(path).arg("--list-modules").output().await?;
+    if !output.status.success() {
+        return Err(JavaSmokeTestError::CommandFailed(
+            path.to_path_buf(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        )
+        .into());
+    }
+    let modules = String::from_utf8_lossy(&output.stdout);
+    let missing: Vec<_> = REQUIRED_JAVA_MODULES
+        .iter()
+        .filter(|module| {
+            !modules
+                .lines()
+                .any(|line| line.split('@').next() == Some(**module))
+        })
+        .copied()
+        .collect();
+    if !missing.is_empty() {
+        return Err(
+            JavaSmokeTestError::MissingModules(path.to_path_buf(), missing.join(", ")).into(),
+        );
+    }
+    Ok(())
+}
+
+/// Whether no Java package exists for this platform, so it has to be installed manually.
+pub fn is_unavailable_for_platform(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<JavaDownloadError>(),
+        Some(
+            JavaDownloadError::UnsupportedArchitecture(_)
+                | JavaDownloadError::UnsupportedOS(_)
+                | JavaDownloadError::NoJavaVersionsAvailable
+        )
+    )
 }
 
-fn get_java_download_params(required_version: &str, archive_type: &str) -> anyhow::Result<String> {
+fn get_java_download_params(
+    required_version: &str,
+    archive_type: &str,
+    package_type: JavaPackageType,
+    latest: bool,
+) -> anyhow::Result<String> {
     let arch = match std::env::consts::ARCH {
         "x86_64" | "amd64" => "x64",
         "aarch64" => "aarch64",
-        _ => return Err(JavaDownloadError::UnsupportedArchitecture.into()),
+        arch => return Err(JavaDownloadError::UnsupportedArchitecture(arch.to_string()).into()),
     };
 
     let os = match std::env::consts::OS {
         "windows" => "windows",
         "linux" => "linux-glibc",
         "macos" => "macos",
-        _ => return Err(JavaDownloadError::UnsupportedOS.into()),
+        os => return Err(JavaDownloadError::UnsupportedOS(os.to_string()).into()),
     };
 
     let params = format!(
-        "java_version={}&os={}&arch={}&archive_type={}&java_package_type=jre&javafx_bundled=false&latest=true&release_status=ga",
-        required_version, os, arch, archive_type
+        "java_version={}&os={}&arch={}&archive_type={}&java_package_type={}&javafx_bundled=false&latest={}&release_status=ga",
+        required_version, os, arch, archive_type, package_type.as_str(), latest
     );
 
     Ok(params)
@@ -277,81 +469,194 @@ pub fn get_temp_dir() -> PathBuf {
     temp_dir
 }
 
-pub async fn download_java<M>(
-    required_version: &str,
-    java_dir: &Path,
-    progress_bar: Arc<dyn ProgressBar<M> + Send + Sync>,
-) -> anyhow::Result<JavaInstallation> {
-    let client = Client::new();
-
-    for archive_type in ["tar.gz", "zip"] {
-        let query_str = get_java_download_params(required_version, archive_type)?;
+const ARCHIVE_TYPES: &[&str] = &["tar.gz", "zip"];
 
-        let versions_url = format!(
-            "https://api.azul.com/metadata/v1/zulu/packages/?{}",
-            query_str
-        );
+pub async fn fetch_java_packages(
+    required_version: &str,
+    archive_type: &str,
+    package_type: JavaPackageType,
+) -> anyhow::Result<Vec<Value>> {
+    let query_str = get_java_download_params(required_version, archive_type, package_type, true)?;
+
+    let versions_url = format!("{}?{}", endpoints::get_java_metadata_url(), query_str);
+
+    let response = http::send(http::with_server_headers(
+        http::client().get(&versions_url),
+        &versions_url,
+    ))
+    .await?;
+    let body = response.text().await?;
+    let versions: Value = serde_json::from_str(&body)?;
+
+    Ok(versions
+        .as_array()
+        .ok_or(JavaDownloadError::NoVersionsArray)?
+        .clone())
+}
 
-        let response = client.get(&versions_url).send().await?;
+/// Lists every available build of the required Java, not only the latest one.
+pub async fn list_java_packages(
+    required_version: &str,
+    package_type: JavaPackageType,
+) -> anyhow::Result<Vec<JavaPackage>> {
+    let mut packages = Vec::new();
+    for archive_type in ARCHIVE_TYPES {
+        let query_str =
+            get_java_download_params(required_version, archive_type, package_type, false)?;
+        let packages_url = format!("{}?{}", endpoints::get_java_metadata_url(), query_str);
+
+        let response = http::send(http::with_server_headers(
+            http::client().get(&packages_url),
+            &packages_url,
+        ))
+        .await?;
         let body = response.text().await?;
-        let versions: Value = serde_json::from_str(&body)?;
+        let archive_packages: Vec<JavaPackage> = serde_json::from_str(&body)?;
+        packages.extend(archive_packages);
+    }
+    Ok(packages)
+}
 
-        if versions
-            .as_array()
-            .ok_or(JavaDownloadError::NoVersionsArray)?
-            .is_empty()
-        {
-            continue;
-        }
+// rough upper bound of the unpacked size relative to the compressed archive
+const JAVA_EXTRACTED_SIZE_FACTOR: u64 = 2;
 
-        let version_url = versions[0]["download_url"]
-            .as_str()
-            .ok_or(JavaDownloadError::NoDownloadURL)?;
-        let response = client.get(version_url).send().await?;
+fn get_extract_dir(java_dir: &Path, required_version: &str) -> PathBuf {
+    java_dir.join(format!("{}.extracting", required_version))
+}
 
-        let java_download_path = get_temp_dir().join(format!("java_download.{}", archive_type));
-        let mut file = fs::File::create(&java_download_path)?;
+// unpacking into a separate directory means an interrupted extraction never leaves a broken install in place
+fn extract_java_archive(
+    archive_path: &Path,
+    archive_type: &str,
+    extract_dir: &Path,
+) -> anyhow::Result<()> {
+    if extract_dir.exists() {
+        fs::remove_dir_all(extract_dir)?;
+    }
+    fs::create_dir_all(extract_dir)?;
 
-        let total_size = response.content_length().unwrap_or(0);
-        progress_bar.set_length(total_size);
+    let archive = fs::File::open(archive_path)?;
+    if archive_type == "tar.gz" {
+        let tar = GzDecoder::new(archive);
+        let mut archive = Archive::new(tar);
+        archive.unpack(extract_dir)?;
+    } else {
+        let mut archive = zip::ZipArchive::new(archive)?;
+        archive.extract(extract_dir)?;
+    }
+    Ok(())
+}
 
-        let mut stream = response.bytes_stream();
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
-            file.write_all(&chunk)?;
-            progress_bar.inc(chunk.len() as u64);
-        }
-        progress_bar.finish();
+fn replace_java_dir(extracted_dir: &Path, target_dir: &Path) -> anyhow::Result<()> {
+    if target_dir.exists() {
+        fs::remove_dir_all(target_dir)?;
+    }
+    fs::rename(extracted_dir, target_dir)?;
+    Ok(())
+}
 
-        let target_dir = java_dir.join(required_version);
-        if target_dir.exists() {
-            fs::remove_dir_all(&target_dir)?;
+async fn install_java_package<M>(
+    required_version: &str,
+    download_url: &str,
+    archive_type: &str,
+    java_dir: &Path,
+    rate_limiter: Option<&RateLimiter>,
+    progress_bar: Arc<dyn ProgressBar<M> + Send + Sync>,
+) -> anyhow::Result<Option<JavaInstallation>> {
+    let client = http::streaming_client_builder()?.build()?;
+    let response = client.get(download_url).send().await?;
+
+    let total_size = response.content_length().unwrap_or(0);
+    files::check_free_space(&get_temp_dir(), total_size)?;
+    files::check_free_space(java_dir, total_size * JAVA_EXTRACTED_SIZE_FACTOR)?;
+
+    let java_download_path = get_temp_dir().join(format!("java_download.{}", archive_type));
+    let mut file = fs::File::create(&java_download_path)?;
+
+    progress_bar.set_length(total_size);
+
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        file.write_all(&chunk)?;
+        progress_bar.inc(chunk.len() as u64);
+        if let Some(rate_limiter) = rate_limiter {
+            rate_limiter.consume(chunk.len()).await;
         }
+    }
+    progress_bar.finish();
+
+    let extract_dir = get_extract_dir(java_dir, required_version);
+    extract_java_archive(&java_download_path, archive_type, &extract_dir)?;
+
+    let url = Url::parse(download_url)?;
+    let filename = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .ok_or(JavaDownloadError::NoFileNameInURL)?
+        .strip_suffix(&format!(".{}", archive_type))
+        .ok_or(JavaDownloadError::NoFileExtensionInURL)?;
+    let extracted_dir = extract_dir.join(filename);
+
+    let valid = check_java(required_version, &get_java_binary_path(&extracted_dir)).await;
+    if !valid {
+        fs::remove_dir_all(&extract_dir)?;
+        return Err(JavaDownloadError::InvalidDownloadedJava.into());
+    }
 
-        let archive = fs::File::open(&java_download_path)?;
-        if archive_type == "tar.gz" {
-            let tar = GzDecoder::new(archive);
-            let mut archive = Archive::new(tar);
-            archive.unpack(java_dir)?;
-        } else {
-            let mut archive = zip::ZipArchive::new(archive)?;
-            archive.extract(java_dir)?;
-        }
+    let target_dir = java_dir.join(required_version);
+    replace_java_dir(&extracted_dir, &target_dir)?;
+    fs::remove_dir_all(&extract_dir)?;
+
+    Ok(get_installation(&get_java_binary_path(&target_dir)).await)
+}
+
+/// Downloads `package` if given, otherwise the latest build.
+pub async fn download_java<M>(
+    required_version: &str,
+    package_type: JavaPackageType,
+    package: Option<&JavaPackage>,
+    java_dir: &Path,
+    max_rate_kbps: Option<u64>,
+    progress_bar: Arc<dyn ProgressBar<M> + Send + Sync>,
+) -> anyhow::Result<JavaInstallation> {
+    let rate_limiter = RateLimiter::new(max_rate_kbps);
+    if let Some(package) = package {
+        let archive_type = ARCHIVE_TYPES
+            .iter()
+            .find(|archive_type| package.name.ends_with(&format!(".{}", archive_type)))
+            .ok_or_else(|| JavaDownloadError::UnsupportedArchiveType(package.name.clone()))?;
+        return install_java_package(
+            required_version,
+            &package.download_url,
+            archive_type,
+            java_dir,
+            rate_limiter.as_ref(),
+            progress_bar,
+        )
+        .await?
+        .ok_or(JavaDownloadError::InvalidDownloadedJava.into());
+    }
 
-        let url = Url::parse(version_url)?;
-        let filename = url
-            .path_segments()
-            .and_then(|segments| segments.last())
-            .ok_or(JavaDownloadError::NoFileNameInURL)?
-            .strip_suffix(&format!(".{}", archive_type))
-            .ok_or(JavaDownloadError::NoFileExtensionInURL)?;
-        fs::rename(java_dir.join(filename), &target_dir)?;
-
-        let java_path = target_dir.join("bin").join(JAVA_BINARY_NAME);
-        if !check_java(required_version, &java_path).await {
-            return Err(JavaDownloadError::InvalidDownloadedJava.into());
+    for archive_type in ARCHIVE_TYPES {
+        let versions = fetch_java_packages(required_version, archive_type, package_type).await?;
+        if versions.is_empty() {
+            continue;
         }
-        if let Some(installation) = get_installation(&java_path).await {
+
+        let version_url = versions[0]["download_url"]
+            .as_str()
+            .ok_or(JavaDownloadError::NoDownloadURL)?;
+        if let Some(installation) = install_java_package(
+            required_version,
+            version_url,
+            archive_type,
+            java_dir,
+            rate_limiter.as_ref(),
+            progress_bar.clone(),
+        )
+        .await?
+        {
             return Ok(installation);
         }
     }
@@ -359,7 +664,11 @@ pub async fn download_java<M>(
     Err(JavaDownloadError::NoJavaVersionsAvailable.into())
 }
 
-pub async fn get_java(required_version: &str, java_dir: &Path) -> Option<JavaInstallation> {
+pub async fn get_java(
+    required_version: &str,
+    package_type: JavaPackageType,
+    java_dir: &Path,
+) -> Option<JavaInstallation> {
     let mut installations = find_java_installations().await;
 
     if let Some(default_installation) = get_installation(Path::new(JAVA_BINARY_NAME)).await {
@@ -367,12 +676,14 @@ pub async fn get_java(required_version: &str, java_dir: &Path) -> Option<JavaIns
     }
 
     let java_dir = java_dir.join(required_version);
-    if let Some(installation) = get_installation(&java_dir.join("bin").join(JAVA_BINARY_NAME)).await
-    {
+    if let Some(installation) = get_installation(&get_java_binary_path(&java_dir)).await {
         installations.push(installation);
     }
 
     for installation in installations {
+        if package_type == JavaPackageType::Jdk && !is_jdk(&installation.path) {
+            continue;
+        }
         if does_match(&installation, required_version).await {
             return Some(installation);
         }
@@ -380,3 +691,64 @@ pub async fn get_java(required_version: &str, java_dir: &Path) -> Option<JavaIns
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+
+    #[test]
+    fn test_partial_extraction_replaced() {
+        let temp_dir = env::temp_dir().join("java_extraction_test");
+        if temp_dir.exists() {
+            fs::remove_dir_all(&temp_dir).unwrap();
+        }
+        let java_dir = temp_dir.join("java");
+        let target_dir = java_dir.join("21");
+        let extract_dir = get_extract_dir(&java_dir, "21");
+
+        // leftovers of an unpack interrupted by a crash
+        fs::create_dir_all(target_dir.join("lib")).unwrap();
+        fs::write(target_dir.join("lib").join("partial"), "partial").unwrap();
+        fs::create_dir_all(extract_dir.join("zulu21").join("lib")).unwrap();
+        fs::write(
+            extract_dir.join("zulu21").join("lib").join("partial"),
+            "partial",
+        )
+        .unwrap();
+
+        let archive_path = temp_dir.join("java.tar.gz");
+        let encoder = GzEncoder::new(
+            fs::File::create(&archive_path).unwrap(),
+            Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        let content = b"java";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "zulu21/bin/java", &content[..])
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        extract_java_archive(&archive_path, "tar.gz", &extract_dir).unwrap();
+        assert!(!extract_dir.join("zulu21").join("lib").exists());
+
+        replace_java_dir(&extract_dir.join("zulu21"), &target_dir).unwrap();
+        fs::remove_dir_all(&extract_dir).unwrap();
+
+        assert_eq!(
+            fs::read(target_dir.join("bin").join("java")).unwrap(),
+            content
+        );
+        assert!(!target_dir.join("lib").exists());
+        assert!(!extract_dir.exists());
+
+        fs::remove_dir_all(&temp_dir).unwrap();
+    }
+}
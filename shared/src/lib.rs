@@ -1,6 +1,9 @@
 pub mod adaptive_download;
+pub mod command;
+pub mod endpoints;
 pub mod files;
 pub mod generate;
+pub mod http;
 pub mod java;
 pub mod loader_generator;
 pub mod logs;
@@ -1,12 +1,12 @@
 use std::path::Path;
 
 use crate::{
+    http,
     paths::get_versions_dir,
     version::{version_manifest::VersionInfo, version_metadata::VersionMetadata},
 };
 use async_trait::async_trait;
 use log::info;
-use reqwest::Client;
 use serde::Deserialize;
 
 use super::generator::{GeneratorResult, VersionGenerator};
@@ -30,7 +30,7 @@ pub struct FabricVersionsMeta {
 impl FabricVersionsMeta {
     pub async fn fetch(game_version: &str) -> anyhow::Result<Self> {
         let fabric_manifest_url = format!("{}{}", FABRIC_META_BASE_URL, game_version);
-        let client = Client::new();
+        let client = http::client();
         let response = client
             .get(&fabric_manifest_url)
             .send()
@@ -93,6 +93,11 @@ impl FabricGenerator {
 pub enum FabricGeneratorError {
     #[error("No Fabric versions found for game version {0}")]
     NoVersionsFound(String),
+    #[error("Fabric loader version {loader_version} not found for game version {game_version}")]
+    LoaderVersionNotFound {
+        loader_version: String,
+        game_version: String,
+    },
 }
 
 #[async_trait]
@@ -112,10 +117,19 @@ impl VersionGenerator for FabricGenerator {
         )
         .await?;
 
+        let meta = FabricVersionsMeta::fetch(&minecraft_version).await?;
         let fabric_version = match &self.loader_version {
-            Some(loader_version) => loader_version.clone(),
+            Some(loader_version) => {
+                if !meta.get_versions().contains(&loader_version.as_str()) {
+                    return Err(FabricGeneratorError::LoaderVersionNotFound {
+                        loader_version: loader_version.clone(),
+                        game_version: minecraft_version,
+                    }
+                    .into());
+                }
+                loader_version.clone()
+            }
             None => {
-                let meta = FabricVersionsMeta::fetch(&minecraft_version).await?;
                 let version =
                     meta.get_latest_version()
                         .ok_or(FabricGeneratorError::NoVersionsFound(
@@ -7,15 +7,14 @@ use std::{
 };
 
 use crate::{
-    files,
-    java::{download_java, get_java},
+    files, http,
+    java::{download_java, get_java, JavaPackageType},
     paths::{get_java_dir, get_libraries_dir, get_metadata_path, get_versions_dir},
     progress::ProgressBar,
     version::{version_manifest::VersionInfo, version_metadata::VersionMetadata},
 };
 use async_trait::async_trait;
 use log::{debug, error, info, warn};
-use reqwest::Client;
 use serde::Deserialize;
 
 use super::generator::{GeneratorResult, VersionGenerator};
@@ -36,7 +35,7 @@ pub struct ForgeMavenMetadata {
 
 impl ForgeMavenMetadata {
     pub async fn fetch() -> anyhow::Result<Self> {
-        let client = Client::new();
+        let client = http::client();
         let response = client
             .get(FORGE_MAVEN_METADATA_URL)
             .send()
@@ -88,7 +87,7 @@ pub struct NeoforgeMavenMetadata {
 
 impl NeoforgeMavenMetadata {
     pub async fn fetch() -> anyhow::Result<Self> {
-        let client = Client::new();
+        let client = http::client();
         let response = client
             .get(NEOFORGE_MAVEN_METADATA_URL)
             .send()
@@ -149,7 +148,7 @@ pub struct ForgePromotions {
 
 impl ForgePromotions {
     pub async fn fetch() -> anyhow::Result<Self> {
-        let client = Client::new();
+        let client = http::client();
         let response = client
             .get(FORGE_PROMOTIONS_URL)
             .send()
@@ -189,7 +188,7 @@ async fn download_forge_installer(
         ),
     };
     let forge_installer_path = work_dir.join(filename);
-    let client = Client::new();
+    let client = http::client();
     files::download_file(&client, &forge_installer_url, &forge_installer_path).await?;
     Ok(forge_installer_path)
 }
@@ -394,20 +393,15 @@ async fn run_forge_command(
                 .arg(&to_abs_path_str(forge_installer_path)?);
             let retry_output = retry_cmd.output().await?;
             if !retry_output.status.success() {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    format!(
-                        "Command failed: {:?}",
-                        String::from_utf8_lossy(&output.stderr)
-                    ),
-                )
+                return Err(std::io::Error::other(format!(
+                    "Command failed: {:?}",
+                    String::from_utf8_lossy(&output.stderr)
+                ))
                 .into());
             }
         } else {
             error!("Command failed: {:?}", output);
-            return Err(
-                std::io::Error::new(std::io::ErrorKind::Other, stderr_str.to_string()).into(),
-            );
+            return Err(std::io::Error::other(stderr_str.to_string()).into());
         }
     }
 
@@ -431,22 +425,29 @@ pub async fn install_forge<M>(
     if !lock_file.exists() {
         let java_version = get_vanilla_java_version(vanilla_metadata)
             .await?
-            .map_or_else(
-                || {
-                    warn!("Java version not found, using default");
-                    "8".to_string()
-                },
-                |v| v,
-            );
+            .unwrap_or_else(|| {
+                warn!("Java version not found, using default");
+                "8".to_string()
+            });
 
         info!("Getting java {}", &java_version);
         let java_installation;
-        if let Some(existing_java_installation) = get_java(&java_version, java_dir).await {
+        if let Some(existing_java_installation) =
+            get_java(&java_version, JavaPackageType::Jre, java_dir).await
+        {
             java_installation = existing_java_installation;
         } else {
             info!("Java installation not found, downloading");
 
-            java_installation = download_java(&java_version, java_dir, progress_bar).await?;
+            java_installation = download_java(
+                &java_version,
+                JavaPackageType::Jre,
+                None,
+                java_dir,
+                None,
+                progress_bar,
+            )
+            .await?;
         }
 
         info!("Downloading forge installer");
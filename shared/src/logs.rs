@@ -1,10 +1,13 @@
-use env_logger::Builder;
+use env_logger::{Builder, Target};
 use log::LevelFilter;
-use std::fs::OpenOptions;
+use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
+const MAX_LOG_FILE_SIZE: u64 = 5 * 1024 * 1024;
+const MAX_LOG_FILES: usize = 10;
+
 pub fn setup_logger(logs_path: &Path) {
     let log_file = OpenOptions::new()
         .create(true)
@@ -26,3 +29,159 @@ pub fn setup_logger(logs_path: &Path) {
 
     builder.init();
 }
+
+/// Log file named `<name>_<date>[.<index>].log` that moves to a new file
+/// when the date changes or the current file grows too big.
+struct RotatingLogFile {
+    logs_dir: PathBuf,
+    name: String,
+    date: String,
+    index: u32,
+    file: File,
+    size: u64,
+}
+
+impl RotatingLogFile {
+    fn get_path(logs_dir: &Path, name: &str, date: &str, index: u32) -> PathBuf {
+        if index == 0 {
+            logs_dir.join(format!("{}_{}.log", name, date))
+        } else {
+            logs_dir.join(format!("{}_{}.{}.log", name, date, index))
+        }
+    }
+
+    fn open(logs_dir: &Path, name: &str) -> std::io::Result<Self> {
+        let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+
+        let mut index = 0;
+        let mut path = Self::get_path(logs_dir, name, &date, index);
+        while path.metadata().is_ok_and(|m| m.len() >= MAX_LOG_FILE_SIZE) {
+            index += 1;
+            path = Self::get_path(logs_dir, name, &date, index);
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+
+        let log_file = Self {
+            logs_dir: logs_dir.to_path_buf(),
+            name: name.to_string(),
+            date,
+            index,
+            file,
+            size,
+        };
+        log_file.remove_old_files();
+        Ok(log_file)
+    }
+
+    fn remove_old_files(&self) {
+        let prefix = format!("{}_", self.name);
+        let Ok(entries) = std::fs::read_dir(&self.logs_dir) else {
+            return;
+        };
+        let mut log_files: Vec<_> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                file_name.starts_with(&prefix) && file_name.ends_with(".log")
+            })
+            .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+            .collect();
+        if log_files.len() <= MAX_LOG_FILES {
+            return;
+        }
+
+        log_files.sort();
+        for (_, path) in &log_files[..log_files.len() - MAX_LOG_FILES] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    fn rotate_if_needed(&mut self) -> std::io::Result<()> {
+        let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+        if date == self.date && self.size < MAX_LOG_FILE_SIZE {
+            return Ok(());
+        }
+
+        if date != self.date {
+            self.date = date;
+            self.index = 0;
+        } else {
+            self.index += 1;
+        }
+        let path = Self::get_path(&self.logs_dir, &self.name, &self.date, self.index);
+        self.file = OpenOptions::new().create(true).append(true).open(path)?;
+        self.size = self.file.metadata()?.len();
+        self.remove_old_files();
+        Ok(())
+    }
+}
+
+impl Write for RotatingLogFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.rotate_if_needed()?;
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Writes every record to stderr and, if it could be opened, to the log file.
+struct LogTarget {
+    file: Option<RotatingLogFile>,
+}
+
+impl Write for LogTarget {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if let Some(file) = &mut self.file {
+            // a failing log file shouldn't stop logging to stderr
+            let _ = file.write_all(buf);
+        }
+        std::io::stderr().write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if let Some(file) = &mut self.file {
+            let _ = file.flush();
+        }
+        std::io::stderr().flush()
+    }
+}
+
+pub fn setup_rotating_logger(logs_dir: &Path, name: &str) {
+    let (log_file, open_error) = match RotatingLogFile::open(logs_dir, name) {
+        Ok(log_file) => (Some(log_file), None),
+        Err(e) => (None, Some(e)),
+    };
+
+    let mut builder = Builder::new();
+    builder.filter(None, LevelFilter::Info);
+    builder.parse_default_env();
+
+    builder.format(|buf, record| {
+        writeln!(
+            buf,
+            "{} {} [{}] - {}",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+            record.level(),
+            record.target(),
+            record.args()
+        )
+    });
+    builder.target(Target::Pipe(Box::new(LogTarget { file: log_file })));
+
+    builder.init();
+    if let Some(e) = open_error {
+        log::warn!(
+            "Failed to open a log file in {:?}, logging to stderr only: {}",
+            logs_dir,
+            e
+        );
+    }
+}
@@ -3,6 +3,44 @@ use std::{
     path::{Path, PathBuf},
 };
 
+#[derive(thiserror::Error, Debug)]
+pub enum UnsafePathError {
+    #[error("Unsafe path: {0:?}")]
+    UnsafePath(String),
+    #[error("Unsafe name: {0:?}")]
+    UnsafeName(String),
+}
+
+// paths come from remote metadata and are checked the same way on every OS,
+// so "..\\" and drive letters are rejected on unix too
+pub fn is_safe_rel_path(path: &str) -> bool {
+    !path.is_empty()
+        && !path.starts_with(['/', '\\'])
+        && !path.contains(':')
+        && path.split(['/', '\\']).all(|component| component != "..")
+        && Path::new(path).is_relative()
+}
+
+pub fn is_safe_name(name: &str) -> bool {
+    is_safe_rel_path(name) && !name.contains(['/', '\\']) && name != "."
+}
+
+pub fn check_safe_rel_path(path: &str) -> anyhow::Result<()> {
+    if is_safe_rel_path(path) {
+        Ok(())
+    } else {
+        Err(UnsafePathError::UnsafePath(path.to_string()).into())
+    }
+}
+
+pub fn check_safe_name(name: &str) -> anyhow::Result<()> {
+    if is_safe_name(name) {
+        Ok(())
+    } else {
+        Err(UnsafePathError::UnsafeName(name.to_string()).into())
+    }
+}
+
 fn created(dir: PathBuf) -> PathBuf {
     fs::create_dir_all(&dir).expect("Failed to create directory");
     dir
@@ -43,6 +81,18 @@ pub fn get_local_instances_path(data_dir: &Path) -> PathBuf {
     parent_created(data_dir.join("local_instances.json"))
 }
 
+pub fn get_manifest_cache_path(data_dir: &Path) -> PathBuf {
+    parent_created(data_dir.join("version_manifest.json"))
+}
+
+pub fn get_manifest_cache_validators_path(data_dir: &Path) -> PathBuf {
+    parent_created(data_dir.join("version_manifest_validators.json"))
+}
+
+pub fn get_hash_cache_path(data_dir: &Path) -> PathBuf {
+    parent_created(data_dir.join("hash_cache.json"))
+}
+
 pub fn get_auth_data_path(data_dir: &Path) -> PathBuf {
     parent_created(data_dir.join("auth_data.json"))
 }
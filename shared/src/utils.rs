@@ -9,17 +9,25 @@ pub const VANILLA_MANIFEST_URL: &str =
 pub enum VanillaGeneratorError {
     #[error("Vanilla version not found")]
     VersionNotFound,
+    #[error("{0} is a snapshot, but snapshots are not enabled")]
+    SnapshotNotAllowed(String),
 }
 
 pub fn get_vanilla_version_info(
     version_manifest: &VersionManifest,
     minecraft_version: &str,
+    include_snapshots: bool,
 ) -> anyhow::Result<VersionInfo> {
     let version_info = version_manifest
         .versions
         .iter()
         .find(|v| v.id == minecraft_version)
         .ok_or(VanillaGeneratorError::VersionNotFound)?;
+    if version_info.is_snapshot() && !include_snapshots {
+        return Err(
+            VanillaGeneratorError::SnapshotNotAllowed(minecraft_version.to_string()).into(),
+        );
+    }
     Ok(version_info.clone())
 }
 
@@ -31,6 +39,16 @@ pub fn url_from_rel_path(rel_path: &Path, download_server_base: &str) -> anyhow:
     ))
 }
 
+pub fn urls_from_rel_path(
+    rel_path: &Path,
+    download_server_bases: &[String],
+) -> anyhow::Result<Vec<String>> {
+    download_server_bases
+        .iter()
+        .map(|base| url_from_rel_path(rel_path, base))
+        .collect()
+}
+
 pub fn url_from_path(
     path: &Path,
     base_dir: &Path,
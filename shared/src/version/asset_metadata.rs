@@ -4,18 +4,26 @@ use std::{
 };
 
 use crate::{
-    adaptive_download::download_files,
+    adaptive_download::{download_files_with_options, DownloadOptions},
     files::{self, CheckEntry},
-    paths::get_asset_index_path,
+    http,
+    paths::{check_safe_name, get_asset_index_path},
     progress,
     version::version_metadata::AssetIndex,
 };
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
 pub struct ObjectData {
     pub hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AssetsMetadataError {
+    #[error("Invalid asset hash: {0:?}")]
+    InvalidHash(String),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -25,8 +33,11 @@ pub struct AssetsMetadata {
 
 impl AssetsMetadata {
     pub async fn fetch(url: &str) -> anyhow::Result<Self> {
-        let client = Client::new();
-        let response = client.get(url).send().await?.json().await?;
+        let client = http::client();
+        let response = http::send(http::with_server_headers(client.get(url), url))
+            .await?
+            .json()
+            .await?;
         Ok(response)
     }
 
@@ -46,17 +57,26 @@ impl AssetsMetadata {
     pub async fn read_or_download(
         asset_index: &AssetIndex,
         assets_dir: &Path,
+        download_options: &DownloadOptions,
     ) -> anyhow::Result<Self> {
+        check_safe_name(&asset_index.id)?;
         let asset_index_path = get_asset_index_path(assets_dir, &asset_index.id);
         let check_entry = CheckEntry {
             url: asset_index.url.clone(),
             remote_sha1: Some(asset_index.sha1.clone()),
             path: asset_index_path.clone(),
+            size: None,
         };
         let check_entries = vec![check_entry];
         let download_entries =
-            files::get_download_entries(check_entries, progress::no_progress_bar()).await?;
-        download_files(download_entries, progress::no_progress_bar()).await?;
+            files::get_download_entries(check_entries.clone(), progress::no_progress_bar()).await?;
+        download_files_with_options(
+            download_entries,
+            progress::no_progress_bar(),
+            download_options,
+        )
+        .await?;
+        files::verify_check_entries(&check_entries).await?;
         Self::read_local(&asset_index.id, assets_dir).await
     }
 
@@ -67,6 +87,12 @@ impl AssetsMetadata {
     ) -> anyhow::Result<Vec<CheckEntry>> {
         let mut download_entries = vec![];
 
+        if let Some(object) = self.objects.values().find(|object| {
+            object.hash.len() < 2 || !object.hash.chars().all(|c| c.is_ascii_hexdigit())
+        }) {
+            return Err(AssetsMetadataError::InvalidHash(object.hash.clone()).into());
+        }
+
         download_entries.extend(self.objects.values().map(|object| {
             CheckEntry {
                 url: format!(
@@ -80,6 +106,7 @@ impl AssetsMetadata {
                     .join(&object.hash[..2])
                     .join(&object.hash),
                 remote_sha1: None, // do not check sha1 for assets since it's in the path
+                size: object.size,
             }
         }));
 
@@ -3,7 +3,10 @@ use std::path::Path;
 use log::warn;
 use serde::{Deserialize, Serialize};
 
-use crate::{files::CheckEntry, paths::get_extra_metadata_path};
+use crate::{
+    files::CheckEntry,
+    paths::{check_safe_rel_path, get_extra_metadata_path},
+};
 
 use super::{version_manifest::VersionInfo, version_metadata::Library};
 
@@ -87,6 +90,9 @@ pub struct ExtraVersionMetadata {
 
     #[serde(default)]
     pub extra_forge_libs: Vec<Library>,
+
+    #[serde(default)]
+    pub recommended_xmx: Option<String>,
 }
 
 impl ExtraVersionMetadata {
@@ -102,7 +108,22 @@ impl ExtraVersionMetadata {
             get_extra_metadata_path(versions_extra_dir, &version_info.get_name());
         let extra_version_metadata_file = tokio::fs::read(extra_version_metadata_path).await?;
 
-        Ok(Some(serde_json::from_slice(&extra_version_metadata_file)?))
+        let extra_version_metadata: Self = serde_json::from_slice(&extra_version_metadata_file)?;
+        extra_version_metadata.check_paths()?;
+        Ok(Some(extra_version_metadata))
+    }
+
+    // every path here is joined to the instance directory, and include entries also decide what gets deleted
+    fn check_paths(&self) -> anyhow::Result<()> {
+        for path in self
+            .include
+            .iter()
+            .chain(self.include_no_overwrite.iter())
+            .chain(self.objects.iter().map(|object| &object.path))
+        {
+            check_safe_rel_path(path)?;
+        }
+        Ok(())
     }
 
     pub fn get_check_entry(
@@ -120,6 +141,7 @@ impl ExtraVersionMetadata {
             url: url.clone(),
             remote_sha1: Some(sha1.clone()),
             path: get_extra_metadata_path(versions_extra_dir, &version_info.get_name()),
+            size: None,
         })
     }
 
@@ -1,7 +1,14 @@
 use std::path::Path;
 
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use log::warn;
+use reqwest::{
+    header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    StatusCode,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use crate::http;
 
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
 pub struct MetadataInfo {
@@ -23,6 +30,10 @@ pub struct VersionInfo {
 
     pub extra_metadata_url: Option<String>,
     pub extra_metadata_sha1: Option<String>,
+
+    /// Release channel from the vanilla manifest, e.g. "release" or "snapshot".
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub version_type: Option<String>,
 }
 
 impl VersionInfo {
@@ -33,6 +44,23 @@ impl VersionInfo {
         }
     }
 
+    pub fn is_snapshot(&self) -> bool {
+        self.version_type.as_deref() == Some("snapshot")
+    }
+
+    pub fn get_minecraft_version(&self) -> &str {
+        match self.inherits_from.first() {
+            Some(parent_info) => &parent_info.id,
+            None => &self.id,
+        }
+    }
+
+    // short identifier of the exact build, changes whenever the metadata or the extra files change
+    pub fn get_build_id(&self) -> &str {
+        let sha1 = self.extra_metadata_sha1.as_ref().unwrap_or(&self.sha1);
+        &sha1[..sha1.len().min(8)]
+    }
+
     pub fn get_parent_metadata_info(&self) -> MetadataInfo {
         match self.inherits_from.first() {
             Some(parent_info) => parent_info.clone(),
@@ -58,8 +86,56 @@ impl VersionInfo {
     }
 }
 
+#[derive(Serialize, Deserialize)]
+struct CacheValidators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    async fn read(path: &Path) -> Option<Self> {
+        let data = tokio::fs::read(path).await.ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+
+    async fn save(
+        &self,
+        path: &Path,
+        manifest_path: &Path,
+        manifest_bytes: &[u8],
+    ) -> anyhow::Result<()> {
+        tokio::fs::write(manifest_path, manifest_bytes).await?;
+        tokio::fs::write(path, serde_json::to_vec(self)?).await?;
+        Ok(())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+// one malformed entry shouldn't hide all the other versions
+fn deserialize_versions<'de, D>(deserializer: D) -> Result<Vec<VersionInfo>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let values = Vec::<Value>::deserialize(deserializer)?;
+    Ok(values
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, value)| match serde_json::from_value(value) {
+            Ok(version_info) => Some(version_info),
+            Err(e) => {
+                warn!("Skipping malformed version manifest entry {}: {}", i, e);
+                None
+            }
+        })
+        .collect())
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct VersionManifest {
+    #[serde(deserialize_with = "deserialize_versions")]
     pub versions: Vec<VersionInfo>,
 }
 
@@ -71,10 +147,8 @@ impl VersionManifest {
     }
 
     pub async fn fetch(url: &str) -> anyhow::Result<Self> {
-        let client = Client::new();
-        let res = client
-            .get(url)
-            .send()
+        let client = http::client();
+        let res = http::send(http::with_server_headers(client.get(url), url))
             .await?
             .error_for_status()?
             .json::<Self>()
@@ -82,6 +156,67 @@ impl VersionManifest {
         Ok(res)
     }
 
+    /// Fetches the manifest, reusing the copy at `manifest_path` if the server reports it as not modified.
+    pub async fn fetch_cached(
+        url: &str,
+        manifest_path: &Path,
+        validators_path: &Path,
+    ) -> anyhow::Result<Self> {
+        let validators = if manifest_path.exists() {
+            CacheValidators::read(validators_path).await
+        } else {
+            None
+        };
+
+        let client = http::client();
+        let mut request = http::with_server_headers(client.get(url), url);
+        if let Some(validators) = &validators {
+            if let Some(etag) = &validators.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &validators.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        let response = http::send(request).await?.error_for_status()?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            match Self::read_local(manifest_path).await {
+                Ok(manifest) => return Ok(manifest),
+                Err(e) => {
+                    warn!("Failed to read cached manifest, refetching:\n{:?}", e);
+                    let _ = tokio::fs::remove_file(validators_path).await;
+                    return Self::fetch(url).await;
+                }
+            }
+        }
+
+        let header_value = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+        let validators = CacheValidators {
+            etag: header_value(ETAG),
+            last_modified: header_value(LAST_MODIFIED),
+        };
+        let manifest_bytes = response.bytes().await?;
+        let manifest: Self = serde_json::from_slice(&manifest_bytes)?;
+
+        if !validators.is_empty() {
+            if let Err(e) = validators
+                .save(validators_path, manifest_path, &manifest_bytes)
+                .await
+            {
+                warn!("Failed to cache version manifest:\n{:?}", e);
+            }
+        }
+
+        Ok(manifest)
+    }
+
     pub async fn read_local(manifest_path: &Path) -> anyhow::Result<Self> {
         let manifest_file = tokio::fs::read(manifest_path).await?;
         let manifest: Self = serde_json::from_slice(&manifest_file)?;
@@ -9,6 +9,7 @@ use tokio::{fs, io::AsyncReadExt as _};
 use crate::{
     adaptive_download::download_files,
     files::{self, CheckEntry},
+    http,
     paths::get_metadata_path,
     progress,
 };
@@ -24,6 +25,14 @@ fn get_arch_os_name(os_name: &str, arch: &str) -> String {
         }
 }
 
+// older natives classifiers contain an "${arch}" placeholder, e.g. "natives-windows-${arch}"
+fn get_arch_bits(arch: &str) -> &'static str {
+    match arch {
+        "x86" | "arm32" => "32",
+        _ => "64",
+    }
+}
+
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Os {
     name: Option<String>,
@@ -58,9 +67,8 @@ pub struct Rule {
 }
 
 impl Rule {
-    fn allowed_on_os(&self, os_name: &str, arch: &str) -> Option<bool> {
+    fn allowed_on_os(&self, os_name: &str, arch: &str, enabled_features: &[&str]) -> Option<bool> {
         let is_allowed = self.action == "allow";
-        let matching_features = ["has_custom_resolution"];
 
         if let Some(os) = &self.os {
             if !os.matches_os(os_name, arch) {
@@ -70,7 +78,7 @@ impl Rule {
 
         if let Some(features) = &self.features {
             for (feature, value) in features {
-                let contains = matching_features.contains(&feature.as_str());
+                let contains = enabled_features.contains(&feature.as_str());
                 if contains != *value {
                     return None;
                 }
@@ -81,10 +89,10 @@ impl Rule {
     }
 }
 
-fn rules_apply(rules: &[Rule], os_name: &str, arch: &str) -> bool {
+fn rules_apply(rules: &[Rule], os_name: &str, arch: &str, enabled_features: &[&str]) -> bool {
     let mut some_allowed = false;
     for rule in rules {
-        if let Some(is_allowed) = rule.allowed_on_os(os_name, arch) {
+        if let Some(is_allowed) = rule.allowed_on_os(os_name, arch, enabled_features) {
             if !is_allowed {
                 return false;
             }
@@ -131,11 +139,16 @@ impl VariableArgument {
         }
     }
 
-    pub fn get_matching_values(&self, os_name: &str, arch: &str) -> Vec<&str> {
+    pub fn get_matching_values(
+        &self,
+        os_name: &str,
+        arch: &str,
+        enabled_features: &[&str],
+    ) -> Vec<&str> {
         match self {
             VariableArgument::Simple(s) => vec![s.as_str()],
             VariableArgument::Complex(complex) => {
-                if rules_apply(&complex.rules, os_name, arch) {
+                if rules_apply(&complex.rules, os_name, arch, enabled_features) {
                     complex.value.get_values()
                 } else {
                     vec![]
@@ -156,6 +169,8 @@ pub struct AssetIndex {
     pub id: String,
     pub sha1: String,
     pub url: String,
+    #[serde(rename = "totalSize", default, skip_serializing_if = "Option::is_none")]
+    pub total_size: Option<u64>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -168,6 +183,8 @@ pub struct JavaVersion {
 pub struct Download {
     pub sha1: String,
     pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
 }
 
 impl Download {
@@ -176,11 +193,12 @@ impl Download {
             url: self.url.clone(),
             remote_sha1: Some(self.sha1.clone()),
             path: path.to_path_buf(),
+            size: self.size,
         }
     }
 
     pub fn get_filename(&self) -> &str {
-        self.url.split('/').last().unwrap_or(&self.url)
+        self.url.split('/').next_back().unwrap_or(&self.url)
     }
 }
 
@@ -190,6 +208,18 @@ pub struct LibraryDownloads {
     pub classifiers: Option<HashMap<String, Download>>,
 }
 
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ExtractRules {
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl ExtractRules {
+    pub fn is_excluded(&self, path: &str) -> bool {
+        self.exclude.iter().any(|prefix| path.starts_with(prefix))
+    }
+}
+
 const MOJANG_LIBRARIES_URL: &str = "https://libraries.minecraft.net/";
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -200,6 +230,7 @@ pub struct Library {
     pub url: Option<String>,
     pub sha1: Option<String>,
     pub natives: Option<HashMap<String, String>>,
+    pub extract: Option<ExtractRules>,
 }
 
 impl Library {
@@ -207,13 +238,18 @@ impl Library {
         Library {
             name,
             downloads: Some(LibraryDownloads {
-                artifact: Some(Download { url, sha1 }),
+                artifact: Some(Download {
+                    url,
+                    sha1,
+                    size: None,
+                }),
                 classifiers: None,
             }),
             rules: None,
             url: None,
             sha1: None,
             natives: None,
+            extract: None,
         }
     }
 
@@ -259,8 +295,12 @@ impl Library {
         path.parent().unwrap_or(libraries_dir).to_path_buf()
     }
 
-    fn get_native_name(&self, os_arch: &str) -> Option<&str> {
-        self.natives.as_ref()?.get(os_arch).map(|x| x.as_str())
+    fn get_native_name(&self, os_name: &str, arch: &str) -> Option<String> {
+        let native_name = self
+            .natives
+            .as_ref()?
+            .get(&get_arch_os_name(os_name, arch))?;
+        Some(native_name.replace("${arch}", get_arch_bits(arch)))
     }
 
     pub fn get_native_download(&self, natives_name: &str) -> Option<&Download> {
@@ -287,9 +327,9 @@ impl Library {
         os_name: &str,
         arch: &str,
     ) -> Option<PathBuf> {
-        if let Some(native_name) = self.get_native_name(&get_arch_os_name(os_name, arch)) {
-            if let Some(download) = self.get_native_download(native_name) {
-                return Some(self.get_native_path(libraries_dir, native_name, download));
+        if let Some(native_name) = self.get_native_name(os_name, arch) {
+            if let Some(download) = self.get_native_download(&native_name) {
+                return Some(self.get_native_path(libraries_dir, &native_name, download));
             }
         }
         None
@@ -308,6 +348,7 @@ impl Library {
                 url: format!("{}/{}", self.get_url(), self.get_path_from_name()),
                 remote_sha1: self.sha1.clone(),
                 path: libraries_dir.join(self.get_path_from_name()),
+                size: None,
             })
         }
     }
@@ -323,14 +364,20 @@ impl Library {
             entries.push(entry);
         }
         if let Some((os_name, arch)) = os_with_arch {
-            if let Some(native_name) = self.get_native_name(&get_arch_os_name(os_name, arch)) {
-                if let Some(download) = self.get_native_download(native_name) {
-                    let path = self.get_native_path(libraries_dir, native_name, download);
+            if let Some(native_name) = self.get_native_name(os_name, arch) {
+                if let Some(download) = self.get_native_download(&native_name) {
+                    let path = self.get_native_path(libraries_dir, &native_name, download);
                     entries.push(download.get_check_entry(&path));
                 }
             }
         } else if let Some(natives) = &self.natives {
-            for native_name in natives.keys() {
+            let mut native_names: Vec<String> = natives
+                .values()
+                .flat_map(|name| [name.replace("${arch}", "32"), name.replace("${arch}", "64")])
+                .collect();
+            native_names.sort();
+            native_names.dedup();
+            for native_name in &native_names {
                 if let Some(download) = self.get_native_download(native_name) {
                     let path = self.get_native_path(libraries_dir, native_name, download);
                     entries.push(download.get_check_entry(&path));
@@ -343,7 +390,7 @@ impl Library {
 
     pub fn applies_to_os(&self, os_name: &str, arch: &str) -> bool {
         if let Some(rules) = &self.rules {
-            rules_apply(rules, os_name, arch)
+            rules_apply(rules, os_name, arch, &[])
         } else {
             true
         }
@@ -445,8 +492,10 @@ impl VersionMetadata {
     }
 
     pub async fn fetch(url: &str) -> anyhow::Result<Self> {
-        let client = reqwest::Client::new();
-        let response = client.get(url).send().await?.error_for_status()?;
+        let client = http::client();
+        let response = http::send(http::with_server_headers(client.get(url), url))
+            .await?
+            .error_for_status()?;
         let metadata = response.json().await?;
         Ok(metadata)
     }
@@ -459,6 +508,7 @@ impl VersionMetadata {
             url,
             remote_sha1: Some(sha1),
             path,
+            size: None,
         }
     }
 
@@ -469,8 +519,9 @@ impl VersionMetadata {
         let check_entry = Self::get_check_entry(metadata_info, versions_dir);
         let check_entries = vec![check_entry];
         let download_entries =
-            files::get_download_entries(check_entries, progress::no_progress_bar()).await?;
+            files::get_download_entries(check_entries.clone(), progress::no_progress_bar()).await?;
         download_files(download_entries, progress::no_progress_bar()).await?;
+        files::verify_check_entries(&check_entries).await?;
         Self::read_local(versions_dir, &metadata_info.id).await
     }
 